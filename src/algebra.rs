@@ -1,25 +1,218 @@
-use std::vec::Vec;
+// without `std`, only the helpers of the array optimizer and bounds are used
+#![cfg_attr(not(feature = "std"), allow(dead_code))]
 
-pub fn sum(p1: &Vec<f64>, p2: &Vec<f64>) -> Vec<f64> {
-    p1.iter().zip(p2.iter()).map(|(x, y)| x + y).collect()
+extern crate num_traits;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+#[cfg(feature = "std")]
+use core::fmt;
+#[cfg(feature = "std")]
+use core::iter::Sum;
+use num_traits::float::FloatCore;
+#[cfg(feature = "std")]
+use num_traits::NumAssignOps;
+#[cfg(feature = "std")]
+use rand::distributions::uniform::SampleUniform;
+
+/// The floating point types that points and values can take, `f64` or
+/// `f32`.
+///
+/// Everything defaults to `f64`, so only code that wants `f32` has to name
+/// the type, as in `minimize::<f32>`.
+#[cfg(feature = "std")]
+pub trait Float:
+    FloatCore + NumAssignOps + Sum + SampleUniform + fmt::Debug + fmt::Display + 'static
+{
+    /// Converts `x` to the nearest value of this type.
+    fn from_f64(x: f64) -> Self;
+    /// Converts the value to an `f64`, which is exact.
+    fn as_f64(self) -> f64;
+    fn sqrt(self) -> Self;
+    fn ln(self) -> Self;
+    fn ln_1p(self) -> Self;
+    fn tanh(self) -> Self;
+    fn atanh(self) -> Self;
+}
+
+#[cfg(feature = "std")]
+macro_rules! impl_float {
+    ($t:ty) => {
+        impl Float for $t {
+            fn from_f64(x: f64) -> $t {
+                x as $t
+            }
+            fn as_f64(self) -> f64 {
+                self as f64
+            }
+            fn sqrt(self) -> $t {
+                <$t>::sqrt(self)
+            }
+            fn ln(self) -> $t {
+                <$t>::ln(self)
+            }
+            fn ln_1p(self) -> $t {
+                <$t>::ln_1p(self)
+            }
+            fn tanh(self) -> $t {
+                <$t>::tanh(self)
+            }
+            fn atanh(self) -> $t {
+                <$t>::atanh(self)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "std")]
+impl_float!(f32);
+#[cfg(feature = "std")]
+impl_float!(f64);
+
+pub fn sum<T: FloatCore>(p1: &[T], p2: &[T]) -> Vec<T> {
+    p1.iter().zip(p2.iter()).map(|(x, y)| *x + *y).collect()
+}
+pub fn diff<T: FloatCore>(p1: &[T], p2: &[T]) -> Vec<T> {
+    p1.iter().zip(p2.iter()).map(|(x, y)| *x - *y).collect()
 }
-pub fn diff(p1: &Vec<f64>, p2: &Vec<f64>) -> Vec<f64> {
-    p1.iter().zip(p2.iter()).map(|(x, y)| x - y).collect()
+pub fn mult<T: FloatCore>(k: T, p: &[T]) -> Vec<T> {
+    p.iter().map(|x| k * *x).collect()
+}
+/// Averages `ps`, scaling every point before adding it so that the sum
+/// doesn't overflow for points near the largest finite values.
+pub fn avg<T: FloatCore>(ps: &[Vec<T>]) -> Vec<T> {
+    let k = T::one() / T::from(ps.len()).unwrap();
+    let head = mult(k, &ps[0]);
+    ps.iter().skip(1).fold(head, |x, y| sum(&x, &mult(k, y)))
+}
+#[cfg(feature = "std")]
+pub fn distance<T: Float>(p1: &[T], p2: &[T]) -> T {
+    p1.iter()
+        .zip(p2.iter())
+        .map(|(x, y)| (*x - *y) * (*x - *y))
+        .sum::<T>()
+        .sqrt()
+}
+/// Clamps every coordinate of `p` to its bounds.
+///
+/// Infinite bounds don't clamp finite coordinates, but a coordinate that
+/// overflowed to an infinity is brought back to the largest finite value,
+/// so one overflowing step doesn't poison the simplex. `NaN` coordinates
+/// are left as they are.
+pub fn clamp<T: FloatCore>(p: &[T], bounds: &[(T, T)]) -> Vec<T> {
+    p.iter()
+        .zip(bounds.iter())
+        .map(|(p, bounds)| clamp_coordinate(*p, *bounds))
+        .collect()
 }
-pub fn mult(k: f64, p: &Vec<f64>) -> Vec<f64> {
-    p.iter().map(|x| k * x).collect()
+
+/// Clamps a single coordinate, as done by `clamp`.
+pub fn clamp_coordinate<T: FloatCore>(x: T, (min, max): (T, T)) -> T {
+    if x < min.max(T::min_value()) {
+        min.max(T::min_value())
+    } else if x > max.min(T::max_value()) {
+        max.min(T::max_value())
+    } else {
+        x
+    }
 }
-pub fn avg(ps: &[Vec<f64>]) -> Vec<f64> {
-    let head = ps[0].clone();
-    mult(
-        1.0 / ps.len() as f64,
-        &ps.iter().skip(1).fold(head, |x, y| sum(&x, &y)),
-    )
+
+/// Orders function values from best to worst, with `NaN` after every
+/// other value, including `+inf`.
+pub fn compare_values<T: FloatCore>(a: T, b: T) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => a.partial_cmp(&b).unwrap(),
+    }
 }
-pub fn clamp(p: &Vec<f64>, bounds: &Vec<(f64, f64)>) -> Vec<f64> {
+
+/// Mirrors every coordinate of `p` that overshoots its bounds back inside,
+/// clamping it if it overshoots by more than the width of the bounds.
+pub fn reflect_into<T: FloatCore>(p: &[T], bounds: &[(T, T)]) -> Vec<T> {
     p.iter()
         .zip(bounds.iter())
-        .map(|(p, (min, max))| p.max(min.clone()).min(max.clone()))
+        .map(|(p, (min, max))| {
+            let (p, min, max) = (*p, *min, *max);
+            let reflected = if p < min {
+                min + (min - p)
+            } else if p > max {
+                max - (p - max)
+            } else {
+                p
+            };
+            clamp_coordinate(reflected, (min, max))
+        })
+        .collect()
+}
+
+/// Moves `p` towards `origin`, which must be within the bounds, until it
+/// is within the bounds as well.
+pub fn truncate_into<T: FloatCore>(origin: &[T], p: &[T], bounds: &[(T, T)]) -> Vec<T> {
+    let t = p
+        .iter()
+        .zip(origin.iter())
+        .zip(bounds.iter())
+        .fold(T::one(), |t, ((p, o), (min, max))| {
+            let (p, o, min, max) = (*p, *o, *min, *max);
+            if p > max {
+                t.min((max - o) / (p - o))
+            } else if p < min {
+                t.min((min - o) / (p - o))
+            } else {
+                t
+            }
+        })
+        .max(T::zero());
+    clamp(&sum(origin, &mult(t, &diff(p, origin))), bounds)
+}
+
+/// Computes the eigenvalues and the (unit) eigenvectors of a symmetric
+/// matrix, using cyclic Jacobi rotations.
+#[cfg(feature = "std")]
+pub fn symmetric_eigen<T: Float>(matrix: &[Vec<T>]) -> Vec<(T, Vec<T>)> {
+    let n = matrix.len();
+    let (zero, one, two) = (T::zero(), T::one(), T::from_f64(2.0));
+    let mut a = matrix.to_vec();
+    let mut v: Vec<Vec<T>> = (0..n)
+        .map(|i| (0..n).map(|j| if i == j { one } else { zero }).collect())
+        .collect();
+    for _ in 0..100 {
+        let off_diagonal: T = (0..n)
+            .flat_map(|i| (0..n).filter(move |&j| j != i).map(move |j| (i, j)))
+            .map(|(i, j)| a[i][j] * a[i][j])
+            .sum();
+        if off_diagonal == zero {
+            break;
+        }
+        for p in 0..n {
+            for q in p + 1..n {
+                if a[p][q] == zero {
+                    continue;
+                }
+                let theta = (a[q][q] - a[p][p]) / (two * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + one).sqrt());
+                let t = if theta == zero { one } else { t };
+                let c = one / (t * t + one).sqrt();
+                let s = t * c;
+                for row in a.iter_mut() {
+                    let (akp, akq) = (row[p], row[q]);
+                    row[p] = c * akp - s * akq;
+                    row[q] = s * akp + c * akq;
+                }
+                let (row_p, row_q) = (a[p].clone(), a[q].clone());
+                a[p] = diff(&mult(c, &row_p), &mult(s, &row_q));
+                a[q] = sum(&mult(s, &row_p), &mult(c, &row_q));
+                for row in v.iter_mut() {
+                    let (vkp, vkq) = (row[p], row[q]);
+                    row[p] = c * vkp - s * vkq;
+                    row[q] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+    (0..n)
+        .map(|j| (a[j][j], v.iter().map(|row| row[j]).collect()))
         .collect()
 }
 
@@ -27,10 +220,54 @@ pub fn clamp(p: &Vec<f64>, bounds: &Vec<(f64, f64)>) -> Vec<f64> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_compare_values() {
+        let mut values = [f64::NAN, 1.0, f64::INFINITY, -f64::NAN, f64::NEG_INFINITY];
+        values.sort_by(|a, b| compare_values(*a, *b));
+        assert_eq!(&values[..3], &[f64::NEG_INFINITY, 1.0, f64::INFINITY]);
+        assert!(values[3].is_nan() && values[4].is_nan());
+    }
+
+    #[test]
+    fn test_clamp_to_infinite_bounds() {
+        let bounds = [(0.0, 1.0), (f64::NEG_INFINITY, f64::INFINITY)];
+        assert_eq!(clamp(&[2.0, -1e300], &bounds), vec![1.0, -1e300]);
+        assert_eq!(clamp(&[-1.0, f64::INFINITY], &bounds), vec![0.0, f64::MAX]);
+        assert!(clamp(&[0.5, f64::NAN], &bounds)[1].is_nan());
+    }
+
+    #[test]
+    fn test_avg_without_overflow() {
+        assert_eq!(avg(&[vec![f64::MAX], vec![f64::MAX]]), vec![f64::MAX]);
+    }
+
+    #[test]
+    fn test_reflect_into() {
+        let bounds = [(0.0, 1.0), (0.0, 1.0), (0.0, 1.0)];
+        assert_eq!(
+            reflect_into(&[-0.25, 1.5, 0.5], &bounds),
+            vec![0.25, 0.5, 0.5]
+        );
+        assert_eq!(reflect_into(&[3.0, 0.0, 1.0], &bounds), vec![0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_truncate_into() {
+        let bounds = [(0.0, 1.0), (0.0, 1.0)];
+        assert_eq!(
+            truncate_into(&[0.5, 0.5], &[1.5, 1.0], &bounds),
+            vec![1.0, 0.75]
+        );
+        assert_eq!(
+            truncate_into(&[0.5, 0.5], &[0.75, 0.25], &bounds),
+            vec![0.75, 0.25]
+        );
+    }
+
     #[test]
     fn test_sum() {
         assert_eq!(
-            sum(&vec![1.0, 2.0, 3.0], &vec![5.0, 6.0, 7.0]),
+            sum(&[1.0, 2.0, 3.0], &[5.0, 6.0, 7.0]),
             vec![6.0, 8.0, 10.0]
         );
     }
@@ -38,33 +275,50 @@ mod tests {
     #[test]
     fn test_diff() {
         assert_eq!(
-            diff(&vec![1.0, 2.0, 3.0], &vec![5.0, 6.0, 7.0]),
+            diff(&[1.0, 2.0, 3.0], &[5.0, 6.0, 7.0]),
             vec![-4.0, -4.0, -4.0]
         );
     }
 
     #[test]
     fn test_mult() {
-        assert_eq!(mult(2.0, &vec![5.0, 6.0, 7.0]), vec![10.0, 12.0, 14.0]);
+        assert_eq!(mult(2.0, &[5.0, 6.0, 7.0]), vec![10.0, 12.0, 14.0]);
     }
 
     #[test]
     fn test_avg() {
         assert_eq!(
-            avg(&vec![vec![1.0, 2.0, 3.0], vec![5.0, 6.0, 7.0]]),
+            avg(&[vec![1.0, 2.0, 3.0], vec![5.0, 6.0, 7.0]]),
             vec![3.0, 4.0, 5.0]
         );
     }
 
+    #[test]
+    fn test_distance() {
+        assert_eq!(distance(&[1.0, 2.0, 3.0], &[4.0, 6.0, 3.0]), 5.0);
+    }
+
     #[test]
     fn test_clamp() {
         assert_eq!(
-            clamp(
-                &vec![1.0, 2.0, 3.0],
-                &vec![(0.0, 2.0), (1.0, 1.5), (4.0, 5.0)]
-            ),
+            clamp(&[1.0, 2.0, 3.0], &[(0.0, 2.0), (1.0, 1.5), (4.0, 5.0)]),
             vec![1.0, 1.5, 4.0]
         );
     }
 
+    #[test]
+    fn test_symmetric_eigen() {
+        let matrix = vec![vec![2.0, 1.0], vec![1.0, 2.0]];
+        let mut eigen = symmetric_eigen(&matrix);
+        eigen.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap());
+        assert!((eigen[0].0 - 1.0).abs() < 1e-12);
+        assert!((eigen[1].0 - 3.0).abs() < 1e-12);
+        for (lambda, v) in eigen {
+            for i in 0..2 {
+                let mv: f64 = (0..2).map(|j| matrix[i][j] * v[j]).sum();
+                assert!((mv - lambda * v[i]).abs() < 1e-12);
+            }
+            assert!((distance(&v, &[0.0, 0.0]) - 1.0).abs() < 1e-12);
+        }
+    }
 }
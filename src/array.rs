@@ -0,0 +1,440 @@
+//! A variant of the optimizer for small dimensions known at compile time.
+//!
+//! Points, bounds and the simplex itself are stored in fixed-size arrays, so
+//! the optimization loop never allocates, and their coordinates and values
+//! can be either `f64` or `f32`, whichever the objective takes. Only
+//! `alpha`, `gamma`, `rho`, `delta`, `f_atol`, `f_rtol` and `shrink_keep`
+//! are taken from `Params`; the other options are ignored.
+//!
+//! # Example
+//!
+//! ```
+//! use nelder_mead::array::*;
+//!
+//! use assert_approx_eq::assert_approx_eq;
+//!
+//! // minimize (x+1)^2 + y^2
+//! let result = minimize_array(
+//!    |args: &[f64; 2]| (args[0]+1.0) * (args[0]+1.0) + args[1]*args[1],
+//!    [5.0, 5.0],
+//!    1.0,
+//!    &Default::default(),
+//!    [(f64::NEG_INFINITY, f64::INFINITY); 2],
+//!    1000);
+//!
+//! // expected minimum: f(-1, 0) = 0
+//! assert_approx_eq!(result.point[0], -1.0);
+//! assert_approx_eq!(result.point[1], 0.0);
+//! assert_approx_eq!(result.value, 0.0);
+//! ```
+
+extern crate num_traits;
+extern crate rand;
+use num_traits::float::FloatCore;
+use rand::distributions::uniform::SampleUniform;
+#[cfg(feature = "std")]
+use rand::rngs::OsRng;
+use rand::Rng;
+
+use crate::algebra::*;
+use crate::params::*;
+
+/// The largest dimension supported by the array optimizer.
+///
+/// Using a larger `N` fails to compile.
+pub const MAX_DIMENSIONS: usize = 8;
+
+/// Room for the vertices of the largest simplex, plus one candidate vertex.
+const CAPACITY: usize = MAX_DIMENSIONS + 2;
+
+type Vertices<T, const N: usize> = [([T; N], T); CAPACITY];
+
+/// The outcome of an optimization run with the array optimizer.
+pub struct ArrayResult<const N: usize, T = f64> {
+    /// The best point found.
+    pub point: [T; N],
+    /// The function value at `point`.
+    pub value: T,
+    /// Number of iterations performed.
+    pub iterations: u32,
+    /// Number of calls to the objective.
+    pub evaluations: u32,
+}
+
+/// Converts a coefficient of `Params` to the float type of the points.
+fn coefficient<T: FloatCore>(k: f64) -> T {
+    T::from(k).expect("The coefficient must be representable")
+}
+
+fn sort<T: FloatCore, const N: usize>(vertices: &mut [([T; N], T)]) {
+    vertices.sort_by(|(_, fx), (_, fy)| compare_values(*fx, *fy));
+}
+
+/// Computes `x + k * (a - b)`.
+fn offset<T: FloatCore, const N: usize>(x: &[T; N], k: T, a: &[T; N], b: &[T; N]) -> [T; N] {
+    let mut result = *x;
+    for (r, (ai, bi)) in result.iter_mut().zip(a.iter().zip(b.iter())) {
+        *r = *r + k * (*ai - *bi);
+    }
+    result
+}
+
+fn clamp<T: FloatCore, const N: usize>(x: [T; N], bounds: &[(T, T); N]) -> [T; N] {
+    let mut result = x;
+    for (r, (min, max)) in result.iter_mut().zip(bounds.iter()) {
+        *r = clamp_coordinate(*r, (*min, *max));
+    }
+    result
+}
+
+fn centroid<T: FloatCore, const N: usize>(vertices: &[([T; N], T)]) -> [T; N] {
+    let k = T::one() / coefficient(vertices.len() as f64);
+    let mut total = [T::zero(); N];
+    for (x, _) in vertices {
+        for (t, xi) in total.iter_mut().zip(x.iter()) {
+            *t = *t + k * *xi;
+        }
+    }
+    total
+}
+
+/// Performs a single iteration on the `N + 1` sorted vertices of `vertices`.
+fn step<T: FloatCore, const N: usize>(
+    f: &impl Fn(&[T; N]) -> T,
+    vertices: &mut Vertices<T, N>,
+    params: &Params,
+    bounds: &[(T, T); N],
+    evaluations: &mut u32,
+) {
+    let mut eval = |x: &[T; N]| {
+        *evaluations += 1;
+        f(x)
+    };
+    let (x1, fx1) = vertices[0];
+    let x0 = centroid(&vertices[..N]);
+    let fxn = vertices[N - 1].1;
+    let (xn1, fxn1) = vertices[N];
+
+    let xr = clamp(offset(&x0, coefficient(params.alpha), &x0, &xn1), bounds);
+    let fxr = eval(&xr);
+
+    let candidate = if fxr < fx1 {
+        let xe = clamp(offset(&x0, coefficient(params.gamma), &xr, &x0), bounds);
+        let fxe = eval(&xe);
+        if fxe < fxr {
+            Some((xe, fxe))
+        } else {
+            Some((xr, fxr))
+        }
+    } else if fxr < fxn {
+        Some((xr, fxr))
+    } else {
+        let xc = clamp(offset(&x0, coefficient(params.rho), &xn1, &x0), bounds);
+        let fxc = eval(&xc);
+        if fxc < fxn1 {
+            Some((xc, fxc))
+        } else {
+            None
+        }
+    };
+
+    match candidate {
+        Some(candidate) => {
+            // insert after the current vertices and drop the worst one
+            vertices[N + 1] = candidate;
+            sort(&mut vertices[..N + 2]);
+        }
+        None => {
+            let keep = params.shrink_keep.clamp(1, N + 1);
+            let mut shrunk: Vertices<T, N> = [([T::zero(); N], T::zero()); CAPACITY];
+            for (i, (xi, _)) in vertices[keep..=N].iter().enumerate() {
+                let x = offset(&x1, coefficient(params.delta), xi, &x1);
+                shrunk[i] = (x, eval(&x));
+            }
+            shrunk[N + 1 - keep..=N].copy_from_slice(&vertices[..keep]);
+            sort(&mut shrunk[..=N]);
+            vertices[..=N].copy_from_slice(&shrunk[..=N]);
+        }
+    }
+}
+
+/// Minimizes a function `f`,
+/// starting with a random simplex of size `initial_simplex_size` centered on
+/// `initial_point`.
+///
+/// The search space is bounded by the `(min, max)` pair of every dimension.
+#[cfg(feature = "std")]
+pub fn minimize_array<const N: usize, T: FloatCore + SampleUniform>(
+    f: impl Fn(&[T; N]) -> T,
+    initial_point: [T; N],
+    initial_simplex_size: T,
+    params: &Params,
+    bounds: [(T, T); N],
+    max_iter: u32,
+) -> ArrayResult<N, T> {
+    let mut rng = OsRng::new().expect("Failed to create the RNG");
+    minimize_array_with_rng(
+        f,
+        initial_point,
+        initial_simplex_size,
+        params,
+        bounds,
+        max_iter,
+        &mut rng,
+    )
+}
+
+/// Minimizes a function `f`,
+/// starting with a random simplex of size `initial_simplex_size` centered on
+/// `initial_point`, drawn from `rng`, and clamped to the bounds.
+///
+/// Unlike `minimize_array`, this doesn't need the operating system's random
+/// generator, so it is available without the `std` feature.
+///
+/// The search space is bounded by the `(min, max)` pair of every dimension.
+pub fn minimize_array_with_rng<const N: usize, T: FloatCore + SampleUniform>(
+    f: impl Fn(&[T; N]) -> T,
+    initial_point: [T; N],
+    initial_simplex_size: T,
+    params: &Params,
+    bounds: [(T, T); N],
+    max_iter: u32,
+    rng: &mut impl Rng,
+) -> ArrayResult<N, T> {
+    let mut initial_simplex = [initial_point; CAPACITY];
+    for point in initial_simplex[..=N].iter_mut() {
+        for x in point.iter_mut() {
+            *x = *x + rng.gen_range(-initial_simplex_size, initial_simplex_size);
+        }
+        *point = clamp(*point, &bounds);
+    }
+    minimize_array_from_simplex(f, &initial_simplex[..=N], params, bounds, max_iter)
+}
+
+/// Minimizes a function `f`,
+/// starting from the `N + 1` vertices of `initial_simplex`.
+///
+/// The search space is bounded by the `(min, max)` pair of every dimension.
+///
+/// # Panics
+///
+/// Panics unless `initial_simplex` has exactly `N + 1` vertices.
+pub fn minimize_array_from_simplex<const N: usize, T: FloatCore>(
+    f: impl Fn(&[T; N]) -> T,
+    initial_simplex: &[[T; N]],
+    params: &Params,
+    bounds: [(T, T); N],
+    max_iter: u32,
+) -> ArrayResult<N, T> {
+    const {
+        assert!(
+            N >= 1 && N <= MAX_DIMENSIONS,
+            "Unsupported dimension for the array optimizer"
+        )
+    };
+    assert_eq!(initial_simplex.len(), N + 1, "Expected N + 1 vertices");
+
+    let mut vertices: Vertices<T, N> = [([T::zero(); N], T::zero()); CAPACITY];
+    for (vertex, x) in vertices.iter_mut().zip(initial_simplex.iter()) {
+        *vertex = (*x, f(x));
+    }
+    let mut evaluations = N as u32 + 1;
+    sort(&mut vertices[..=N]);
+
+    let mut iterations = 0;
+    for iteration in 0..max_iter {
+        step(&f, &mut vertices, params, &bounds, &mut evaluations);
+        iterations = iteration + 1;
+        if params.f_atol != 0.0 || params.f_rtol != 0.0 {
+            let tolerance = coefficient::<T>(params.f_atol)
+                + coefficient::<T>(params.f_rtol) * vertices[0].1.abs();
+            if vertices[N].1 - vertices[0].1 <= tolerance {
+                break;
+            }
+        }
+    }
+
+    let (x1, fx1) = vertices[0];
+    let x0 = centroid(&vertices[..N]);
+    let fx0 = f(&x0);
+    evaluations += 1;
+    let (point, value) = if fx1 < fx0 { (x1, fx1) } else { (x0, fx0) };
+    ArrayResult {
+        point,
+        value,
+        iterations,
+        evaluations,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bounds::*;
+    use crate::initial_simplex::*;
+    use crate::simplex::*;
+
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+
+    /// Counts the allocations made by each thread.
+    struct CountingAllocator;
+
+    thread_local! {
+        static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let _ = ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    fn rosenbrock(args: &[f64]) -> f64 {
+        (1.0 - args[0]) * (1.0 - args[0])
+            + 100.0 * (args[1] - args[0] * args[0]) * (args[1] - args[0] * args[0])
+            + args[2] * args[2]
+    }
+
+    #[test]
+    fn match_vec_optimizer() {
+        let points = [
+            [-1.2, 1.0, 0.5],
+            [-1.0, 1.0, 0.5],
+            [-1.2, 1.2, 0.5],
+            [-1.2, 1.0, 0.7],
+        ];
+        let params = Params {
+            f_atol: 1e-12,
+            ..Default::default()
+        };
+        let bounds = Bounds {
+            min: vec![-2.0, -2.0, 0.25],
+            max: vec![2.0, 2.0, 2.0],
+        };
+        let bounds_array = [(-2.0, 2.0), (-2.0, 2.0), (0.25, 2.0)];
+
+        let array_result =
+            minimize_array_from_simplex(|x| rosenbrock(x), &points, &params, bounds_array, 5000);
+        let f = |x: &[f64]| rosenbrock(x);
+        let initial_simplex = InitialSimplex {
+            points: points.iter().map(|x| x.to_vec()).collect(),
+        };
+        let vec_result = minimize(
+            f,
+            evaluate_simplex(f, &initial_simplex),
+            params,
+            bounds,
+            5000,
+        );
+
+        assert_eq!(array_result.point.to_vec(), vec_result.point);
+        assert_eq!(array_result.value, vec_result.value);
+        assert_eq!(array_result.iterations, vec_result.iterations);
+        assert!(array_result.iterations < 5000);
+    }
+
+    #[test]
+    fn do_not_allocate() {
+        let points = [
+            [-1.2, 1.0, 0.5],
+            [-1.0, 1.0, 0.5],
+            [-1.2, 1.2, 0.5],
+            [-1.2, 1.0, 0.7],
+        ];
+        let params = Default::default();
+        let before = ALLOCATIONS.with(|allocations| allocations.get());
+        let result = minimize_array_from_simplex(
+            |x| rosenbrock(x),
+            &points,
+            &params,
+            [(f64::NEG_INFINITY, f64::INFINITY); 3],
+            1000,
+        );
+        let after = ALLOCATIONS.with(|allocations| allocations.get());
+        assert_eq!(after, before);
+        assert_eq!(result.iterations, 1000);
+    }
+
+    #[test]
+    fn minimize_single_precision() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let params = Params {
+            f_atol: 1e-10,
+            ..Default::default()
+        };
+        let result = minimize_array_with_rng(
+            |x: &[f32; 2]| (x[0] - 1.5) * (x[0] - 1.5) + (x[1] + 0.5) * (x[1] + 0.5),
+            [5.0f32, 0.5],
+            1.0,
+            &params,
+            [(f32::NEG_INFINITY, f32::INFINITY), (-0.25, 1.0)],
+            1000,
+            &mut StdRng::seed_from_u64(0),
+        );
+        assert!((result.point[0] - 1.5).abs() < 1e-3);
+        assert_eq!(result.point[1], -0.25);
+        assert!((result.value - 0.0625).abs() < 1e-5);
+        assert!(result.iterations < 1000);
+    }
+
+    #[test]
+    fn minimize_with_user_rng() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let run = |seed| {
+            minimize_array_with_rng(
+                |x: &[f64; 2]| (x[0] - 1.0) * (x[0] - 1.0) + (x[1] + 2.0) * (x[1] + 2.0),
+                [5.0, 5.0],
+                0.5,
+                &Default::default(),
+                [(f64::NEG_INFINITY, f64::INFINITY); 2],
+                1000,
+                &mut StdRng::seed_from_u64(seed),
+            )
+        };
+        let (first, second) = (run(9), run(9));
+        assert_eq!(first.point, second.point);
+        assert_eq!(first.evaluations, second.evaluations);
+        assert!(first.value < 1e-6);
+    }
+
+    #[test]
+    fn start_within_bounds() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        use std::cell::Cell;
+
+        let outside = Cell::new(0);
+        let bounds = [(5.0, 6.0), (-1.0, 5.0)];
+        minimize_array_with_rng(
+            |x: &[f64; 2]| {
+                if x.iter()
+                    .zip(bounds.iter())
+                    .any(|(x, (min, max))| x < min || x > max)
+                {
+                    outside.set(outside.get() + 1);
+                }
+                (x[0] - 1.0) * (x[0] - 1.0) + (x[1] + 2.0) * (x[1] + 2.0)
+            },
+            [5.0, 5.0],
+            1.0,
+            &Default::default(),
+            bounds,
+            100,
+            &mut StdRng::seed_from_u64(0),
+        );
+        assert_eq!(outside.get(), 0);
+    }
+}
@@ -1,24 +1,374 @@
-pub struct Bounds {
-    pub min: Vec<f64>,
-    pub max: Vec<f64>,
+use alloc::vec;
+use alloc::vec::Vec;
+use num_traits::float::FloatCore;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::algebra::*;
+use crate::error::*;
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "crate::serialization::BoundsRepr<T>",
+        into = "crate::serialization::BoundsRepr<T>",
+        bound(
+            serialize = "T: FloatCore + Serialize",
+            deserialize = "T: FloatCore + Deserialize<'de>"
+        )
+    )
+)]
+pub struct Bounds<T = f64> {
+    pub min: Vec<T>,
+    pub max: Vec<T>,
 }
 
-impl Bounds {
-    pub fn none(n: usize) -> Bounds {
-        let mut min = Vec::new();
-        let mut max = Vec::new();
-        for _ in 0..n {
-            min.push(std::f64::MIN);
-            max.push(std::f64::MAX);
+impl<T: FloatCore> Bounds<T> {
+    /// Builds bounds that leave all `n` dimensions unbounded.
+    ///
+    /// The bounds are infinite, which the optimizer never clamps to, so
+    /// points far from the origin don't overflow the arithmetic.
+    pub fn none(n: usize) -> Bounds<T> {
+        Bounds {
+            min: vec![T::neg_infinity(); n],
+            max: vec![T::infinity(); n],
         }
-        Bounds { min, max }
     }
 
-    pub fn as_vec(self: &Bounds) -> Vec<(f64, f64)> {
+    pub fn as_vec(self: &Bounds<T>) -> Vec<(T, T)> {
         self.min
             .iter()
             .cloned()
             .zip(self.max.iter().cloned())
             .collect()
     }
+
+    /// Checks that the bounds have `dimensions` minimums and maximums, and
+    /// that no minimum is above its maximum.
+    pub fn validate(self: &Bounds<T>, dimensions: usize) -> Result<(), NelderMeadError> {
+        for found in [self.min.len(), self.max.len()] {
+            if found != dimensions {
+                return Err(NelderMeadError::DimensionMismatch {
+                    expected: dimensions,
+                    found,
+                });
+            }
+        }
+        match self
+            .min
+            .iter()
+            .zip(self.max.iter())
+            .position(|(min, max)| min > max || min.is_nan() || max.is_nan())
+        {
+            Some(dimension) => Err(NelderMeadError::EmptyBounds { dimension }),
+            None => Ok(()),
+        }
+    }
+
+    /// Computes the intersection of two bounds, keeping the tightest minimum
+    /// and maximum of every dimension.
+    ///
+    /// Fails if the bounds have a different number of dimensions or if the
+    /// intersection is empty in any dimension.
+    pub fn intersect(self: &Bounds<T>, other: &Bounds<T>) -> Result<Bounds<T>, NelderMeadError> {
+        if self.min.len() != other.min.len() {
+            return Err(NelderMeadError::DimensionMismatch {
+                expected: self.min.len(),
+                found: other.min.len(),
+            });
+        }
+        let min: Vec<T> = self
+            .min
+            .iter()
+            .zip(other.min.iter())
+            .map(|(a, b)| a.max(*b))
+            .collect();
+        let max: Vec<T> = self
+            .max
+            .iter()
+            .zip(other.max.iter())
+            .map(|(a, b)| a.min(*b))
+            .collect();
+        match min.iter().zip(max.iter()).position(|(min, max)| min > max) {
+            Some(dimension) => Err(NelderMeadError::EmptyIntersection { dimension }),
+            None => Ok(Bounds { min, max }),
+        }
+    }
+
+    /// Treats the bounds as open intervals, moving the minimum and the
+    /// maximum of every dimension inwards by its `(lower, upper)` margin.
+    ///
+    /// Points are then clamped to `min + lower` and `max - upper`, so a
+    /// margin keeps them strictly away from a bound where the objective is
+    /// singular, like a variance of 0. A margin of 0 leaves that end of the
+    /// interval closed.
+    ///
+    /// Fails if there isn't one pair of margins per dimension or if the
+    /// margins leave any dimension empty.
+    pub fn exclusive(self: &Bounds<T>, margins: &[(T, T)]) -> Result<Bounds<T>, NelderMeadError> {
+        if self.min.len() != margins.len() {
+            return Err(NelderMeadError::DimensionMismatch {
+                expected: self.min.len(),
+                found: margins.len(),
+            });
+        }
+        let min: Vec<T> = self
+            .min
+            .iter()
+            .zip(margins.iter())
+            .map(|(min, (lower, _))| *min + *lower)
+            .collect();
+        let max: Vec<T> = self
+            .max
+            .iter()
+            .zip(margins.iter())
+            .map(|(max, (_, upper))| *max - *upper)
+            .collect();
+        match min.iter().zip(max.iter()).position(|(min, max)| min > max) {
+            Some(dimension) => Err(NelderMeadError::EmptyIntersection { dimension }),
+            None => Ok(Bounds { min, max }),
+        }
+    }
+}
+
+/// Wraps an objective so that every point is clamped to the bounds before
+/// being evaluated.
+///
+/// Unlike the clamping done by the optimizer on its trial points, this also
+/// covers the vertices of the initial simplex and any point injected by a
+/// callback, so `f` never sees a point outside of the bounds.
+/// Since `Bounded::eval` takes any objective, the wrapped `f` can itself
+/// count or cache its evaluations.
+pub struct Bounded<F: Fn(&[T]) -> T, T = f64> {
+    f: F,
+    bounds: Vec<(T, T)>,
+}
+
+impl<F: Fn(&[T]) -> T, T: FloatCore> Bounded<F, T> {
+    pub fn new(f: F, bounds: &Bounds<T>) -> Bounded<F, T> {
+        Bounded {
+            f,
+            bounds: bounds.as_vec(),
+        }
+    }
+
+    /// Evaluates `f` at `x`, clamped to the bounds.
+    pub fn eval(&self, x: &[T]) -> T {
+        (self.f)(&clamp(x, &self.bounds))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::callback::*;
+    use crate::initial_simplex::*;
+    use crate::params::*;
+    use crate::simplex::{evaluate_simplex, minimize};
+
+    use std::cell::{Cell, RefCell};
+
+    #[test]
+    fn intersect_overlapping_bounds() {
+        let a = Bounds {
+            min: vec![0.0, -1.0],
+            max: vec![2.0, 1.0],
+        };
+        let b = Bounds {
+            min: vec![1.0, f64::NEG_INFINITY],
+            max: vec![3.0, 0.5],
+        };
+        let intersection = a.intersect(&b).unwrap();
+        assert_eq!(intersection.min, vec![1.0, -1.0]);
+        assert_eq!(intersection.max, vec![2.0, 0.5]);
+    }
+
+    #[test]
+    fn intersect_with_unbounded() {
+        let a = Bounds {
+            min: vec![0.0, -1.0],
+            max: vec![2.0, 1.0],
+        };
+        let intersection = a.intersect(&Bounds::none(2)).unwrap();
+        assert_eq!(intersection.min, a.min);
+        assert_eq!(intersection.max, a.max);
+    }
+
+    #[test]
+    fn intersect_disjoint_bounds() {
+        let a = Bounds {
+            min: vec![0.0, 0.0],
+            max: vec![1.0, 1.0],
+        };
+        let b = Bounds {
+            min: vec![0.5, 2.0],
+            max: vec![1.5, 3.0],
+        };
+        assert_eq!(
+            a.intersect(&b).err(),
+            Some(NelderMeadError::EmptyIntersection { dimension: 1 })
+        );
+    }
+
+    #[test]
+    fn intersect_mismatched_bounds() {
+        assert_eq!(
+            Bounds::<f64>::none(2).intersect(&Bounds::none(3)).err(),
+            Some(NelderMeadError::DimensionMismatch {
+                expected: 2,
+                found: 3
+            })
+        );
+    }
+
+    #[test]
+    fn validate_bounds() {
+        let bounds = Bounds {
+            min: vec![0.0, -1.0],
+            max: vec![1.0, f64::INFINITY],
+        };
+        assert_eq!(bounds.validate(2), Ok(()));
+        assert_eq!(Bounds::<f64>::none(3).validate(3), Ok(()));
+        assert_eq!(
+            bounds.validate(3),
+            Err(NelderMeadError::DimensionMismatch {
+                expected: 3,
+                found: 2
+            })
+        );
+        let uneven = Bounds {
+            min: vec![0.0, 0.0],
+            max: vec![1.0],
+        };
+        assert_eq!(
+            uneven.validate(2),
+            Err(NelderMeadError::DimensionMismatch {
+                expected: 2,
+                found: 1
+            })
+        );
+        let empty = Bounds {
+            min: vec![0.0, 2.0, 0.0],
+            max: vec![1.0, 1.0, f64::NAN],
+        };
+        assert_eq!(
+            empty.validate(3),
+            Err(NelderMeadError::EmptyBounds { dimension: 1 })
+        );
+    }
+
+    #[test]
+    fn stay_finite_far_from_the_origin() {
+        // minimum at (1e308, -1e308), where the centroid and the expansions
+        // overshoot the largest finite values
+        let f = |x: &[f64]| (x[0] / 1e307 - 10.0).powi(2) + (x[1] / 1e307 + 10.0).powi(2);
+        let initial_simplex = InitialSimplex::axis_aligned(vec![0.0, 0.0], 1e305);
+        let result = minimize(
+            f,
+            evaluate_simplex(f, &initial_simplex),
+            Default::default(),
+            Bounds::none(2),
+            1000,
+        );
+        assert!(result
+            .final_simplex
+            .iter()
+            .all(|(x, fx)| fx.is_finite() && x.iter().all(|c| c.is_finite())));
+        assert!((result.point[0] / 1e308 - 1.0).abs() < 1e-6);
+        assert!((result.point[1] / 1e308 + 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn keep_away_from_exclusive_bound() {
+        let bounds = Bounds {
+            min: vec![0.0, f64::NEG_INFINITY],
+            max: vec![10.0, f64::INFINITY],
+        }
+        .exclusive(&[(1e-6, 0.0), (0.0, 0.0)])
+        .unwrap();
+        assert_eq!(bounds.min[0], 1e-6);
+        assert_eq!(bounds.max[0], 10.0);
+
+        // singular at x = 0, while the unconstrained minimum is at x = -1
+        let evaluated = RefCell::new(Vec::new());
+        let f = |x: &[f64]| {
+            evaluated.borrow_mut().push(x.to_vec());
+            (x[0] + 1.0) * (x[0] + 1.0) - x[0].ln() * 1e-9 + x[1] * x[1]
+        };
+        let initial_simplex = InitialSimplex::axis_aligned(vec![1.0, 1.0], 0.5);
+        let result = minimize(
+            f,
+            evaluate_simplex(f, &initial_simplex),
+            Default::default(),
+            bounds,
+            500,
+        );
+        assert!(evaluated.borrow().iter().all(|x| x[0] > 0.0));
+        assert!(result.value.is_finite());
+        assert_eq!(result.point[0], 1e-6);
+    }
+
+    #[test]
+    fn reject_exclusive_margins_that_empty_the_bounds() {
+        let bounds = Bounds {
+            min: vec![0.0],
+            max: vec![1.0],
+        };
+        assert_eq!(
+            bounds.exclusive(&[(0.6, 0.6)]).err(),
+            Some(NelderMeadError::EmptyIntersection { dimension: 0 })
+        );
+        assert_eq!(
+            bounds.exclusive(&[]).err(),
+            Some(NelderMeadError::DimensionMismatch {
+                expected: 1,
+                found: 0
+            })
+        );
+    }
+
+    #[test]
+    fn never_evaluate_out_of_bounds() {
+        let bounds = Bounds {
+            min: vec![0.0, 0.0],
+            max: vec![1.0, 1.0],
+        };
+        let outside = Cell::new(0);
+        let f = |x: &[f64]| {
+            if !x.iter().all(|xi| (0.0..=1.0).contains(xi)) {
+                outside.set(outside.get() + 1);
+            }
+            (x[0] - 2.0) * (x[0] - 2.0) + x[1] * x[1]
+        };
+        // starting on the corner, the other vertices step outside of it
+        let params = Params {
+            axis_aligned_simplex: true,
+            ..Default::default()
+        };
+        crate::minimize(f, vec![1.0, 1.0], 0.5, params.clone(), bounds.clone(), 200);
+        assert!(outside.replace(0) > 0);
+
+        let bounded = Bounded::new(f, &bounds);
+        assert_eq!(bounded.eval(&[3.0, -1.0]), 1.0);
+        let result = crate::minimize_with_callback(
+            |x| bounded.eval(x),
+            vec![1.0, 1.0],
+            0.5,
+            params,
+            bounds,
+            200,
+            |info| {
+                if info.iteration == 5 {
+                    Action::TryPoint(vec![3.0, -1.0])
+                } else {
+                    Action::Continue
+                }
+            },
+        );
+        assert_eq!(outside.get(), 0);
+        assert!(result.point.iter().all(|xi| (0.0..=1.0).contains(xi)));
+        assert_eq!(result.value, f(&result.point));
+    }
 }
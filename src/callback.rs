@@ -0,0 +1,36 @@
+use crate::bounds::*;
+
+/// The state of the optimization after an iteration, as passed to the
+/// per-iteration callback.
+pub struct IterationInfo<'a, T = f64> {
+    /// Index of the iteration that just finished, starting at 0.
+    pub iteration: u32,
+    /// The current simplex, sorted from best to worst vertex.
+    pub simplex: &'a [(Vec<T>, T)],
+    /// Estimated progress towards termination, between 0 and 1.
+    ///
+    /// This is the furthest of the progress towards `max_iter`, towards
+    /// `Params::max_evaluations` and, when value tolerances are set, the
+    /// logarithmic reduction of the value spread towards the tolerance. It
+    /// never decreases during a run.
+    pub progress: f64,
+}
+
+/// What the optimizer should do after the per-iteration callback returns.
+pub enum Action<T = f64> {
+    /// Keep optimizing as usual.
+    Continue,
+    /// Replace the search bounds for subsequent iterations.
+    ///
+    /// Vertices outside the new bounds are clamped into them and
+    /// re-evaluated.
+    SetBounds(Bounds<T>),
+    /// Evaluate a candidate point, which replaces the worst vertex if it is
+    /// better.
+    ///
+    /// The candidate is clamped into the bounds before being evaluated.
+    TryPoint(Vec<T>),
+    /// Stop the optimization, reporting the best vertex so far with
+    /// `TerminationReason::Aborted`.
+    Abort,
+}
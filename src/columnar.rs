@@ -0,0 +1,143 @@
+//! Columnar export of recorded evaluations.
+//!
+//! `to_columnar` flattens the `evaluation_history` of a batch of runs into
+//! parallel column vectors, one entry per evaluation, which can be handed
+//! over to a dataframe library without building intermediate rows.
+//!
+//! # Example
+//!
+//! ```
+//! use nelder_mead::*;
+//! use nelder_mead::bounds::*;
+//! use nelder_mead::columnar::*;
+//! use nelder_mead::params::*;
+//!
+//! let params = Params {
+//!    record_evaluations: true,
+//!    ..Default::default()
+//! };
+//! let result = minimize_with_report(
+//!    |args: &[f64]| (args[0]+1.0) * (args[0]+1.0) + args[1]*args[1],
+//!    vec![5.0, 5.0],
+//!    1.0,
+//!    params,
+//!    Bounds::none(2),
+//!    1000);
+//!
+//! let columns = to_columnar(&[result]);
+//! assert_eq!(columns.coordinates.len(), 2);
+//! assert_eq!(columns.value.len(), columns.run.len());
+//! ```
+
+use crate::result::*;
+
+/// Evaluations of a batch of runs, stored column by column.
+///
+/// All columns have one entry per evaluation.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EvaluationColumns {
+    /// Index of the run in the exported batch.
+    pub run: Vec<u32>,
+    /// Index of the evaluation within its run.
+    pub evaluation: Vec<u32>,
+    /// One column per dimension of the search space.
+    pub coordinates: Vec<Vec<f64>>,
+    /// Value of the objective.
+    pub value: Vec<f64>,
+}
+
+impl EvaluationColumns {
+    /// Number of exported evaluations.
+    pub fn len(&self) -> usize {
+        self.value.len()
+    }
+
+    /// Whether no evaluation was exported.
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+}
+
+/// Exports the recorded evaluations of `results` as columns.
+///
+/// Only runs with `Params::record_evaluations` set have evaluations to
+/// export.
+///
+/// # Panics
+///
+/// Panics if the runs don't share the same dimension.
+pub fn to_columnar(results: &[OptimizationResult]) -> EvaluationColumns {
+    let total: usize = results.iter().map(|r| r.evaluation_history.len()).sum();
+    let dimensions = results
+        .iter()
+        .find(|r| !r.evaluation_history.is_empty())
+        .map(|r| r.evaluation_history[0].0.len())
+        .unwrap_or(0);
+    let mut columns = EvaluationColumns {
+        run: Vec::with_capacity(total),
+        evaluation: Vec::with_capacity(total),
+        coordinates: (0..dimensions).map(|_| Vec::with_capacity(total)).collect(),
+        value: Vec::with_capacity(total),
+    };
+    for (run, result) in results.iter().enumerate() {
+        for (evaluation, (x, fx)) in result.evaluation_history.iter().enumerate() {
+            assert_eq!(x.len(), dimensions, "Runs with different dimensions");
+            columns.run.push(run as u32);
+            columns.evaluation.push(evaluation as u32);
+            for (column, xi) in columns.coordinates.iter_mut().zip(x) {
+                column.push(*xi);
+            }
+            columns.value.push(*fx);
+        }
+    }
+    columns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bounds::*;
+    use crate::params::*;
+    use crate::*;
+
+    #[test]
+    fn export_batch_columns() {
+        let f = |x: &[f64]| (x[0] - 1.0) * (x[0] - 1.0) + 2.0 * x[1] * x[1] + x[2].abs();
+        let params = Params {
+            record_evaluations: true,
+            ..Default::default()
+        };
+        let results: Vec<_> = [vec![3.0, 2.0, 1.0], vec![-2.0, 0.5, -1.0]]
+            .iter()
+            .map(|x0| {
+                minimize_with_report(f, x0.clone(), 0.5, params.clone(), Bounds::none(3), 200)
+            })
+            .collect();
+        let columns = to_columnar(&results);
+
+        let total: usize = results.iter().map(|r| r.evaluation_history.len()).sum();
+        assert!(total > 0);
+        assert_eq!(columns.len(), total);
+        assert_eq!(columns.run.len(), total);
+        assert_eq!(columns.evaluation.len(), total);
+        assert_eq!(columns.coordinates.len(), 3);
+        for column in columns.coordinates.iter() {
+            assert_eq!(column.len(), total);
+        }
+
+        let mut row = 0;
+        for (run, result) in results.iter().enumerate() {
+            assert_eq!(result.evaluation_history.len() as u32, result.evaluations);
+            for (evaluation, (x, fx)) in result.evaluation_history.iter().enumerate() {
+                assert_eq!(columns.run[row], run as u32);
+                assert_eq!(columns.evaluation[row], evaluation as u32);
+                for (i, xi) in x.iter().enumerate() {
+                    assert_eq!(columns.coordinates[i][row], *xi);
+                }
+                assert_eq!(columns.value[row], *fx);
+                assert_eq!(*fx, f(x));
+                row += 1;
+            }
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use crate::algebra::*;
+use crate::Function;
+
+/// Adds the logarithmic barrier `-mu * sum(log(-g(x)))` over all
+/// `constraints` to `f`.
+///
+/// Points that don't strictly satisfy every `g(x) < 0` evaluate to
+/// `f64::INFINITY`.
+pub fn barrier<'a, T: Float>(
+    f: impl Fn(&[T]) -> T + 'a,
+    constraints: &'a [&'a Function<T>],
+    mu: T,
+) -> impl Fn(&[T]) -> T + 'a {
+    move |x| {
+        let mut barrier = T::zero();
+        for g in constraints {
+            let gx = g(x);
+            if gx >= T::zero() || gx.is_nan() {
+                return T::infinity();
+            }
+            barrier -= (-gx).ln();
+        }
+        f(x) + mu * barrier
+    }
+}
+
+/// How the penalty added by `minimize_with_penalty` grows with the
+/// violation of a constraint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PenaltyGrowth {
+    /// Proportional to the violation, which gives the exact constrained
+    /// minimum once the weight is large enough, at the cost of a kink on
+    /// the boundary.
+    Linear,
+    /// Proportional to the squared violation, which is smooth on the
+    /// boundary but only approaches the constrained minimum as the weight
+    /// grows.
+    Quadratic,
+}
+
+/// Adds `weight * max(g(x), 0)` over all `constraints` to `f`, or its square
+/// with `PenaltyGrowth::Quadratic`.
+///
+/// Points where some `g(x)` is `NaN` evaluate to `f64::INFINITY`.
+pub fn penalty<'a, T: Float>(
+    f: impl Fn(&[T]) -> T + 'a,
+    constraints: &'a [&'a Function<T>],
+    weight: T,
+    growth: PenaltyGrowth,
+) -> impl Fn(&[T]) -> T + 'a {
+    move |x| match penalty_at(x, constraints, weight, growth) {
+        Some(penalty) => f(x) + penalty,
+        None => T::infinity(),
+    }
+}
+
+/// Computes the penalty that `penalty` adds to `f` at `x`, or `None` if some
+/// `g(x)` is `NaN`.
+pub(crate) fn penalty_at<T: Float>(
+    x: &[T],
+    constraints: &[&Function<T>],
+    weight: T,
+    growth: PenaltyGrowth,
+) -> Option<T> {
+    let mut penalty = T::zero();
+    for g in constraints {
+        let gx = g(x);
+        if gx.is_nan() {
+            return None;
+        }
+        let violation = gx.max(T::zero());
+        penalty += match growth {
+            PenaltyGrowth::Linear => violation,
+            PenaltyGrowth::Quadratic => violation * violation,
+        };
+    }
+    Some(weight * penalty)
+}
+
+/// Finds a point that satisfies both the `projection` constraint and the
+/// bounds, by alternating the projection and the clamping of `x` until they
+/// agree.
+pub fn alternating_projection<T: Float>(
+    x: &[T],
+    projection: impl Fn(&[T]) -> Vec<T>,
+    bounds_vec: &[(T, T)],
+) -> Vec<T> {
+    let mut x = clamp(x, bounds_vec);
+    for _ in 0..100 {
+        let next = clamp(&projection(&x), bounds_vec);
+        let moved = distance(&next, &x);
+        x = next;
+        if moved <= T::from_f64(1e-12) * (T::one() + distance(&x, &vec![T::zero(); x.len()])) {
+            break;
+        }
+    }
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_barrier_inside_feasible_region() {
+        let g: &Function = &(|x| x[0] - 1.0);
+        let constraints = [g];
+        let f = barrier(|x| x[0] * x[0], &constraints, 0.5);
+        assert_eq!(f(&[0.0]), 0.0);
+        assert_eq!(f(&[-1.0]), 1.0 - 0.5 * 2.0f64.ln());
+    }
+
+    #[test]
+    fn reject_points_outside_feasible_region() {
+        let g: &Function = &(|x| x[0] - 1.0);
+        let constraints = [g];
+        let f = barrier(|x| x[0] * x[0], &constraints, 0.5);
+        assert_eq!(f(&[1.0]), f64::INFINITY);
+        assert_eq!(f(&[2.0]), f64::INFINITY);
+    }
+
+    #[test]
+    fn only_penalize_violated_constraints() {
+        let g: &Function = &(|x| x[0] - 1.0);
+        let h: &Function = &(|x| -x[0]);
+        let constraints = [g, h];
+        let linear = penalty(|x| x[0], &constraints, 10.0, PenaltyGrowth::Linear);
+        let quadratic = penalty(|x| x[0], &constraints, 10.0, PenaltyGrowth::Quadratic);
+        assert_eq!(linear(&[0.5]), 0.5);
+        assert_eq!(quadratic(&[0.5]), 0.5);
+        assert_eq!(linear(&[3.0]), 3.0 + 10.0 * 2.0);
+        assert_eq!(quadratic(&[3.0]), 3.0 + 10.0 * 4.0);
+        assert_eq!(linear(&[-3.0]), -3.0 + 10.0 * 3.0);
+        assert_eq!(quadratic(&[-3.0]), -3.0 + 10.0 * 9.0);
+    }
+
+    #[test]
+    fn reject_nan_constraints() {
+        let g: &Function = &(|x| x[0].sqrt());
+        let constraints = [g];
+        let f = penalty(|x| x[0], &constraints, 1.0, PenaltyGrowth::Linear);
+        assert_eq!(f(&[-1.0]), f64::INFINITY);
+    }
+
+    #[test]
+    fn project_onto_line_within_box() {
+        // x + y = 1, within [0, 0.8] x [0, 1]
+        let projection = |x: &[f64]| {
+            let offset = (x[0] + x[1] - 1.0) / 2.0;
+            vec![x[0] - offset, x[1] - offset]
+        };
+        let bounds_vec = [(0.0, 0.8), (0.0, 1.0)];
+        let x = alternating_projection(&[2.0, -0.5], projection, &bounds_vec);
+        assert!((x[0] - 0.8).abs() < 1e-9);
+        assert!((x[1] - 0.2).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,124 @@
+//! Finite-difference derivatives of an objective, for diagnostics and for
+//! building hybrid methods on top of the optimizer.
+
+use alloc::vec::Vec;
+
+/// The step used along each dimension by `finite_diff_gradient_with`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum StepSize {
+    /// The same step `h` along every dimension.
+    Absolute(f64),
+    /// A step of `h * max(|x_i|, 1)` along dimension `i`, which follows the
+    /// magnitude of the coordinates.
+    Relative(f64),
+    /// A given step along each dimension.
+    PerDimension(Vec<f64>),
+}
+
+impl StepSize {
+    /// The step along dimension `i` at `point`.
+    fn along(&self, point: &[f64], i: usize) -> f64 {
+        match self {
+            StepSize::Absolute(h) => *h,
+            StepSize::Relative(h) => h * point[i].abs().max(1.0),
+            StepSize::PerDimension(steps) => steps[i],
+        }
+    }
+}
+
+/// Approximates the gradient of `f` at `point` with central differences of
+/// step `h` along every dimension.
+///
+/// Takes `2n` evaluations of `f`.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::diff::*;
+///
+/// use assert_approx_eq::assert_approx_eq;
+///
+/// // the gradient of x^2 + 3y is (2x, 3)
+/// let gradient = finite_diff_gradient(|x| x[0] * x[0] + 3.0 * x[1], &[2.0, 1.0], 1e-5);
+///
+/// assert_approx_eq!(gradient[0], 4.0);
+/// assert_approx_eq!(gradient[1], 3.0);
+/// ```
+pub fn finite_diff_gradient(f: impl Fn(&[f64]) -> f64, point: &[f64], h: f64) -> Vec<f64> {
+    finite_diff_gradient_with(f, point, &StepSize::Absolute(h))
+}
+
+/// Approximates the gradient of `f` at `point` with central differences,
+/// using the steps given by `step_size`.
+///
+/// Takes `2n` evaluations of `f`.
+pub fn finite_diff_gradient_with(
+    f: impl Fn(&[f64]) -> f64,
+    point: &[f64],
+    step_size: &StepSize,
+) -> Vec<f64> {
+    let mut x = point.to_vec();
+    (0..point.len())
+        .map(|i| {
+            let h = step_size.along(point, i);
+            x[i] = point[i] + h;
+            let forward = f(&x);
+            x[i] = point[i] - h;
+            let backward = f(&x);
+            x[i] = point[i];
+            (forward - backward) / (2.0 * h)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: &[f64], b: &[f64], tol: f64) {
+        assert_eq!(a.len(), b.len());
+        for (a, b) in a.iter().zip(b.iter()) {
+            assert!((a - b).abs() <= tol, "{} != {}", a, b);
+        }
+    }
+
+    #[test]
+    fn match_quadratic_gradient() {
+        // f(x) = x^T A x / 2 + b^T x, whose gradient is A x + b
+        let f = |x: &[f64]| {
+            (2.0 * x[0] * x[0] + 2.0 * x[0] * x[1] + 3.0 * x[1] * x[1]) / 2.0 + x[0] - 4.0 * x[1]
+        };
+        let point = [1.5, -2.0];
+        let expected = [2.0 * 1.5 - 2.0 + 1.0, 1.5 - 3.0 * 2.0 - 4.0];
+        assert_close(&finite_diff_gradient(f, &point, 1e-4), &expected, 1e-8);
+        assert_close(
+            &finite_diff_gradient_with(f, &point, &StepSize::PerDimension(vec![1e-3, 1e-5])),
+            &expected,
+            1e-8,
+        );
+    }
+
+    #[test]
+    fn match_trigonometric_gradient() {
+        let f = |x: &[f64]| x[0].sin() * x[1].cos() + (3.0 * x[2]).sin();
+        let point = [0.3, 1.2, -0.7];
+        let expected = [
+            0.3f64.cos() * 1.2f64.cos(),
+            -0.3f64.sin() * 1.2f64.sin(),
+            3.0 * (3.0 * -0.7f64).cos(),
+        ];
+        assert_close(&finite_diff_gradient(f, &point, 1e-5), &expected, 1e-8);
+        assert_close(
+            &finite_diff_gradient_with(f, &point, &StepSize::Relative(1e-5)),
+            &expected,
+            1e-8,
+        );
+    }
+
+    #[test]
+    fn scale_relative_steps_with_coordinates() {
+        let step_size = StepSize::Relative(1e-6);
+        assert_eq!(step_size.along(&[0.5, -2000.0], 0), 1e-6);
+        assert_eq!(step_size.along(&[0.5, -2000.0], 1), 2e-3);
+    }
+}
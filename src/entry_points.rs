@@ -0,0 +1,2208 @@
+//! The entry points of the optimizer, which need the standard library and
+//! are re-exported from the crate root.
+
+use rand::rngs::OsRng;
+use rand::Rng;
+
+use crate::algebra::*;
+use crate::bounds::*;
+use crate::callback::*;
+use crate::constraints::*;
+use crate::error::*;
+use crate::initial_simplex::*;
+use crate::params::*;
+use crate::result::*;
+use crate::simplex::*;
+use crate::Function;
+
+/// Minimizes a function `f`,
+/// starting with a simplex of size `initial_simplex_size` centered on
+/// `initial_point`.
+///
+/// The search space is bounded by a `Bounds` definition.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+/// use nelder_mead::bounds::*;
+///
+/// use assert_approx_eq::assert_approx_eq;
+///
+/// // minimize (x+1)^2 + y^2
+/// let (x, fx) = minimize(
+///    |args: &[f64]| (args[0]+1.0) * (args[0]+1.0) + args[1]*args[1],
+///    vec![5.0,5.0],
+///    1.0,
+///    Default::default(),
+///    Bounds {min: vec![0.0, 0.0], max: vec![10.0, 10.0]},
+///    1000);
+///
+/// // expected bounded minimum: f(0, 0) = 1
+/// assert_approx_eq!(x[0], 0.0);
+/// assert_approx_eq!(x[1], 0.0);
+/// assert_approx_eq!(fx, 1.0);
+/// ```
+pub fn minimize<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    initial_point: Vec<T>,
+    initial_simplex_size: T,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+) -> (Vec<T>, T) {
+    let result = minimize_with_report(
+        f,
+        initial_point,
+        initial_simplex_size,
+        params,
+        bounds,
+        max_iter,
+    );
+    (result.point, result.value)
+}
+
+/// Minimizes a function `f`,
+/// starting with a simplex of size `initial_simplex_size` centered on
+/// `initial_point`, and returns an `OptimizationResult` with the best point
+/// and value, along with the diagnostics enabled in `params`.
+///
+/// The search space is bounded by a `Bounds` definition.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+/// use nelder_mead::bounds::*;
+/// use nelder_mead::params::*;
+///
+/// use assert_approx_eq::assert_approx_eq;
+/// use std::cell::Cell;
+///
+/// // minimize (x+1)^2 + y^2, probing for lower values around the result
+/// let result = minimize_with_report(
+///    |args: &[f64]| (args[0]+1.0) * (args[0]+1.0) + args[1]*args[1],
+///    vec![5.0,5.0],
+///    1.0,
+///    Params { local_minimum_probes: Some((20, 1.0)), ..Default::default() },
+///    Bounds::none(2),
+///    1000);
+///
+/// // expected minimum: f(-1, 0) = 0, which is global
+/// assert_approx_eq!(result.best_point()[0], -1.0);
+/// assert_approx_eq!(result.best_point()[1], 0.0);
+/// assert_approx_eq!(result.best_value(), 0.0);
+/// assert!(!result.likely_local_minimum());
+///
+/// // the improvement over the initial point: f(5, 5) = 61
+/// assert_eq!(result.initial_value(), 61.0);
+/// assert!(result.best_value() <= result.initial_value());
+///
+/// // with the initial point as a vertex, its value comes from the initial
+/// // simplex instead of one more evaluation
+/// let calls = Cell::new(0);
+/// let f = |args: &[f64]| {
+///     calls.set(calls.get() + 1);
+///     (args[0]+1.0) * (args[0]+1.0) + args[1]*args[1]
+/// };
+/// let params = Params { initial_point_as_vertex: true, ..Default::default() };
+/// let result = minimize_with_report(
+///    f, vec![5.0, 5.0], 1.0, params.clone(), Bounds::none(2), 100);
+/// assert_eq!(result.initial_value(), 61.0);
+/// assert_eq!(result.evaluations(), calls.get());
+///
+/// let result = minimize_with_report(
+///    f, vec![f64::NAN, 5.0], 1.0, params, Bounds::none(2), 100);
+/// assert!(result.initial_value().is_nan());
+/// ```
+pub fn minimize_with_report<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    initial_point: Vec<T>,
+    initial_simplex_size: T,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+) -> OptimizationResult<T> {
+    minimize_with_callback(
+        f,
+        initial_point,
+        initial_simplex_size,
+        params,
+        bounds,
+        max_iter,
+        |_| Action::Continue,
+    )
+}
+
+/// Minimizes a function `f` given as a trait object,
+/// starting with a simplex of size `initial_simplex_size` centered on
+/// `initial_point`, like `minimize_with_report`.
+///
+/// This suits objectives chosen at runtime, like `Box<dyn Fn(&[f64]) -> f64>`
+/// plugins, and compiles the optimizer once for all of them instead of once
+/// per closure type.
+///
+/// The search space is bounded by a `Bounds` definition.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+/// use nelder_mead::bounds::*;
+///
+/// use assert_approx_eq::assert_approx_eq;
+///
+/// let objectives: Vec<(&str, Box<dyn Fn(&[f64]) -> f64>)> = vec![
+///     ("sphere", Box::new(|args| args[0]*args[0] + args[1]*args[1])),
+///     ("shifted", Box::new(|args| (args[0]+1.0) * (args[0]+1.0) + args[1]*args[1])),
+/// ];
+/// let name = "shifted";
+/// let (_, f) = objectives.iter().find(|(n, _)| *n == name).unwrap();
+///
+/// let result = minimize_dyn(
+///    f.as_ref(),
+///    vec![5.0,5.0],
+///    1.0,
+///    Default::default(),
+///    Bounds::none(2),
+///    1000);
+///
+/// // expected minimum: f(-1, 0) = 0
+/// assert_approx_eq!(result.best_point()[0], -1.0);
+/// assert_approx_eq!(result.best_point()[1], 0.0);
+/// assert_approx_eq!(result.best_value(), 0.0);
+/// ```
+pub fn minimize_dyn<T: Float>(
+    f: &dyn Fn(&[T]) -> T,
+    initial_point: Vec<T>,
+    initial_simplex_size: T,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+) -> OptimizationResult<T> {
+    minimize_with_report(
+        |x: &[T]| f(x),
+        initial_point,
+        initial_simplex_size,
+        params,
+        bounds,
+        max_iter,
+    )
+}
+
+/// Minimizes a function `f`,
+/// starting with a simplex of size `initial_simplex_size` centered on
+/// `initial_point`, calling `callback` after every iteration.
+///
+/// The callback can stop the optimization early by returning
+/// `Action::Abort`.
+///
+/// The search space is bounded by a `Bounds` definition, which the callback
+/// can replace by returning `Action::SetBounds`.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+/// use nelder_mead::bounds::*;
+/// use nelder_mead::callback::*;
+///
+/// use assert_approx_eq::assert_approx_eq;
+///
+/// // minimize (x+1)^2 + y^2, restricting x to [0, 10] after 50 iterations
+/// let result = minimize_with_callback(
+///    |args: &[f64]| (args[0]+1.0) * (args[0]+1.0) + args[1]*args[1],
+///    vec![5.0,5.0],
+///    1.0,
+///    Default::default(),
+///    Bounds::none(2),
+///    1000,
+///    |info| {
+///        if info.iteration == 50 {
+///            Action::SetBounds(Bounds {min: vec![0.0, -10.0], max: vec![10.0, 10.0]})
+///        } else {
+///            Action::Continue
+///        }
+///    });
+///
+/// // expected bounded minimum: f(0, 0) = 1
+/// assert_approx_eq!(result.best_point()[0], 0.0);
+/// assert_approx_eq!(result.best_point()[1], 0.0);
+/// assert_approx_eq!(result.best_value(), 1.0);
+/// ```
+pub fn minimize_with_callback<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    initial_point: Vec<T>,
+    initial_simplex_size: T,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+    callback: impl FnMut(&IterationInfo<T>) -> Action<T>,
+) -> OptimizationResult<T> {
+    let (initial_simplex, initial_value) =
+        simplex_around(&f, initial_point.clone(), initial_simplex_size, &params);
+    let record_evaluations = params.record_evaluations;
+    let mut result = crate::simplex::minimize_with_callback(
+        &f,
+        initial_simplex,
+        params,
+        bounds,
+        max_iter,
+        callback,
+    );
+    report_initial_value(
+        &f,
+        &mut result,
+        initial_point,
+        initial_value,
+        record_evaluations,
+    );
+    result
+}
+
+/// Minimizes a function `f`,
+/// starting with a simplex of size `initial_simplex_size` centered on
+/// `initial_point`, until `stop` returns `true`.
+///
+/// `stop` is called after every iteration with the index of that iteration
+/// and the current simplex, sorted from best to worst vertex. It adds to the
+/// stopping conditions of `Params` and `max_iter`, and whichever is met
+/// first stops the optimization, with `TerminationReason::Aborted` when it
+/// is `stop`.
+///
+/// The search space is bounded by a `Bounds` definition.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+/// use nelder_mead::bounds::*;
+/// use nelder_mead::result::*;
+///
+/// // minimize (x+1)^2 + y^2, until it is below 1e-3
+/// let result = minimize_until(
+///    |args| (args[0]+1.0) * (args[0]+1.0) + args[1]*args[1],
+///    vec![5.0,5.0],
+///    1.0,
+///    Default::default(),
+///    Bounds::none(2),
+///    1000,
+///    |_, simplex| simplex[0].1 < 1e-3);
+///
+/// assert_eq!(result.termination(), TerminationReason::Aborted);
+/// assert!(result.best_value() < 1e-3);
+/// assert!(result.iterations() < 1000);
+/// ```
+pub fn minimize_until<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    initial_point: Vec<T>,
+    initial_simplex_size: T,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+    mut stop: impl FnMut(u32, &[(Vec<T>, T)]) -> bool,
+) -> OptimizationResult<T> {
+    minimize_with_callback(
+        f,
+        initial_point,
+        initial_simplex_size,
+        params,
+        bounds,
+        max_iter,
+        |info| {
+            if stop(info.iteration, info.simplex) {
+                Action::Abort
+            } else {
+                Action::Continue
+            }
+        },
+    )
+}
+
+/// Minimizes a fallible function `f`,
+/// starting with a simplex of size `initial_simplex_size` centered on
+/// `initial_point`.
+///
+/// An `Err` from `f` is handled as set by `on_error`: either as a point
+/// worse than any other, or by stopping with that error. Errors never
+/// reach the simplex as sentinel values that distort its geometry.
+///
+/// The failed points are remembered, so a failure is never mistaken for a
+/// `NaN` returned by `f`. If the result's point failed, the best vertex of
+/// the final simplex that didn't fail is returned instead, and if every
+/// vertex failed, the error of the result's point is returned, without
+/// evaluating `f` again.
+///
+/// The search space is bounded by a `Bounds` definition.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+/// use nelder_mead::bounds::*;
+/// use nelder_mead::params::*;
+///
+/// use assert_approx_eq::assert_approx_eq;
+///
+/// // minimize (x+1)^2 + y^2, which can only be evaluated for x >= 0
+/// let f = |args: &[f64]| {
+///     if args[0] < 0.0 {
+///         Err("diverged")
+///     } else {
+///         Ok((args[0]+1.0) * (args[0]+1.0) + args[1]*args[1])
+///     }
+/// };
+/// let params = Params { axis_aligned_simplex: true, ..Default::default() };
+///
+/// let result = minimize_fallible(
+///    f,
+///    vec![5.0,5.0],
+///    1.0,
+///    params.clone(),
+///    Bounds::none(2),
+///    1000,
+///    ErrorPolicy::Worst).unwrap();
+/// assert_approx_eq!(result.best_point()[0], 0.0);
+/// assert_approx_eq!(result.best_point()[1], 0.0);
+/// assert_approx_eq!(result.best_value(), 1.0);
+///
+/// let result = minimize_fallible(
+///    f,
+///    vec![5.0,5.0],
+///    1.0,
+///    params.clone(),
+///    Bounds::none(2),
+///    1000,
+///    ErrorPolicy::Abort);
+/// assert_eq!(result.map(|result| result.best_value()), Err("diverged"));
+///
+/// // only the last evaluation fails, so the best vertex that didn't is kept
+/// let sphere = |args: &[f64]| args[0]*args[0] + args[1]*args[1];
+/// let calls = std::cell::Cell::new(0);
+/// let failing_at = |last: u32| {
+///     let calls = &calls;
+///     move |args: &[f64]| {
+///         calls.set(calls.get() + 1);
+///         if calls.get() == last { Err("diverged") } else { Ok(sphere(args)) }
+///     }
+/// };
+/// minimize_fallible(
+///    failing_at(0),
+///    vec![5.0,5.0],
+///    1.0,
+///    params.clone(),
+///    Bounds::none(2),
+///    100,
+///    ErrorPolicy::Worst).unwrap();
+/// let last = calls.replace(0);
+/// let result = minimize_fallible(
+///    failing_at(last),
+///    vec![5.0,5.0],
+///    1.0,
+///    params,
+///    Bounds::none(2),
+///    100,
+///    ErrorPolicy::Worst).unwrap();
+/// assert_eq!(calls.get(), last);
+/// assert_eq!(result.best_value(), sphere(result.best_point()));
+///
+/// // every vertex failed, since every point fails
+/// let result = minimize_fallible(
+///    |_| Err::<f64, _>("diverged"),
+///    vec![1.0],
+///    1.0,
+///    Default::default(),
+///    Bounds::none(1),
+///    10,
+///    ErrorPolicy::Worst);
+/// assert_eq!(result.err(), Some("diverged"));
+/// ```
+pub fn minimize_fallible<T: Float, E>(
+    f: impl Fn(&[T]) -> Result<T, E>,
+    initial_point: Vec<T>,
+    initial_simplex_size: T,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+    on_error: ErrorPolicy,
+) -> Result<OptimizationResult<T>, E> {
+    let key = |x: &[T]| x.iter().map(|c| c.as_f64().to_bits()).collect::<Vec<u64>>();
+    let aborted = std::cell::RefCell::new(None);
+    let failures = std::cell::RefCell::new(std::collections::HashMap::new());
+    let mut result = minimize_with_callback(
+        |x| {
+            if aborted.borrow().is_some() {
+                return T::nan();
+            }
+            match f(x) {
+                Ok(value) => value,
+                Err(e) => {
+                    if on_error == ErrorPolicy::Abort {
+                        *aborted.borrow_mut() = Some(e);
+                    } else {
+                        failures.borrow_mut().insert(key(x), e);
+                    }
+                    // sorted after every other value, including +inf
+                    T::nan()
+                }
+            }
+        },
+        initial_point,
+        initial_simplex_size,
+        params,
+        bounds,
+        max_iter,
+        |_| {
+            if aborted.borrow().is_some() {
+                Action::Abort
+            } else {
+                Action::Continue
+            }
+        },
+    );
+    if let Some(e) = aborted.into_inner() {
+        return Err(e);
+    }
+    let mut failures = failures.into_inner();
+    if let Some(e) = failures.remove(&key(&result.point)) {
+        // like the failed centroid that the final check may pick
+        match result
+            .final_simplex
+            .iter()
+            .find(|(x, _)| !failures.contains_key(&key(x)) && *x != result.point)
+        {
+            Some((x, fx)) => {
+                result.point = x.clone();
+                result.value = *fx;
+                result.feasible = result.value.is_finite()
+                    && result
+                        .point
+                        .iter()
+                        .zip(result.bounds.as_vec())
+                        .all(|(x, (min, max))| min <= *x && *x <= max);
+            }
+            None => return Err(e),
+        }
+    }
+    Ok(result)
+}
+
+/// Minimizes a function `f` like `minimize_with_callback`, threading a
+/// mutable `state` through the callback calls and returning its final value
+/// along with the result.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+/// use nelder_mead::bounds::*;
+/// use nelder_mead::callback::*;
+/// use nelder_mead::params::*;
+///
+/// use assert_approx_eq::assert_approx_eq;
+/// use std::cell::RefCell;
+///
+/// let f = |args: &[f64]| (args[0]+1.0) * (args[0]+1.0) + args[1]*args[1];
+/// let params = Params { axis_aligned_simplex: true, ..Default::default() };
+///
+/// // sum the best values, and count the iterations
+/// let (result, (total, count)) = minimize_with_state(
+///    f,
+///    vec![5.0,5.0],
+///    1.0,
+///    params.clone(),
+///    Bounds::none(2),
+///    100,
+///    (0.0, 0),
+///    |(total, count), info| {
+///        *total += info.simplex[0].1;
+///        *count += 1;
+///        Action::Continue
+///    });
+///
+/// let best_values = RefCell::new(Vec::new());
+/// minimize_with_callback(f, vec![5.0,5.0], 1.0, params, Bounds::none(2), 100, |info| {
+///     best_values.borrow_mut().push(info.simplex[0].1);
+///     Action::Continue
+/// });
+/// assert_eq!(count, result.iterations());
+/// assert_approx_eq!(total, best_values.borrow().iter().sum::<f64>());
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn minimize_with_state<T: Float, S>(
+    f: impl Fn(&[T]) -> T,
+    initial_point: Vec<T>,
+    initial_simplex_size: T,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+    mut state: S,
+    mut callback: impl FnMut(&mut S, &IterationInfo<T>) -> Action<T>,
+) -> (OptimizationResult<T>, S) {
+    let result = minimize_with_callback(
+        f,
+        initial_point,
+        initial_simplex_size,
+        params,
+        bounds,
+        max_iter,
+        |info| callback(&mut state, info),
+    );
+    (result, state)
+}
+
+/// Minimizes a function `f`,
+/// starting with a simplex of size `initial_simplex_size` centered on
+/// `initial_point`, after running the sanity checks enabled in `params`.
+///
+/// Fails with `NelderMeadError::DimensionMismatch` unless the bounds have
+/// as many dimensions as `initial_point`, and with
+/// `NelderMeadError::EmptyBounds` if the minimum of a dimension is above its
+/// maximum. Fails with `NelderMeadError::NonDeterministicObjective` if
+/// `params.check_determinism` is set and evaluating `f` twice at
+/// `initial_point` gives different values.
+///
+/// The search space is bounded by a `Bounds` definition.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+/// use nelder_mead::bounds::*;
+/// use nelder_mead::error::*;
+/// use nelder_mead::params::*;
+///
+/// use std::cell::Cell;
+///
+/// // an objective that accidentally depends on how many times it was called
+/// let calls = Cell::new(0);
+/// let result = try_minimize(
+///    |args| {
+///        calls.set(calls.get() + 1);
+///        args[0]*args[0] + args[1]*args[1] + calls.get() as f64
+///    },
+///    vec![5.0,5.0],
+///    1.0,
+///    Params { check_determinism: Some(1e-12), ..Default::default() },
+///    Bounds::none(2),
+///    1000);
+///
+/// assert!(matches!(result, Err(NelderMeadError::NonDeterministicObjective { .. })));
+///
+/// // bounds with a dimension too many
+/// let result = try_minimize(
+///    |args| args[0]*args[0] + args[1]*args[1],
+///    vec![5.0,5.0],
+///    1.0,
+///    Default::default(),
+///    Bounds::none(3),
+///    1000);
+///
+/// assert_eq!(result, Err(NelderMeadError::DimensionMismatch { expected: 2, found: 3 }));
+/// ```
+pub fn try_minimize<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    initial_point: Vec<T>,
+    initial_simplex_size: T,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+) -> Result<(Vec<T>, T), NelderMeadError> {
+    bounds.validate(initial_point.len())?;
+    if let Some(epsilon) = params.check_determinism {
+        check_determinism(&f, &initial_point, epsilon)?;
+    }
+    Ok(minimize(
+        f,
+        initial_point,
+        initial_simplex_size,
+        params,
+        bounds,
+        max_iter,
+    ))
+}
+
+/// Maximizes a function `f`,
+/// starting with a simplex of size `initial_simplex_size` centered on
+/// `initial_point`.
+///
+/// The search space is bounded by a `Bounds` definition.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+/// use nelder_mead::bounds::*;
+///
+/// use assert_approx_eq::assert_approx_eq;
+///
+/// // maximize -2.0 * ((x+1)^2 + y^2)
+/// let (x, fx) = maximize(
+///    |args: &[f64]| -2.0 * ((args[0]+1.0) * (args[0]+1.0) + args[1]*args[1]),
+///    vec![5.0,5.0],
+///    1.0,
+///    Default::default(),
+///    Bounds {min: vec![0.0, 0.0], max: vec![10.0, 10.0]},
+///    1000);
+///
+/// // expected bounded maximum: f(0, 0) = -2
+/// assert_approx_eq!(x[0], 0.0);
+/// assert_approx_eq!(x[1], 0.0);
+/// assert_approx_eq!(fx, -2.0);
+/// ```
+pub fn maximize<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    initial_point: Vec<T>,
+    initial_simplex_size: T,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+) -> (Vec<T>, T) {
+    let g = |x: &[T]| -f(x);
+    let (initial_simplex, _) = simplex_around(g, initial_point, initial_simplex_size, &params);
+    let result = crate::simplex::minimize(g, initial_simplex, params, bounds, max_iter);
+    (result.point, -result.value)
+}
+
+/// Minimizes a function `f`,
+/// starting with a simplex of size `initial_simplex_size` centered on
+/// `initial_point`.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+/// use nelder_mead::bounds::*;
+///
+/// use assert_approx_eq::assert_approx_eq;
+///
+/// // minimize (x+1)^2 + y^2
+/// let (x, fx) = minimize_unbounded(
+///    |args: &[f64]| (args[0]+1.0) * (args[0]+1.0) + args[1]*args[1],
+///    vec![5.0,5.0],
+///    1.0,
+///    Default::default(),
+///    1000);
+///
+/// // expected bounded minimum: f(-1, 0) = 0
+/// assert_approx_eq!(x[0], -1.0);
+/// assert_approx_eq!(x[1], 0.0);
+/// assert_approx_eq!(fx, 0.0);
+/// ```
+pub fn minimize_unbounded<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    initial_point: Vec<T>,
+    initial_simplex_size: T,
+    params: Params<T>,
+    max_iter: u32,
+) -> (Vec<T>, T) {
+    let bounds = Bounds::none(initial_point.len());
+    minimize(
+        f,
+        initial_point,
+        initial_simplex_size,
+        params,
+        bounds,
+        max_iter,
+    )
+}
+
+/// Maximizes a function `f`,
+/// starting with a simplex of size `initial_simplex_size` centered on
+/// `initial_point`.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+/// use nelder_mead::bounds::*;
+///
+/// use assert_approx_eq::assert_approx_eq;
+///
+/// // maximize -2.0 * ((x+1)^2 + y^2)
+/// let (x, fx) = maximize_unbounded(
+///    |args: &[f64]| -2.0 * ((args[0]+1.0) * (args[0]+1.0) + args[1]*args[1]),
+///    vec![5.0,5.0],
+///    1.0,
+///    Default::default(),
+///    1000);
+///
+/// // expected bounded maximum: f(-1, 0) = 0
+/// assert_approx_eq!(x[0], -1.0);
+/// assert_approx_eq!(x[1], 0.0);
+/// assert_approx_eq!(fx, 0.0);
+/// ```
+pub fn maximize_unbounded<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    initial_point: Vec<T>,
+    initial_simplex_size: T,
+    params: Params<T>,
+    max_iter: u32,
+) -> (Vec<T>, T) {
+    let bounds = Bounds::none(initial_point.len());
+    maximize(
+        f,
+        initial_point,
+        initial_simplex_size,
+        params,
+        bounds,
+        max_iter,
+    )
+}
+
+/// Minimizes a function `f`,
+/// starting from the vertices of a precomputed `initial_simplex`.
+///
+/// The simplex geometry is evaluated under `f` on each call, so the same
+/// `InitialSimplex` can be reused to optimize several objectives.
+///
+/// The search space is bounded by a `Bounds` definition.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+/// use nelder_mead::bounds::*;
+/// use nelder_mead::initial_simplex::*;
+///
+/// use assert_approx_eq::assert_approx_eq;
+///
+/// let initial_simplex = InitialSimplex::new(vec![5.0, 5.0], 1.0);
+///
+/// // minimize (x+1)^2 + y^2 and (x-1)^2 + y^2 from the same starting geometry
+/// let (x, fx) = minimize_from_simplex(
+///    |args: &[f64]| (args[0]+1.0) * (args[0]+1.0) + args[1]*args[1],
+///    &initial_simplex,
+///    Default::default(),
+///    Bounds::none(2),
+///    1000);
+/// let (y, fy) = minimize_from_simplex(
+///    |args| (args[0]-1.0) * (args[0]-1.0) + args[1]*args[1],
+///    &initial_simplex,
+///    Default::default(),
+///    Bounds::none(2),
+///    1000);
+///
+/// // expected minima: f(-1, 0) = 0 and g(1, 0) = 0
+/// assert_approx_eq!(x[0], -1.0);
+/// assert_approx_eq!(x[1], 0.0);
+/// assert_approx_eq!(fx, 0.0);
+/// assert_approx_eq!(y[0], 1.0);
+/// assert_approx_eq!(y[1], 0.0);
+/// assert_approx_eq!(fy, 0.0);
+/// ```
+pub fn minimize_from_simplex<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    initial_simplex: &InitialSimplex<T>,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+) -> (Vec<T>, T) {
+    let initial_simplex = evaluate_initial_simplex(&f, initial_simplex, &params);
+    let result = crate::simplex::minimize(&f, initial_simplex, params, bounds, max_iter);
+    (result.point, result.value)
+}
+
+/// Minimizes a function `f`, starting around the result of a `previous`
+/// optimization, such as one of a slightly different objective.
+///
+/// The new simplex is axis-aligned around the best point of `previous`,
+/// stepping along each axis by the extent of the previous final simplex
+/// along it, so it keeps the local scale learned by that optimization. The
+/// steps are at least `min_size`, since a converged simplex is usually too
+/// small to follow a moved minimum, and may even meet the tolerances of
+/// `Params` right away. Unlike the final simplex itself, which tends to
+/// flatten while converging, the new one is never degenerate.
+///
+/// Only the spread of the previous final simplex is used: its vertices and
+/// values aren't reused, since the objective may have changed, so every new
+/// vertex is evaluated. A step that would leave the bounds is taken
+/// backwards instead, or up to the farthest bound if both directions leave
+/// them.
+///
+/// The search space is bounded by a `Bounds` definition.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+/// use nelder_mead::bounds::*;
+/// use nelder_mead::params::*;
+///
+/// // fit (a, b) to data that changes slightly between two fits
+/// let fit = |a: f64, b: f64| move |args: &[f64]| (args[0]-a).powi(2) + 10.0 * (args[1]-b).powi(2);
+/// let params = Params { f_atol: 1e-12, axis_aligned_simplex: true, ..Default::default() };
+///
+/// let first = minimize_with_report(
+///    fit(1.0, 2.0), vec![5.0, 5.0], 1.0, params.clone(), Bounds::none(2), 1000);
+/// let fresh = minimize_with_report(
+///    fit(1.1, 2.05), vec![5.0, 5.0], 1.0, params.clone(), Bounds::none(2), 1000);
+/// let warm = minimize_warm_start(
+///    fit(1.1, 2.05), &first, 0.1, params.clone(), Bounds::none(2), 1000);
+///
+/// assert!((warm.best_point()[0] - 1.1).abs() < 1e-4);
+/// assert!((warm.best_point()[1] - 2.05).abs() < 1e-4);
+/// assert!(warm.iterations() < fresh.iterations());
+///
+/// // a fit that ends on a bound is continued within the bounds
+/// let bounds = Bounds { min: vec![0.0, 0.0], max: vec![1.0, 3.0] };
+/// let first = minimize_with_report(
+///    fit(2.0, 2.0), vec![0.5, 0.5], 0.2, params.clone(), bounds.clone(), 1000);
+/// assert_eq!(first.best_point()[0], 1.0);
+///
+/// let in_bounds = |args: &[f64]| args[0] <= 1.0 && args[1] <= 3.0;
+/// let warm = minimize_warm_start(
+///    |args| {
+///        assert!(in_bounds(args));
+///        fit(2.1, 2.05)(args)
+///    },
+///    &first,
+///    0.1,
+///    params,
+///    bounds,
+///    1000);
+/// assert_eq!(warm.best_point()[0], 1.0);
+/// ```
+pub fn minimize_warm_start<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    previous: &OptimizationResult<T>,
+    min_size: T,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+) -> OptimizationResult<T> {
+    let center = &previous.point;
+    let bounds_vec = bounds.as_vec();
+    let mut points = vec![center.clone()];
+    for i in 0..center.len() {
+        let step = previous
+            .final_simplex
+            .iter()
+            .map(|(x, _)| (x[i] - center[i]).abs())
+            .fold(min_size, T::max);
+        let mut point = center.clone();
+        point[i] = step_within_bounds(center[i], step, bounds_vec[i]);
+        points.push(point);
+    }
+    let (initial_simplex, initial_value) = evaluate_initial_simplex_from_first(
+        |xs| xs.iter().map(|x| f(x)).collect(),
+        &InitialSimplex { points },
+        &params,
+    );
+    let mut result = crate::simplex::minimize(&f, initial_simplex, params, bounds, max_iter);
+    result.initial_point = center.clone();
+    result.initial_value = initial_value;
+    result
+}
+
+/// Minimizes a function `f` subject to `g(x) <= 0` for every `g` in
+/// `constraints`, using a logarithmic barrier.
+///
+/// The barrier `-mu * sum(log(-g(x)))` is added to `f` and the optimization
+/// is repeated for every `mu` in the decreasing `mu_schedule`, each stage
+/// starting with a simplex of size `initial_simplex_size` that has the
+/// result of the previous one as a vertex. The first stage starts from
+/// `initial_point`, which must strictly satisfy every constraint.
+///
+/// The search stays in the interior of the feasible region, so the result's
+/// point strictly satisfies every constraint and its value is `f` without
+/// the barrier. The evaluations of `f` without the barrier, at the initial
+/// point and at the result, are counted in `evaluations`.
+///
+/// The search space is bounded by a `Bounds` definition.
+///
+/// # Panics
+///
+/// Panics if `mu_schedule` is empty, or if `initial_point` doesn't strictly
+/// satisfy every constraint, since the barrier is infinite there.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+/// use nelder_mead::bounds::*;
+///
+/// use assert_approx_eq::assert_approx_eq;
+///
+/// // minimize (x-2)^2 + (y-2)^2 subject to x + y <= 2
+/// let constraint: &Function = &(|args| args[0] + args[1] - 2.0);
+/// let result = minimize_with_barrier(
+///    |args| (args[0]-2.0) * (args[0]-2.0) + (args[1]-2.0) * (args[1]-2.0),
+///    &[constraint],
+///    &[1.0, 1e-2, 1e-4, 1e-6],
+///    vec![0.0, 0.0],
+///    0.5,
+///    Default::default(),
+///    Bounds::none(2),
+///    1000);
+///
+/// // expected minimum on the boundary, approached from the interior: f(1, 1) = 2
+/// assert!(result.best_point()[0] + result.best_point()[1] < 2.0);
+/// assert_approx_eq!(result.best_point()[0], 1.0, 1e-2);
+/// assert_approx_eq!(result.best_point()[1], 1.0, 1e-2);
+/// assert_approx_eq!(result.best_value(), 2.0, 1e-3);
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn minimize_with_barrier<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    constraints: &[&Function<T>],
+    mu_schedule: &[T],
+    initial_point: Vec<T>,
+    initial_simplex_size: T,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+) -> OptimizationResult<T> {
+    assert!(!mu_schedule.is_empty(), "The mu schedule must not be empty");
+    assert!(
+        constraints.iter().all(|g| g(&initial_point) < T::zero()),
+        "The initial point must strictly satisfy every constraint"
+    );
+    let initial_value = f(&initial_point);
+    let mut point = initial_point.clone();
+    let mut iterations = 0;
+    // the evaluations of `f` without the barrier, at the initial point and
+    // at the result
+    let mut evaluations = 2;
+    let mut result = None;
+    for &mu in mu_schedule {
+        // keep the feasible center as a vertex, so the simplex is never
+        // entirely outside the feasible region
+        let mut initial_simplex =
+            InitialSimplex::from_params(point.clone(), initial_simplex_size, &params);
+        initial_simplex.points[0] = point;
+        let g = barrier(&f, constraints, mu);
+        let stage = crate::simplex::minimize(
+            &g,
+            evaluate_initial_simplex(&g, &initial_simplex, &params),
+            params.clone(),
+            bounds.clone(),
+            max_iter,
+        );
+        iterations += stage.iterations;
+        evaluations += stage.evaluations;
+        point = stage.point.clone();
+        result = Some(stage);
+    }
+    let mut result = result.unwrap();
+    result.initial_point = initial_point;
+    result.initial_value = initial_value;
+    result.value = f(&result.point);
+    result.iterations = iterations;
+    result.evaluations = evaluations;
+    result
+}
+
+/// Minimizes a function `f` subject to `g(x) <= 0` for every `g` in
+/// `constraints`, using a penalty.
+///
+/// The penalty `weight * sum(max(g(x), 0))`, or its square with
+/// `PenaltyGrowth::Quadratic`, is added to `f`, which pushes the simplex
+/// back into the feasible region. Unlike `minimize_with_barrier`, the
+/// search may leave the feasible region, so `initial_point` needs not
+/// satisfy the constraints, and the result may slightly violate them when
+/// the weight is too low. The result's values, including the ones of the
+/// final simplex and of the evaluation history, are `f` without the
+/// penalty, and it is only feasible if every constraint is satisfied.
+///
+/// The search starts with a simplex of size `initial_simplex_size` centered
+/// on `initial_point`, and the search space is bounded by a `Bounds`
+/// definition.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+/// use nelder_mead::bounds::*;
+///
+/// use assert_approx_eq::assert_approx_eq;
+///
+/// // minimize (x-2)^2 + (y-2)^2 inside the unit circle
+/// let circle: &Function = &(|args| args[0] * args[0] + args[1] * args[1] - 1.0);
+/// let f = |args: &[f64]| (args[0]-2.0) * (args[0]-2.0) + (args[1]-2.0) * (args[1]-2.0);
+/// let result = minimize_with_penalty(
+///    f,
+///    &[circle],
+///    1e6,
+///    PenaltyGrowth::Quadratic,
+///    vec![0.0, 0.0],
+///    0.5,
+///    Default::default(),
+///    Bounds::none(2),
+///    1000);
+///
+/// // expected minimum on the circle: f(1/sqrt(2), 1/sqrt(2)) = 9 - 4 sqrt(2)
+/// assert_approx_eq!(result.best_point()[0], 0.5f64.sqrt(), 1e-4);
+/// assert_approx_eq!(result.best_point()[1], 0.5f64.sqrt(), 1e-4);
+/// assert_approx_eq!(result.best_value(), 9.0 - 4.0 * 2.0f64.sqrt(), 1e-3);
+/// // the final simplex holds the values without the penalty too
+/// for (x, value) in result.final_simplex() {
+///     assert_approx_eq!(*value, f(x), 1e-9);
+/// }
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn minimize_with_penalty<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    constraints: &[&Function<T>],
+    weight: T,
+    growth: PenaltyGrowth,
+    initial_point: Vec<T>,
+    initial_simplex_size: T,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+) -> OptimizationResult<T> {
+    let g = penalty(&f, constraints, weight, growth);
+    let mut result = minimize_with_report(
+        &g,
+        initial_point,
+        initial_simplex_size,
+        params,
+        bounds,
+        max_iter,
+    );
+    // the penalty only depends on the constraints, so it is taken back out
+    // of the recorded values without calling `f` again
+    let unpenalized = |(x, value): (Vec<T>, T)| match penalty_at(&x, constraints, weight, growth) {
+        Some(penalty) if penalty > T::zero() && penalty.is_finite() => (x, value - penalty),
+        _ => (x, value),
+    };
+    result.final_simplex = result.final_simplex.into_iter().map(unpenalized).collect();
+    sort_simplex(&mut result.final_simplex);
+    result.value_spread = match (result.final_simplex.first(), result.final_simplex.last()) {
+        (Some(best), Some(worst)) => worst.1 - best.1,
+        _ => T::zero(),
+    };
+    result.evaluation_history = result
+        .evaluation_history
+        .into_iter()
+        .map(unpenalized)
+        .collect();
+    result.value = f(&result.point);
+    result.initial_value = f(&result.initial_point);
+    result.evaluations += 2;
+    result.feasible &= constraints.iter().all(|g| g(&result.point) <= T::zero());
+    result
+}
+
+/// Minimizes a function `f` over the points that satisfy a constraint,
+/// given by a `projection` onto the feasible set, and the bounds.
+///
+/// Every point is moved to the feasible set before being evaluated, by
+/// alternating the projection and the clamping to the bounds, so `f` is
+/// only ever evaluated at feasible points. The simplex itself keeps its full
+/// dimension, its values being `f` at the feasible point plus the squared
+/// distance to it, while the result's point is feasible and its value is
+/// `f` at that point.
+///
+/// The search starts with a simplex of size `initial_simplex_size` centered
+/// on `initial_point`, and the search space is bounded by a `Bounds`
+/// definition.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+/// use nelder_mead::bounds::*;
+///
+/// use assert_approx_eq::assert_approx_eq;
+///
+/// // minimize x^2 + y^2 subject to x + y = 1
+/// let result = minimize_with_projection(
+///    |args: &[f64]| args[0]*args[0] + args[1]*args[1],
+///    |x| {
+///        let offset = (x[0] + x[1] - 1.0) / 2.0;
+///        vec![x[0] - offset, x[1] - offset]
+///    },
+///    vec![0.0, 0.0],
+///    1.0,
+///    Default::default(),
+///    Bounds::none(2),
+///    1000);
+///
+/// // expected minimum: f(0.5, 0.5) = 0.5
+/// assert_approx_eq!(result.best_point()[0], 0.5);
+/// assert_approx_eq!(result.best_point()[1], 0.5);
+/// assert_approx_eq!(result.best_value(), 0.5);
+/// ```
+pub fn minimize_with_projection<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    projection: impl Fn(&[T]) -> Vec<T>,
+    initial_point: Vec<T>,
+    initial_simplex_size: T,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+) -> OptimizationResult<T> {
+    let bounds_vec = bounds.as_vec();
+    minimize_through(
+        f,
+        |x| alternating_projection(x, &projection, &bounds_vec),
+        initial_point,
+        initial_simplex_size,
+        params,
+        bounds,
+        max_iter,
+    )
+}
+
+/// Minimizes a function `f` over the points that satisfy a `feasible`
+/// predicate, using a cheap `repair` heuristic instead of an exact
+/// projection.
+///
+/// Every point is clamped to the bounds and, if it isn't feasible, replaced
+/// by `repair` of it, clamped again, before being evaluated. `repair` must
+/// return feasible points, in which case `f` is only ever evaluated at
+/// feasible points. Like in `minimize_with_projection`, the simplex keeps
+/// its full dimension, its values being `f` at the repaired point plus the
+/// squared distance to it, while the result's point is repaired and its
+/// value is `f` at that point.
+///
+/// The search starts with a simplex of size `initial_simplex_size` centered
+/// on `initial_point`, and the search space is bounded by a `Bounds`
+/// definition.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+/// use nelder_mead::bounds::*;
+///
+/// use assert_approx_eq::assert_approx_eq;
+/// use std::cell::RefCell;
+///
+/// // minimize (x-2)^2 + (y-2)^2 over the points with x + y <= 2, repairing
+/// // infeasible points by scaling them down onto the line x + y = 2
+/// let feasible = |x: &[f64]| x[0] + x[1] <= 2.0 + 1e-12;
+/// let evaluated = RefCell::new(Vec::new());
+/// let result = minimize_with_repair(
+///    |args| {
+///        evaluated.borrow_mut().push(args.to_vec());
+///        (args[0]-2.0).powi(2) + (args[1]-2.0).powi(2)
+///    },
+///    feasible,
+///    |x| {
+///        let scale = 2.0 / (x[0] + x[1]);
+///        vec![x[0] * scale, x[1] * scale]
+///    },
+///    vec![0.25, 0.5],
+///    0.5,
+///    Default::default(),
+///    Bounds {min: vec![0.0, 0.0], max: vec![10.0, 10.0]},
+///    1000);
+///
+/// assert!(evaluated.borrow().iter().all(|x| feasible(x)));
+/// // expected minimum: f(1, 1) = 2
+/// assert_approx_eq!(result.best_point()[0], 1.0, 1e-4);
+/// assert_approx_eq!(result.best_point()[1], 1.0, 1e-4);
+/// assert_approx_eq!(result.best_value(), 2.0, 1e-4);
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn minimize_with_repair<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    feasible: impl Fn(&[T]) -> bool,
+    repair: impl Fn(&[T]) -> Vec<T>,
+    initial_point: Vec<T>,
+    initial_simplex_size: T,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+) -> OptimizationResult<T> {
+    let bounds_vec = bounds.as_vec();
+    let repaired = |x: &[T]| {
+        let x = clamp(x, &bounds_vec);
+        if feasible(&x) {
+            x
+        } else {
+            clamp(&repair(&x), &bounds_vec)
+        }
+    };
+    minimize_through(
+        f,
+        repaired,
+        initial_point,
+        initial_simplex_size,
+        params,
+        bounds,
+        max_iter,
+    )
+}
+
+/// Minimizes `f` at the points given by `to_feasible`, which must enforce
+/// the bounds, as `minimize_with_projection` and `minimize_with_repair` do.
+fn minimize_through<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    to_feasible: impl Fn(&[T]) -> Vec<T>,
+    initial_point: Vec<T>,
+    initial_simplex_size: T,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+) -> OptimizationResult<T> {
+    // the bounds are enforced by `to_feasible`, so clamping the simplex
+    // would only make it collapse against them, and the squared distance to
+    // the feasible point keeps the search from drifting where `f` is flat
+    let mut result = minimize_with_report(
+        |x| {
+            let feasible = to_feasible(x);
+            let offset = distance(x, &feasible);
+            f(&feasible) + offset * offset
+        },
+        initial_point,
+        initial_simplex_size,
+        params,
+        Bounds::none(bounds.min.len()),
+        max_iter,
+    );
+    result.point = to_feasible(&result.point);
+    result.value = f(&result.point);
+    result.initial_point = to_feasible(&result.initial_point);
+    result.initial_value = f(&result.initial_point);
+    result.evaluations += 2;
+    result.bounds = bounds;
+    result
+}
+
+/// Estimates the number of evaluations that minimizing a function of
+/// `dimensions` variables with `params` and `max_iter` would take, without
+/// calling any objective.
+///
+/// The estimate follows the structure of the iterations, for the entry
+/// points that build their initial simplex from a single point, like
+/// `minimize`. Cache hits of `Params::exact_cache` and
+/// `Params::cache_tolerance` would only lower the actual counts.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+/// use nelder_mead::bounds::*;
+/// use nelder_mead::params::*;
+///
+/// use std::cell::Cell;
+///
+/// let plan = dry_run::<f64>(2, &Default::default(), 50);
+/// assert_eq!(plan.initial, 3);
+/// assert_eq!(plan.best_case_total, 3 + 50 + 2);
+///
+/// // every new point is worse than the previous ones, so every iteration
+/// // ends with a shrink
+/// let calls = Cell::new(0);
+/// let result = minimize_with_report(
+///    |_| {
+///        calls.set(calls.get() + 1);
+///        calls.get() as f64
+///    },
+///    vec![0.0, 0.0],
+///    1.0,
+///    Params { record_step_history: true, ..Default::default() },
+///    Bounds::none(2),
+///    50);
+///
+/// assert!(result.step_history().iter().all(|kind| *kind == result::StepKind::Shrink));
+/// assert_eq!(calls.get(), plan.worst_case_total);
+/// assert_eq!(result.evaluations(), plan.worst_case_total);
+/// ```
+pub fn dry_run<T: Float>(dimensions: usize, params: &Params<T>, max_iter: u32) -> EvaluationPlan {
+    let n = dimensions as u32;
+    let shrunk = (n + 1).saturating_sub(params.shrink_keep.max(1) as u32);
+    let shrink = 2
+        + shrunk
+        + match params.detect_cycles {
+            Some(_) => shrunk,
+            None => 0,
+        };
+    let integer_dimensions = match &params.integer_refinement {
+        Some(mask) => mask.iter().filter(|integer| **integer).count() as u32,
+        None => 0,
+    };
+    let final_evaluations =
+        1 + match params.integer_refinement {
+            Some(_) if integer_dimensions as usize > MAX_GRID_SEARCH_DIMENSIONS => 1,
+            Some(_) => 3u32.saturating_pow(integer_dimensions),
+            None => 0,
+        } + match &params.snap_result_to_grid {
+            Some(spacing) => {
+                let grid_dimensions = spacing.iter().filter(|s| **s > T::zero()).count();
+                if grid_dimensions > MAX_GRID_SEARCH_DIMENSIONS {
+                    1
+                } else {
+                    3u32.saturating_pow(grid_dimensions as u32)
+                }
+            }
+            None => 0,
+        } + match params.local_minimum_probes {
+            Some((probes, _)) => probes,
+            None => 0,
+        };
+    let iterations = match params.extend_if_improving {
+        Some((_, max_extension)) => max_iter.saturating_add(max_extension),
+        None => max_iter,
+    };
+    // every automatic boundary mode probe is a short run, with its own
+    // final evaluation, and the chosen mode continues from its probe
+    let (probe_iterations, probe_final_evaluations) = match params.boundary_mode {
+        BoundaryMode::Auto(probe_iterations) => (2 * probe_iterations.min(max_iter), 3),
+        _ => (0, 0),
+    };
+    // every restart evaluates the n new vertices
+    let restarts = match params.restart_after {
+        Some(patience) => n.saturating_mul(iterations / patience.max(1)),
+        None => 0,
+    };
+    let initial = n + 1;
+    let best_case_total = initial + max_iter + final_evaluations;
+    let worst_case_total = initial
+        + shrink.saturating_mul(iterations.saturating_add(probe_iterations))
+        + probe_final_evaluations
+        + restarts
+        + final_evaluations;
+    // every point takes the samples of `Params::resample`
+    let samples = crate::evaluation::samples_per_point(params);
+    // the value at the initial point takes one more evaluation, unless it is
+    // a vertex of the initial simplex
+    let initial_value = if params.initial_point_as_vertex { 0 } else { 1 };
+    EvaluationPlan {
+        initial: initial * samples,
+        reflection: samples,
+        expansion: 2 * samples,
+        contraction: 2 * samples,
+        shrink: shrink.saturating_mul(samples),
+        final_evaluations: final_evaluations
+            .saturating_mul(samples)
+            .saturating_add(initial_value),
+        max_iterations: iterations.saturating_add(probe_iterations),
+        best_case_total: best_case_total
+            .saturating_mul(samples)
+            .saturating_add(initial_value),
+        worst_case_total: worst_case_total
+            .saturating_mul(samples)
+            .saturating_add(initial_value)
+            .min(params.max_evaluations.unwrap_or(u32::MAX))
+            .min(params.max_distinct_evals.map_or(u32::MAX, |max| {
+                max.saturating_mul(samples as u64).min(u32::MAX as u64) as u32
+            })),
+    }
+}
+
+/// Minimizes a function `f`, restarting the optimization `restarts` times
+/// from the best point found so far.
+///
+/// Every run starts with a simplex whose edges have length
+/// `initial_simplex_size`. The first one is aligned with the axes and so are
+/// the restarts, unless a `rotation_seed` is given, in which case restart
+/// `i` is rotated by a random orthonormal basis seeded with
+/// `rotation_seed + i`. Rotating can escape stalls of a simplex that is
+/// degenerate along the axes.
+///
+/// Returns the best result, with the iterations and evaluations of all the
+/// runs.
+///
+/// The search space is bounded by a `Bounds` definition.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+/// use nelder_mead::bounds::*;
+///
+/// use assert_approx_eq::assert_approx_eq;
+///
+/// // minimize (x+1)^2 + y^2
+/// let result = minimize_with_restarts(
+///    |args: &[f64]| (args[0]+1.0) * (args[0]+1.0) + args[1]*args[1],
+///    vec![5.0, 5.0],
+///    1.0,
+///    Default::default(),
+///    Bounds::none(2),
+///    1000,
+///    2,
+///    Some(42));
+///
+/// // expected minimum: f(-1, 0) = 0
+/// assert_approx_eq!(result.best_point()[0], -1.0);
+/// assert_approx_eq!(result.best_point()[1], 0.0);
+/// assert_approx_eq!(result.best_value(), 0.0);
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn minimize_with_restarts<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    initial_point: Vec<T>,
+    initial_simplex_size: T,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+    restarts: u32,
+    rotation_seed: Option<u64>,
+) -> OptimizationResult<T> {
+    let mut iterations = 0;
+    let mut evaluations = 0;
+    let mut best: Option<OptimizationResult<T>> = None;
+    for restart in 0..=restarts {
+        let point = best
+            .as_ref()
+            .map_or(initial_point.clone(), |best| best.point.clone());
+        let initial_simplex = match rotation_seed {
+            Some(seed) if restart > 0 => InitialSimplex::rotated(
+                point,
+                initial_simplex_size,
+                seed.wrapping_add(restart as u64),
+            ),
+            _ => InitialSimplex::axis_aligned(point, initial_simplex_size),
+        };
+        let run = crate::simplex::minimize(
+            &f,
+            evaluate_initial_simplex(&f, &initial_simplex, &params),
+            params.clone(),
+            bounds.clone(),
+            max_iter,
+        );
+        iterations += run.iterations;
+        evaluations += run.evaluations;
+        best = match best {
+            Some(best) if best.value <= run.value => Some(best),
+            _ => Some(run),
+        };
+    }
+    let mut result = best.expect("At least one run is always performed");
+    result.initial_point = initial_point;
+    result.initial_value = f(&result.initial_point);
+    result.iterations = iterations;
+    // along with the evaluation of the initial value
+    result.evaluations = evaluations + 1;
+    result
+}
+
+/// Minimizes the largest absolute value of the `residuals`,
+/// starting with a simplex of size `initial_simplex_size` centered on
+/// `initial_point`.
+///
+/// This fits in the minimax (Chebyshev) sense, minimizing the worst-case
+/// residual instead of the sum of squares. Returns the result along with
+/// the residuals at its point.
+///
+/// A `NaN` residual makes the value of the point `NaN`, which ranks it after
+/// every other point, instead of being skipped by the maximum.
+///
+/// The search space is bounded by a `Bounds` definition.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+/// use nelder_mead::bounds::*;
+///
+/// use assert_approx_eq::assert_approx_eq;
+///
+/// // fit a constant to [0, 0, 0, 10]
+/// let data = vec![0.0, 0.0, 0.0, 10.0];
+/// let (result, residuals) = minimize_minimax(
+///    |args: &[f64]| data.iter().map(|y| y - args[0]).collect(),
+///    vec![1.0],
+///    1.0,
+///    Default::default(),
+///    Bounds::none(1),
+///    1000);
+///
+/// // expected minimax fit: the midrange 5, with a maximum residual of 5
+/// // (the least squares fit would be the mean 2.5, with a maximum residual of 7.5)
+/// assert_approx_eq!(result.best_point()[0], 5.0);
+/// assert_approx_eq!(result.best_value(), 5.0);
+/// assert_approx_eq!(residuals[0], -5.0);
+/// assert_approx_eq!(residuals[3], 5.0);
+///
+/// // a NaN residual is never mistaken for a small one
+/// let (result, _) = minimize_minimax(
+///    |_| vec![f64::NAN, 1.0], vec![1.0], 1.0, Default::default(), Bounds::none(1), 10);
+/// assert!(result.best_value().is_nan());
+/// ```
+pub fn minimize_minimax<T: Float>(
+    residuals: impl Fn(&[T]) -> Vec<T>,
+    initial_point: Vec<T>,
+    initial_simplex_size: T,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+) -> (OptimizationResult<T>, Vec<T>) {
+    let result = minimize_with_report(
+        |x| {
+            // `f64::max` skips `NaN`, so it is propagated by hand
+            residuals(x).iter().fold(T::zero(), |max: T, r| {
+                if max.is_nan() || r.is_nan() {
+                    T::nan()
+                } else {
+                    max.max(r.abs())
+                }
+            })
+        },
+        initial_point,
+        initial_simplex_size,
+        params,
+        bounds,
+        max_iter,
+    );
+    let final_residuals = residuals(&result.point);
+    (result, final_residuals)
+}
+
+/// Minimizes the weighted sum of squares of the `residuals`,
+/// `sum(weights[i] * residuals[i]^2)`, starting with a simplex of size
+/// `initial_simplex_size` centered on `initial_point`.
+///
+/// The weights are usually the inverse variances of the measurements, so
+/// that the more precise ones weigh more on the fit. Returns the result,
+/// whose value is the weighted sum of squares, along with the residuals at
+/// its point.
+///
+/// Fails unless there is one weight per residual at `initial_point`.
+///
+/// The search space is bounded by a `Bounds` definition.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+/// use nelder_mead::bounds::*;
+///
+/// use assert_approx_eq::assert_approx_eq;
+///
+/// // fit a constant to [0, 10], trusting the second measurement more
+/// let data = vec![0.0, 10.0];
+/// let fit = |weights: Vec<f64>| {
+///     minimize_weighted_least_squares(
+///        |args| data.iter().map(|y| y - args[0]).collect(),
+///        weights,
+///        vec![1.0],
+///        1.0,
+///        Default::default(),
+///        Bounds::none(1),
+///        1000).unwrap()
+/// };
+/// let (uniform, uniform_residuals) = fit(vec![1.0, 1.0]);
+/// let (weighted, weighted_residuals) = fit(vec![1.0, 9.0]);
+///
+/// // expected fits: the mean 5 and the weighted mean 9
+/// assert_approx_eq!(uniform.best_point()[0], 5.0);
+/// assert_approx_eq!(weighted.best_point()[0], 9.0);
+/// assert_approx_eq!(weighted.best_value(), 81.0 + 9.0);
+/// assert!(weighted_residuals[1].abs() < uniform_residuals[1].abs());
+///
+/// let mismatch = minimize_weighted_least_squares(
+///    |args| data.iter().map(|y| y - args[0]).collect(),
+///    vec![1.0],
+///    vec![1.0],
+///    1.0,
+///    Default::default(),
+///    Bounds::none(1),
+///    1000);
+/// assert!(mismatch.is_err());
+/// ```
+pub fn minimize_weighted_least_squares<T: Float>(
+    residuals: impl Fn(&[T]) -> Vec<T>,
+    weights: Vec<T>,
+    initial_point: Vec<T>,
+    initial_simplex_size: T,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+) -> Result<(OptimizationResult<T>, Vec<T>), NelderMeadError> {
+    let found = residuals(&initial_point).len();
+    if found != weights.len() {
+        return Err(NelderMeadError::DimensionMismatch {
+            expected: weights.len(),
+            found,
+        });
+    }
+    let result = minimize_with_report(
+        |x| {
+            residuals(x)
+                .iter()
+                .zip(weights.iter())
+                .map(|(&r, &w)| w * r * r)
+                .sum()
+        },
+        initial_point,
+        initial_simplex_size,
+        params,
+        bounds,
+        max_iter,
+    );
+    let final_residuals = residuals(&result.point);
+    Ok((result, final_residuals))
+}
+
+/// Fits the parameters of a `log_density` model to the `data` by maximum
+/// likelihood, starting with a simplex of size `initial_simplex_size`
+/// centered on `initial_point`.
+///
+/// `log_density(params, x)` is the log of the probability density of the
+/// data point `x` under the model with the given parameters. This minimizes
+/// the negative log-likelihood, the sum of `-log_density(params, x)` over
+/// all the `data`, so the result's value is the negative log-likelihood at
+/// its point.
+///
+/// The search space is bounded by a `Bounds` definition.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+/// use nelder_mead::bounds::*;
+///
+/// use assert_approx_eq::assert_approx_eq;
+///
+/// // fit the mean and the variance of a gaussian
+/// let samples = vec![1.2, 0.7, 2.5, 1.9, 0.3, 1.4];
+/// let result = minimize_nll(
+///    |params, x: &f64| {
+///        let (mean, variance) = (params[0], params[1]);
+///        -0.5 * (2.0 * std::f64::consts::PI * variance).ln()
+///            - (x - mean) * (x - mean) / (2.0 * variance)
+///    },
+///    &samples,
+///    vec![0.0, 1.0],
+///    0.5,
+///    Default::default(),
+///    Bounds {
+///        min: vec![f64::NEG_INFINITY, 1e-6],
+///        max: vec![f64::INFINITY, f64::INFINITY],
+///    },
+///    1000);
+///
+/// // expected fit: the empirical mean and (biased) variance
+/// let mean = samples.iter().sum::<f64>() / 6.0;
+/// let variance = samples.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / 6.0;
+/// assert_approx_eq!(result.best_point()[0], mean, 1e-4);
+/// assert_approx_eq!(result.best_point()[1], variance, 1e-4);
+/// ```
+pub fn minimize_nll<T: Float, D>(
+    log_density: impl Fn(&[T], &D) -> T,
+    data: &[D],
+    initial_point: Vec<T>,
+    initial_simplex_size: T,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+) -> OptimizationResult<T> {
+    minimize_with_report(
+        |x| -data.iter().map(|d| log_density(x, d)).sum::<T>(),
+        initial_point,
+        initial_simplex_size,
+        params,
+        bounds,
+        max_iter,
+    )
+}
+
+/// Minimizes a function `f`,
+/// starting with a simplex whose vertices are `initial_point` and
+/// `initial_point + p` for each of the `perturbations`.
+///
+/// Fails unless there are exactly `n` perturbations of dimension `n`, where
+/// `n` is the dimension of `initial_point`.
+///
+/// The search space is bounded by a `Bounds` definition.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+/// use nelder_mead::bounds::*;
+///
+/// use assert_approx_eq::assert_approx_eq;
+///
+/// // minimize (x+1)^2 + y^2, starting with a skewed simplex
+/// let (x, fx) = minimize_with_perturbations(
+///    |args: &[f64]| (args[0]+1.0) * (args[0]+1.0) + args[1]*args[1],
+///    vec![5.0,5.0],
+///    vec![vec![1.0, 0.5], vec![-0.5, 1.0]],
+///    Default::default(),
+///    Bounds::none(2),
+///    1000).unwrap();
+///
+/// // expected minimum: f(-1, 0) = 0
+/// assert_approx_eq!(x[0], -1.0);
+/// assert_approx_eq!(x[1], 0.0);
+/// assert_approx_eq!(fx, 0.0);
+/// ```
+pub fn minimize_with_perturbations<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    initial_point: Vec<T>,
+    perturbations: Vec<Vec<T>>,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+) -> Result<(Vec<T>, T), NelderMeadError> {
+    let initial_simplex = InitialSimplex::from_perturbations(initial_point, perturbations)?;
+    Ok(minimize_from_simplex(
+        f,
+        &initial_simplex,
+        params,
+        bounds,
+        max_iter,
+    ))
+}
+
+/// Approximates the trade-off front between two objectives, by minimizing
+/// `w * obj_a + (1 - w) * obj_b` for `n_weights` weights `w` evenly spaced
+/// from 0 to 1.
+///
+/// Every minimization starts with a simplex of size `initial_simplex_size`
+/// centered on `initial_point`. Returns, for every weight, the weight, the
+/// best point and the values of both objectives at that point.
+///
+/// The search space is bounded by a `Bounds` definition.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+/// use nelder_mead::bounds::*;
+///
+/// // two conflicting objectives, with minima at (0, 0) and (2, 0)
+/// let front = minimize_tradeoff(
+///    |args| args[0]*args[0] + args[1]*args[1],
+///    |args| (args[0]-2.0) * (args[0]-2.0) + args[1]*args[1],
+///    5,
+///    vec![1.0, 1.0],
+///    1.0,
+///    Default::default(),
+///    Bounds::none(2),
+///    1000);
+///
+/// // giving more weight to the first objective improves it at the expense of the second one
+/// assert_eq!(front.len(), 5);
+/// for pair in front.windows(2) {
+///     let (w0, _, (a0, b0)) = &pair[0];
+///     let (w1, _, (a1, b1)) = &pair[1];
+///     assert!(w0 < w1);
+///     assert!(a1 < a0);
+///     assert!(b1 > b0);
+/// }
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn minimize_tradeoff<T: Float>(
+    obj_a: impl Fn(&[T]) -> T,
+    obj_b: impl Fn(&[T]) -> T,
+    n_weights: u32,
+    initial_point: Vec<T>,
+    initial_simplex_size: T,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+) -> Vec<(T, Vec<T>, (T, T))> {
+    (0..n_weights)
+        .map(|k| {
+            let w = if n_weights > 1 {
+                T::from_f64(k as f64 / (n_weights - 1) as f64)
+            } else {
+                T::from_f64(0.5)
+            };
+            let result = minimize_with_report(
+                |x| w * obj_a(x) + (T::one() - w) * obj_b(x),
+                initial_point.clone(),
+                initial_simplex_size,
+                params.clone(),
+                bounds.clone(),
+                max_iter,
+            );
+            let values = (obj_a(&result.point), obj_b(&result.point));
+            (w, result.point, values)
+        })
+        .collect()
+}
+
+/// Minimizes a function `f`,
+/// starting with a simplex of size `initial_simplex_size` centered on
+/// `initial_point`, and counts the calls to `f` it took to first get within
+/// `tol` of a known `target_value`.
+///
+/// Returns `None` if no evaluation got within `tol` of the target. Set
+/// `Params::seed` to make the count reproducible, as the initial simplex is
+/// otherwise random.
+///
+/// The search space is bounded by a `Bounds` definition.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+/// use nelder_mead::bounds::*;
+/// use nelder_mead::params::*;
+///
+/// // the minimum of (x+1)^2 + y^2 is 0
+/// let f = |args: &[f64]| (args[0]+1.0) * (args[0]+1.0) + args[1]*args[1];
+/// let params = Params { seed: Some(42), ..Default::default() };
+/// let count = || {
+///     evals_to_target(f, vec![5.0, 5.0], 1.0, params.clone(), Bounds::none(2), 1000, 0.0, 1e-6)
+/// };
+///
+/// let evaluations = count().unwrap();
+/// assert!(3 < evaluations && evaluations < 500);
+/// assert_eq!(count(), Some(evaluations));
+///
+/// // every call counts, like the samples of `Params::resample`
+/// let resampled = Params { resample: Some((2, Aggregate::Mean)), ..params.clone() };
+/// let samples =
+///     evals_to_target(f, vec![5.0, 5.0], 1.0, resampled, Bounds::none(2), 1000, 0.0, 1e-6);
+/// assert_eq!(samples, Some(2 * evaluations - 1));
+///
+/// // unreachable within 10 iterations
+/// let too_short =
+///     evals_to_target(f, vec![5.0, 5.0], 1.0, params.clone(), Bounds::none(2), 10, 0.0, 1e-6);
+/// assert_eq!(too_short, None);
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn evals_to_target<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    initial_point: Vec<T>,
+    initial_simplex_size: T,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+    target_value: T,
+    tol: T,
+) -> Option<u64> {
+    // every call is counted as it happens, which includes those outside of
+    // the search itself, like the probes of `BoundaryMode::Auto` and the
+    // samples of `Params::resample`
+    let calls = std::cell::Cell::new(0u64);
+    let reached = std::cell::Cell::new(None);
+    minimize_with_report(
+        |x: &[T]| {
+            calls.set(calls.get() + 1);
+            let fx = f(x);
+            if reached.get().is_none() && (fx - target_value).abs() <= tol {
+                reached.set(Some(calls.get()));
+            }
+            fx
+        },
+        initial_point,
+        initial_simplex_size,
+        params,
+        bounds,
+        max_iter,
+    );
+    reached.get()
+}
+
+/// Checks that `f` seems bounded below within `bounds`, by evaluating it at
+/// `samples` random points.
+///
+/// Fails if some value is not finite, or is absurdly lower than the median
+/// value, which usually points to a sign error or to an objective that
+/// diverges to negative infinity. This is a cheap heuristic, so passing it
+/// does not prove that `f` is bounded.
+///
+/// All bounds must be finite.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+/// use nelder_mead::bounds::*;
+///
+/// let bounds = Bounds { min: vec![0.0, 0.0], max: vec![1.0, 1.0] };
+///
+/// // well-posed
+/// assert!(check_objective_bounded(
+///     |args| args[0]*args[0] + args[1]*args[1],
+///     &bounds,
+///     1000).is_ok());
+///
+/// // diverges to negative infinity around (0.3, 0.3)
+/// assert!(check_objective_bounded(
+///     |args: &[f64]| -1.0 / ((args[0]-0.3).powi(2) + (args[1]-0.3).powi(2)).powi(5),
+///     &bounds,
+///     1000).is_err());
+/// ```
+pub fn check_objective_bounded<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    bounds: &Bounds<T>,
+    samples: u32,
+) -> Result<(), NelderMeadError> {
+    let bounds_vec = bounds.as_vec();
+    if let Some(dimension) = bounds_vec
+        .iter()
+        .position(|&(min, max)| !(max - min).is_finite())
+    {
+        return Err(NelderMeadError::NonFiniteBounds { dimension });
+    }
+    let mut rng = OsRng::new().expect("Failed to create the RNG");
+    let mut evaluated: Vec<(Vec<T>, T)> = (0..samples)
+        .map(|_| {
+            let point: Vec<T> = bounds_vec
+                .iter()
+                .map(|(min, max)| {
+                    if min < max {
+                        rng.gen_range(*min, *max)
+                    } else {
+                        *min
+                    }
+                })
+                .collect();
+            let value = f(&point);
+            (point, value)
+        })
+        .collect();
+    if let Some((point, value)) = evaluated.iter().find(|(_, value)| !value.is_finite()) {
+        return Err(NelderMeadError::UnboundedObjective {
+            point: point.iter().map(|x| x.as_f64()).collect(),
+            value: value.as_f64(),
+        });
+    }
+    evaluated.sort_by(|(_, x), (_, y)| compare_values(*x, *y));
+    match evaluated.first() {
+        Some((point, value)) => {
+            let median = evaluated[evaluated.len() / 2].1;
+            if *value < median - T::from_f64(1e6) * (T::one() + median.abs()) {
+                Err(NelderMeadError::UnboundedObjective {
+                    point: point.iter().map(|x| x.as_f64()).collect(),
+                    value: value.as_f64(),
+                })
+            } else {
+                Ok(())
+            }
+        }
+        None => Ok(()),
+    }
+}
+
+/// Builds and evaluates a simplex whose vertices are `center` and `center`
+/// moved by `step` along each axis.
+///
+/// Unlike a random simplex, this one is deterministic and never degenerate.
+/// The same geometry is used by the entry points that start from a single
+/// point when `Params::axis_aligned_simplex` is set, and is available, for
+/// use with `minimize_from_simplex`, as `InitialSimplex::axis_aligned`.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+///
+/// let f = |args: &[f64]| args[0]*args[0] + args[1]*args[1];
+/// let simplex = new_simplex_axis_aligned(f, vec![1.0, 2.0], 0.5);
+///
+/// // sorted from best to worst vertex
+/// assert_eq!(simplex, vec![
+///     (vec![1.0, 2.0], 5.0),
+///     (vec![1.5, 2.0], 6.25),
+///     (vec![1.0, 2.5], 7.25),
+/// ]);
+/// ```
+pub fn new_simplex_axis_aligned<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    center: Vec<T>,
+    step: T,
+) -> Simplex<T> {
+    evaluate_simplex(f, &InitialSimplex::axis_aligned(center, step))
+}
+
+/// Builds and evaluates a random simplex of size `step` centered on
+/// `center`, drawn from a generator seeded with `seed`.
+///
+/// The same seed always builds the same simplex, like setting
+/// `Params::seed` does for the entry points that start from a single point.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+/// use nelder_mead::bounds::*;
+/// use nelder_mead::params::*;
+///
+/// let f = |args: &[f64]| (args[0]+1.0) * (args[0]+1.0) + args[1]*args[1];
+/// assert_eq!(
+///     new_simplex_seeded(f, vec![5.0, 5.0], 1.0, 42),
+///     new_simplex_seeded(f, vec![5.0, 5.0], 1.0, 42));
+///
+/// // seeded runs are reproducible, bit for bit
+/// let params = Params { seed: Some(42), ..Default::default() };
+/// let run = || minimize(f, vec![5.0, 5.0], 1.0, params.clone(), Bounds::none(2), 100);
+/// let (x, fx) = run();
+/// let (y, fy) = run();
+/// assert_eq!(x, y);
+/// assert_eq!(fx.to_bits(), fy.to_bits());
+/// ```
+pub fn new_simplex_seeded<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    center: Vec<T>,
+    step: T,
+    seed: u64,
+) -> Simplex<T> {
+    evaluate_simplex(f, &InitialSimplex::seeded(center, step, seed))
+}
+
+/// Builds and evaluates a simplex around `center` whose steps are scaled to
+/// each coordinate, as in Pfeffer's method (also used by scipy).
+///
+/// The step along axis `i` is `pct * |center[i]|`, or `zero_step` when
+/// `center[i]` is zero. The same geometry is available, for use with
+/// `minimize_from_simplex`, as `InitialSimplex::pfeffer`.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+///
+/// let f = |args: &[f64]| args[0]*args[0] + args[1]*args[1];
+/// let simplex = new_simplex_pfeffer(f, vec![100.0, 0.0], 0.05, 0.00025);
+///
+/// // sorted from best to worst vertex
+/// assert_eq!(simplex, vec![
+///     (vec![100.0, 0.0], 10000.0),
+///     (vec![100.0, 0.00025], 10000.0000000625),
+///     (vec![105.0, 0.0], 11025.0),
+/// ]);
+/// ```
+pub fn new_simplex_pfeffer<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    center: Vec<T>,
+    pct: T,
+    zero_step: T,
+) -> Simplex<T> {
+    evaluate_simplex(f, &InitialSimplex::pfeffer(center, pct, zero_step))
+}
+
+/// Performs a single Nelder-Mead iteration on a sorted `simplex`, returning
+/// the new sorted simplex and the kind of step taken.
+///
+/// The search space is bounded by a `Bounds` definition.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+/// use nelder_mead::bounds::*;
+///
+/// // minimize x^2 + y^2 by manually stepping from a sorted simplex
+/// let f = |args: &[f64]| args[0]*args[0] + args[1]*args[1];
+/// let mut simplex: Simplex = vec![vec![1.0, 1.0], vec![2.0, 1.0], vec![1.0, 2.0]]
+///     .into_iter()
+///     .map(|x| { let fx = f(&x); (x, fx) })
+///     .collect();
+/// for _ in 0..10 {
+///     simplex = step(f, simplex, &Default::default(), &Bounds::none(2)).0;
+/// }
+///
+/// assert!(simplex[0].1 < 2.0);
+/// ```
+pub fn step<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    simplex: Simplex<T>,
+    params: &Params<T>,
+    bounds: &Bounds<T>,
+) -> (Simplex<T>, StepKind) {
+    let n = simplex.len() - 1;
+    crate::simplex::step(f, simplex, params, &bounds.as_vec(), n)
+}
+
+/// Performs a single Nelder-Mead iteration on a sorted `simplex`, returning
+/// the new sorted simplex and the kind of step taken.
+///
+/// The centroid is computed over every vertex except the one whose index is
+/// returned by `exclusion_policy`. `step` always excludes the worst vertex.
+///
+/// The search space is bounded by a `Bounds` definition.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::*;
+/// use nelder_mead::bounds::*;
+///
+/// // step while excluding the second worst vertex from the centroid
+/// let f = |args: &[f64]| args[0]*args[0] + args[1]*args[1];
+/// let mut simplex: Simplex = vec![vec![1.0, 1.0], vec![2.0, 1.0], vec![1.0, 2.0]]
+///     .into_iter()
+///     .map(|x| { let fx = f(&x); (x, fx) })
+///     .collect();
+/// for _ in 0..10 {
+///     simplex = step_with_exclusion(
+///         f,
+///         simplex,
+///         &Default::default(),
+///         &Bounds::none(2),
+///         |simplex| simplex.len() - 2).0;
+/// }
+///
+/// assert!(simplex[0].1 <= 2.0);
+/// ```
+pub fn step_with_exclusion<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    simplex: Simplex<T>,
+    params: &Params<T>,
+    bounds: &Bounds<T>,
+    exclusion_policy: impl Fn(&Simplex<T>) -> usize,
+) -> (Simplex<T>, StepKind) {
+    let excluded = exclusion_policy(&simplex);
+    crate::simplex::step(f, simplex, params, &bounds.as_vec(), excluded)
+}
+
+/// Evaluates a simplex of size `initial_simplex_size` around
+/// `initial_point`, built as set by `Params`, returning it along with the
+/// value at `initial_point` when `Params::initial_point_as_vertex` makes it
+/// one of the vertices.
+pub(crate) fn simplex_around<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    initial_point: Vec<T>,
+    initial_simplex_size: T,
+    params: &Params<T>,
+) -> (Simplex<T>, Option<T>) {
+    simplex_around_with(
+        |xs| xs.iter().map(|x| f(x)).collect(),
+        initial_point,
+        initial_simplex_size,
+        params,
+    )
+}
+
+/// Builds the initial simplex like `simplex_around`, evaluating its
+/// vertices all at once with `f_all`.
+pub(crate) fn simplex_around_with<T: Float>(
+    f_all: impl Fn(&[Vec<T>]) -> Vec<T>,
+    initial_point: Vec<T>,
+    initial_simplex_size: T,
+    params: &Params<T>,
+) -> (Simplex<T>, Option<T>) {
+    let mut initial_simplex =
+        InitialSimplex::from_params(initial_point.clone(), initial_simplex_size, params);
+    if params.initial_point_as_vertex {
+        initial_simplex.points[0] = initial_point;
+        let (simplex, initial_value) =
+            evaluate_initial_simplex_from_first(f_all, &initial_simplex, params);
+        (simplex, Some(initial_value))
+    } else {
+        (
+            evaluate_initial_simplex_with(f_all, &initial_simplex, params),
+            None,
+        )
+    }
+}
+
+/// Sets the initial point of `result` and its value, which is evaluated
+/// with `f` and counted in `result` unless `simplex_around` already
+/// returned it as `initial_value`.
+pub(crate) fn report_initial_value<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    result: &mut OptimizationResult<T>,
+    initial_point: Vec<T>,
+    initial_value: Option<T>,
+    record_evaluations: bool,
+) {
+    result.initial_value = match initial_value {
+        Some(initial_value) => initial_value,
+        None => {
+            let initial_value = f(&initial_point);
+            result.evaluations += 1;
+            if record_evaluations {
+                result
+                    .evaluation_history
+                    .push((initial_point.clone(), initial_value));
+            }
+            initial_value
+        }
+    };
+    result.initial_point = initial_point;
+}
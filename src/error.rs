@@ -0,0 +1,76 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Errors reported by the optimizer and its helpers.
+#[derive(Clone, Debug, PartialEq)]
+pub enum NelderMeadError {
+    /// Two values that should describe the same number of dimensions don't.
+    DimensionMismatch { expected: usize, found: usize },
+    /// The intersection of several bounds is empty along `dimension`.
+    EmptyIntersection { dimension: usize },
+    /// Evaluating the objective twice at the same point gave different values.
+    NonDeterministicObjective { first: f64, second: f64 },
+    /// Only `found` of the `needed` feasible vertices were sampled within the
+    /// allowed number of attempts.
+    InfeasibleInitialSimplex { found: usize, needed: usize },
+    /// The bounds of `dimension` do not span a finite range.
+    NonFiniteBounds { dimension: usize },
+    /// The minimum of `dimension` is above its maximum, or either is `NaN`.
+    EmptyBounds { dimension: usize },
+    /// The objective seems to be unbounded below, having returned `value`,
+    /// which is either not finite or far below the other sampled values, at
+    /// `point`.
+    UnboundedObjective { point: Vec<f64>, value: f64 },
+    /// A JSON document couldn't be read.
+    InvalidJson { message: String },
+    /// The coefficient `name` is `value`, outside of the `expected` range.
+    InvalidCoefficient {
+        name: &'static str,
+        value: f64,
+        expected: &'static str,
+    },
+}
+
+impl fmt::Display for NelderMeadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NelderMeadError::DimensionMismatch { expected, found } => {
+                write!(f, "expected {} dimensions, found {}", expected, found)
+            }
+            NelderMeadError::EmptyIntersection { dimension } => {
+                write!(f, "empty bounds intersection in dimension {}", dimension)
+            }
+            NelderMeadError::NonDeterministicObjective { first, second } => write!(
+                f,
+                "objective returned {} and then {} for the same point",
+                first, second
+            ),
+            NelderMeadError::InfeasibleInitialSimplex { found, needed } => write!(
+                f,
+                "only found {} of {} feasible initial vertices",
+                found, needed
+            ),
+            NelderMeadError::NonFiniteBounds { dimension } => {
+                write!(f, "non-finite bounds in dimension {}", dimension)
+            }
+            NelderMeadError::EmptyBounds { dimension } => {
+                write!(f, "empty bounds in dimension {}", dimension)
+            }
+            NelderMeadError::UnboundedObjective { point, value } => write!(
+                f,
+                "objective seems unbounded below, returning {} at {:?}",
+                value, point
+            ),
+            NelderMeadError::InvalidJson { message } => write!(f, "invalid JSON: {}", message),
+            NelderMeadError::InvalidCoefficient {
+                name,
+                value,
+                expected,
+            } => write!(f, "{} is {}, expected {}", name, value, expected),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NelderMeadError {}
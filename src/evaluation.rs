@@ -0,0 +1,346 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+use crate::algebra::*;
+use crate::params::*;
+
+/// Maps the key of a cache cell to the first point evaluated in it, and its
+/// value.
+type Cache<T> = HashMap<Vec<u64>, (Vec<T>, T)>;
+
+/// Wraps the objective during an optimization run, applying the evaluation
+/// options from `Params` and keeping the statistics reported in the result.
+pub struct Evaluator<'a, F: Fn(&[T]) -> T, T: Float = f64> {
+    f: F,
+    params: &'a Params<T>,
+    /// Number of calls to the objective.
+    pub evaluations: Cell<u32>,
+    /// Lowest and highest values returned since the last `reset_range`.
+    pub range: Cell<(T, T)>,
+    cache: RefCell<Cache<T>>,
+    /// Number of evaluations served from the cache.
+    pub cache_hits: Cell<u32>,
+    /// Number of evaluations not found in the cache.
+    pub cache_misses: Cell<u32>,
+    /// Every evaluated point and its value, when `Params::record_evaluations`
+    /// is set.
+    pub history: RefCell<Vec<(Vec<T>, T)>>,
+    /// Number of calls to `f` that returned `NaN`.
+    pub nan_evaluations: Cell<u32>,
+}
+
+/// Cache key of a point, which is bitwise-exact unless a `tolerance` is
+/// given, in which case every coordinate is rounded to a multiple of it.
+fn cache_key<T: Float>(x: &[T], tolerance: Option<T>) -> Vec<u64> {
+    match tolerance {
+        Some(tolerance) => x
+            .iter()
+            // adding 0 turns -0 into 0, so both share a key
+            .map(|&c| ((c / tolerance).round() + T::zero()).as_f64().to_bits())
+            .collect(),
+        None => x.iter().map(|c| c.as_f64().to_bits()).collect(),
+    }
+}
+
+/// Snaps each coordinate of `x` to the nearest of its `allowed_values`, if
+/// any.
+pub fn snap<T: Float>(x: &[T], allowed_values: &[Option<Vec<T>>]) -> Vec<T> {
+    x.iter()
+        .zip(allowed_values.iter())
+        .map(|(&c, allowed)| match allowed {
+            Some(values) => values.iter().cloned().fold(T::nan(), |nearest, value| {
+                if nearest.is_nan() || (value - c).abs() < (nearest - c).abs() {
+                    value
+                } else {
+                    nearest
+                }
+            }),
+            None => c,
+        })
+        .collect()
+}
+
+/// Number of calls to the objective per point, as set by
+/// `Params::resample`.
+pub fn samples_per_point<T>(params: &Params<T>) -> u32 {
+    match params.resample {
+        Some((k, _)) => k.max(1),
+        None => 1,
+    }
+}
+
+/// Evaluates `f` at `x`, combining repeated samples as set by
+/// `Params::resample`.
+pub fn sample<T: Float>(f: impl Fn(&[T]) -> T, x: &[T], params: &Params<T>) -> T {
+    match params.resample {
+        Some((k, aggregate)) if k > 1 => combine((0..k).map(|_| f(x)).collect(), aggregate),
+        _ => f(x),
+    }
+}
+
+/// Evaluates `f_all` at every point of `xs` at once, combining repeated
+/// samples of each point as set by `Params::resample`.
+pub fn sample_all<T: Float>(
+    f_all: impl Fn(&[Vec<T>]) -> Vec<T>,
+    xs: &[Vec<T>],
+    params: &Params<T>,
+) -> Vec<T> {
+    match params.resample {
+        Some((k, aggregate)) if k > 1 => {
+            let repeated: Vec<Vec<T>> = xs
+                .iter()
+                .flat_map(|x| (0..k).map(move |_| x.clone()))
+                .collect();
+            f_all(&repeated)
+                .chunks(k as usize)
+                .map(|samples| combine(samples.to_vec(), aggregate))
+                .collect()
+        }
+        _ => f_all(xs),
+    }
+}
+
+/// Combines the repeated `samples` of a point into its value.
+fn combine<T: Float>(mut samples: Vec<T>, aggregate: Aggregate) -> T {
+    match aggregate {
+        Aggregate::Mean => samples.iter().copied().sum::<T>() / T::from_f64(samples.len() as f64),
+        Aggregate::Median => {
+            samples.sort_by(|a, b| compare_values(*a, *b));
+            let middle = samples.len() / 2;
+            if samples.len().is_multiple_of(2) {
+                (samples[middle - 1] + samples[middle]) / T::from_f64(2.0)
+            } else {
+                samples[middle]
+            }
+        }
+    }
+}
+
+impl<'a, F: Fn(&[T]) -> T, T: Float> Evaluator<'a, F, T> {
+    /// Wraps `f`, accounting for the already evaluated `initial_simplex`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `Params::cache_tolerance` is set but isn't finite and
+    /// positive.
+    pub fn new(
+        f: F,
+        params: &'a Params<T>,
+        initial_simplex: &[(Vec<T>, T)],
+    ) -> Evaluator<'a, F, T> {
+        if let Some(tolerance) = params.cache_tolerance {
+            assert!(
+                tolerance.is_finite() && tolerance > T::zero(),
+                "The cache tolerance must be finite and positive"
+            );
+        }
+        let cache = if params.exact_cache || params.cache_tolerance.is_some() {
+            let mut cache = HashMap::new();
+            for (x, fx) in initial_simplex {
+                cache
+                    .entry(cache_key(x, params.cache_tolerance))
+                    .or_insert((x.clone(), *fx));
+            }
+            cache
+        } else {
+            HashMap::new()
+        };
+        Evaluator {
+            f,
+            params,
+            evaluations: Cell::new(initial_simplex.len() as u32 * samples_per_point(params)),
+            range: Cell::new((T::infinity(), T::neg_infinity())),
+            cache: RefCell::new(cache),
+            cache_hits: Cell::new(0),
+            cache_misses: Cell::new(0),
+            nan_evaluations: Cell::new(
+                initial_simplex.iter().filter(|(_, fx)| fx.is_nan()).count() as u32,
+            ),
+            history: RefCell::new(if params.record_evaluations {
+                initial_simplex.to_vec()
+            } else {
+                Vec::new()
+            }),
+        }
+    }
+
+    pub fn eval(&self, x: &[T]) -> T {
+        let snapped;
+        let x = match &self.params.allowed_values {
+            Some(allowed_values) => {
+                snapped = snap(x, allowed_values);
+                &snapped
+            }
+            None => x,
+        };
+        let fx = if self.params.exact_cache || self.params.cache_tolerance.is_some() {
+            let key = cache_key(x, self.params.cache_tolerance);
+            let cached = self.cache.borrow().get(&key).map(|(_, fx)| *fx);
+            match cached {
+                Some(fx) => {
+                    self.cache_hits.set(self.cache_hits.get() + 1);
+                    fx
+                }
+                None => {
+                    self.cache_misses.set(self.cache_misses.get() + 1);
+                    match self.call(x) {
+                        Some(fx) => {
+                            self.cache.borrow_mut().insert(key, (x.to_vec(), fx));
+                            fx
+                        }
+                        // never evaluated, so not cached
+                        None => T::infinity(),
+                    }
+                }
+            }
+        } else {
+            self.call(x).unwrap_or(T::infinity())
+        };
+        let (lowest, highest) = self.range.get();
+        self.range.set((lowest.min(fx), highest.max(fx)));
+        fx
+    }
+
+    /// Evaluates every point of `xs` like `eval`, calling `f_all` once with
+    /// the points that are not cached, instead of `f` for each of them.
+    pub fn eval_all(&self, xs: &[Vec<T>], f_all: impl Fn(&[Vec<T>]) -> Vec<T>) -> Vec<T> {
+        let caching = self.params.exact_cache || self.params.cache_tolerance.is_some();
+        let xs: Vec<Vec<T>> = match &self.params.allowed_values {
+            Some(allowed_values) => xs.iter().map(|x| snap(x, allowed_values)).collect(),
+            None => xs.to_vec(),
+        };
+        let keys: Vec<Vec<u64>> = xs
+            .iter()
+            .map(|x| cache_key(x, self.params.cache_tolerance))
+            .collect();
+        // the points to evaluate, as the index of the first point of each
+        // cell that isn't cached, within the budget
+        let mut pending: Vec<usize> = Vec::new();
+        for (i, key) in keys.iter().enumerate() {
+            let cached = caching
+                && (self.cache.borrow().contains_key(key)
+                    || pending.iter().any(|j| keys[*j] == *key));
+            if cached {
+                self.cache_hits.set(self.cache_hits.get() + 1);
+            } else {
+                if caching {
+                    self.cache_misses.set(self.cache_misses.get() + 1);
+                }
+                if self.affords(pending.len() as u32 + 1) {
+                    pending.push(i);
+                }
+            }
+        }
+        let points: Vec<Vec<T>> = pending.iter().map(|i| xs[*i].clone()).collect();
+        let values = if points.is_empty() {
+            Vec::new()
+        } else {
+            sample_all(f_all, &points, self.params)
+        };
+        self.evaluations
+            .set(self.evaluations.get() + pending.len() as u32 * samples_per_point(self.params));
+        let mut evaluated = HashMap::new();
+        for ((i, x), fx) in pending.iter().zip(points).zip(values) {
+            if fx.is_nan() {
+                self.nan_evaluations.set(self.nan_evaluations.get() + 1);
+            }
+            if self.params.record_evaluations {
+                self.history.borrow_mut().push((x.clone(), fx));
+            }
+            if caching {
+                self.cache.borrow_mut().insert(keys[*i].clone(), (x, fx));
+            }
+            evaluated.insert(*i, fx);
+        }
+        let fxs: Vec<T> = (0..xs.len())
+            .map(|i| match evaluated.get(&i) {
+                Some(fx) => *fx,
+                None if caching => self
+                    .cache
+                    .borrow()
+                    .get(&keys[i])
+                    .map(|(_, fx)| *fx)
+                    // past the budget, points are infinitely bad
+                    .unwrap_or(T::infinity()),
+                None => T::infinity(),
+            })
+            .collect();
+        for fx in &fxs {
+            let (lowest, highest) = self.range.get();
+            self.range.set((lowest.min(*fx), highest.max(*fx)));
+        }
+        fxs
+    }
+
+    /// Turns the value `fx` that `eval` returned for `x` into the value of
+    /// `f` at the returned point.
+    ///
+    /// With `Params::cache_tolerance`, `fx` may be the value of another
+    /// point of the same cell, in which case `x` is evaluated again, or, if
+    /// that would exceed `Params::max_evaluations`, replaced by that point.
+    pub fn settle(&self, x: Vec<T>, fx: T) -> (Vec<T>, T) {
+        if self.params.cache_tolerance.is_none() {
+            return (x, fx);
+        }
+        let key = cache_key(&x, self.params.cache_tolerance);
+        let cached = self.cache.borrow().get(&key).cloned();
+        match cached {
+            Some((evaluated, _)) if evaluated == x => (x, fx),
+            Some(evaluated) if !self.affords(1) => evaluated,
+            _ => match self.call(&x) {
+                Some(fx) => (x, fx),
+                None => (x, fx),
+            },
+        }
+    }
+
+    /// Evaluates `f` at `x` like `eval`, but without snapping `x` to the
+    /// allowed values nor going through the cache, so that a probe always
+    /// calls `f`.
+    pub fn probe(&self, x: &[T]) -> T {
+        self.call(x).unwrap_or(T::infinity())
+    }
+
+    /// Whether evaluating `points` more points stays within
+    /// `Params::max_evaluations` and `Params::max_distinct_evals`.
+    pub fn affords(&self, points: u32) -> bool {
+        let samples = samples_per_point(self.params);
+        let within_calls = match self.params.max_evaluations {
+            Some(max_evaluations) => {
+                let needed = points.saturating_mul(samples);
+                self.evaluations.get().saturating_add(needed) <= max_evaluations
+            }
+            None => true,
+        };
+        // every point takes the same number of calls, and only calls count
+        let within_points = match self.params.max_distinct_evals {
+            Some(max_distinct_evals) => {
+                (self.evaluations.get() / samples) as u64 + points as u64 <= max_distinct_evals
+            }
+            None => true,
+        };
+        within_calls && within_points
+    }
+
+    pub fn reset_range(&self) {
+        self.range.set((T::infinity(), T::neg_infinity()));
+    }
+
+    /// Calls `f` at `x`, or returns `None` without calling it past the
+    /// budget, where points are infinitely bad.
+    fn call(&self, x: &[T]) -> Option<T> {
+        if !self.affords(1) {
+            return None;
+        }
+        self.evaluations
+            .set(self.evaluations.get() + samples_per_point(self.params));
+        let fx = sample(&self.f, x, self.params);
+        if fx.is_nan() {
+            self.nan_evaluations.set(self.nan_evaluations.get() + 1);
+        }
+        if self.params.record_evaluations {
+            self.history.borrow_mut().push((x.to_vec(), fx));
+        }
+        Some(fx)
+    }
+}
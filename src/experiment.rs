@@ -0,0 +1,257 @@
+//! Records of optimization runs, for experiment logging.
+//!
+//! An `ExperimentRecord` captures the configuration of a seeded run along
+//! with its outcome, and can be exported to and imported from JSON, so that
+//! the run can be archived and reproduced later.
+//!
+//! # Example
+//!
+//! ```
+//! use nelder_mead::bounds::*;
+//! use nelder_mead::experiment::*;
+//!
+//! let f = |args: &[f64]| (args[0]+1.0) * (args[0]+1.0) + args[1]*args[1];
+//! let record = ExperimentRecord::run(
+//!    f,
+//!    vec![5.0, 5.0],
+//!    1.0,
+//!    Default::default(),
+//!    Bounds::none(2),
+//!    1000,
+//!    42);
+//!
+//! let json = record.to_json();
+//! let archived = ExperimentRecord::from_json(&json).unwrap();
+//! assert_eq!(archived.rerun(f).point, record.point);
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::bounds::*;
+use crate::error::*;
+use crate::initial_simplex::*;
+use crate::params::*;
+use crate::result::*;
+use crate::simplex::*;
+
+/// The configuration and the outcome of a seeded optimization run.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExperimentRecord {
+    /// Parameters of the run.
+    pub params: Params,
+    /// Bounds of the search space.
+    pub bounds: Bounds,
+    /// The point the initial simplex is centered on.
+    pub initial_point: Vec<f64>,
+    /// Size of the initial simplex.
+    pub initial_simplex_size: f64,
+    /// Maximum number of iterations.
+    pub max_iter: u32,
+    /// Seed of the initial simplex, built with `InitialSimplex::seeded`.
+    pub seed: u64,
+    /// Why the run stopped.
+    pub termination: TerminationReason,
+    /// Number of iterations performed.
+    pub iterations: u32,
+    /// Number of calls to the objective, including the evaluation of the
+    /// initial simplex.
+    pub evaluations: u32,
+    /// The best point found.
+    pub point: Vec<f64>,
+    /// The function value at `point`.
+    #[serde(with = "crate::serialization::non_finite")]
+    pub value: f64,
+}
+
+impl ExperimentRecord {
+    /// Minimizes a function `f`,
+    /// starting with a random simplex of size `initial_simplex_size` centered
+    /// on `initial_point` and seeded with `seed`, and records the run.
+    ///
+    /// The search space is bounded by a `Bounds` definition.
+    #[allow(clippy::too_many_arguments)]
+    pub fn run(
+        f: impl Fn(&[f64]) -> f64,
+        initial_point: Vec<f64>,
+        initial_simplex_size: f64,
+        params: Params,
+        bounds: Bounds,
+        max_iter: u32,
+        seed: u64,
+    ) -> ExperimentRecord {
+        let initial_simplex =
+            InitialSimplex::seeded(initial_point.clone(), initial_simplex_size, seed);
+        let result = minimize(
+            &f,
+            evaluate_initial_simplex(&f, &initial_simplex, &params),
+            params.clone(),
+            bounds.clone(),
+            max_iter,
+        );
+        ExperimentRecord {
+            params,
+            bounds,
+            initial_point,
+            initial_simplex_size,
+            max_iter,
+            seed,
+            termination: result.termination,
+            iterations: result.iterations,
+            evaluations: result.evaluations,
+            point: result.point,
+            value: result.value,
+        }
+    }
+
+    /// Runs the recorded configuration again on `f`.
+    ///
+    /// A deterministic `f` reproduces the recorded outcome.
+    pub fn rerun(&self, f: impl Fn(&[f64]) -> f64) -> ExperimentRecord {
+        ExperimentRecord::run(
+            f,
+            self.initial_point.clone(),
+            self.initial_simplex_size,
+            self.params.clone(),
+            self.bounds.clone(),
+            self.max_iter,
+            self.seed,
+        )
+    }
+
+    /// Exports the record as a JSON document.
+    ///
+    /// Infinite bounds are written as `null`, and a value that isn't finite
+    /// as a string, so that the record can be imported back. The other
+    /// numbers, like those of `params` and the points, are written as JSON
+    /// numbers, so a record is only imported back if they are finite.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Failed to serialize the record")
+    }
+
+    /// Imports a record exported with `to_json`.
+    pub fn from_json(json: &str) -> Result<ExperimentRecord, NelderMeadError> {
+        serde_json::from_str(json).map_err(|error| NelderMeadError::InvalidJson {
+            message: error.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rosenbrock(args: &[f64]) -> f64 {
+        (1.0 - args[0]) * (1.0 - args[0])
+            + 100.0 * (args[1] - args[0] * args[0]) * (args[1] - args[0] * args[0])
+    }
+
+    #[test]
+    fn round_trip_record() {
+        let params = Params {
+            f_atol: 1e-10,
+            max_evaluations: Some(500),
+            reflection_target: ReflectionTarget::SecondWorstWhenStuck(4),
+            ..Default::default()
+        };
+        let bounds = Bounds {
+            min: vec![-2.0, f64::NEG_INFINITY],
+            max: vec![2.0, 3.0],
+        };
+        let record = ExperimentRecord::run(
+            |x: &[f64]| rosenbrock(x),
+            vec![-1.2, 1.0],
+            0.5,
+            params,
+            bounds,
+            1000,
+            7,
+        );
+        let round_trip = ExperimentRecord::from_json(&record.to_json()).unwrap();
+
+        assert_eq!(round_trip.to_json(), record.to_json());
+        assert_eq!(round_trip.params.f_atol, 1e-10);
+        assert_eq!(round_trip.params.max_evaluations, Some(500));
+        assert_eq!(
+            round_trip.params.reflection_target,
+            ReflectionTarget::SecondWorstWhenStuck(4)
+        );
+        assert_eq!(round_trip.bounds.min, record.bounds.min);
+        assert_eq!(round_trip.bounds.max, record.bounds.max);
+        assert_eq!(round_trip.initial_point, record.initial_point);
+        assert_eq!(round_trip.initial_simplex_size, record.initial_simplex_size);
+        assert_eq!(round_trip.max_iter, record.max_iter);
+        assert_eq!(round_trip.seed, record.seed);
+        assert_eq!(round_trip.termination, record.termination);
+        assert_eq!(round_trip.iterations, record.iterations);
+        assert_eq!(round_trip.evaluations, record.evaluations);
+        assert_eq!(round_trip.point, record.point);
+        assert_eq!(round_trip.value, record.value);
+    }
+
+    #[test]
+    fn reproduce_recorded_run() {
+        let f = |x: &[f64]| rosenbrock(x);
+        let record = ExperimentRecord::run(
+            f,
+            vec![-1.2, 1.0],
+            0.5,
+            Default::default(),
+            Bounds::none(2),
+            200,
+            11,
+        );
+        let archived = ExperimentRecord::from_json(&record.to_json()).unwrap();
+        let rerun = archived.rerun(f);
+        assert_eq!(rerun.termination, record.termination);
+        assert_eq!(rerun.iterations, record.iterations);
+        assert_eq!(rerun.evaluations, record.evaluations);
+        assert_eq!(rerun.point, record.point);
+        assert_eq!(rerun.value, record.value);
+    }
+
+    #[test]
+    fn round_trip_non_finite_value() {
+        let mut record = ExperimentRecord::run(
+            |x: &[f64]| rosenbrock(x),
+            vec![-1.2, 1.0],
+            0.5,
+            Default::default(),
+            Bounds::none(2),
+            10,
+            3,
+        );
+        record.value = f64::NEG_INFINITY;
+        let round_trip = ExperimentRecord::from_json(&record.to_json()).unwrap();
+        assert_eq!(round_trip.value, f64::NEG_INFINITY);
+        record.value = f64::NAN;
+        let round_trip = ExperimentRecord::from_json(&record.to_json()).unwrap();
+        assert!(round_trip.value.is_nan());
+    }
+
+    #[test]
+    fn reject_non_finite_params() {
+        let mut record = ExperimentRecord::run(
+            |x: &[f64]| rosenbrock(x),
+            vec![-1.2, 1.0],
+            0.5,
+            Default::default(),
+            Bounds::none(2),
+            10,
+            3,
+        );
+        record.params.f_atol = f64::INFINITY;
+        assert!(matches!(
+            ExperimentRecord::from_json(&record.to_json()),
+            Err(NelderMeadError::InvalidJson { .. })
+        ));
+    }
+
+    #[test]
+    fn reject_incomplete_record() {
+        assert!(matches!(
+            ExperimentRecord::from_json("{\"seed\": 1}"),
+            Err(NelderMeadError::InvalidJson { .. })
+        ));
+    }
+}
@@ -0,0 +1,398 @@
+extern crate rand;
+use rand::distributions::uniform::SampleUniform;
+#[cfg(feature = "std")]
+use rand::rngs::OsRng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use alloc::vec;
+use alloc::vec::Vec;
+use num_traits::float::FloatCore;
+
+use crate::algebra::*;
+#[cfg(feature = "std")]
+use crate::bounds::*;
+use crate::error::*;
+#[cfg(feature = "std")]
+use crate::params::*;
+
+/// The geometry of a starting simplex, without any function values.
+///
+/// An `InitialSimplex` can be built once and reused across several
+/// optimizations, each of which evaluates its vertices under its own
+/// objective.
+pub struct InitialSimplex<T = f64> {
+    pub points: Vec<Vec<T>>,
+}
+
+impl<T: FloatCore> InitialSimplex<T> {
+    /// Builds a random simplex of size `step` centered on `center`.
+    #[cfg(feature = "std")]
+    pub fn new(center: Vec<T>, step: T) -> InitialSimplex<T>
+    where
+        T: Float,
+    {
+        let mut rng = OsRng::new().expect("Failed to create the RNG");
+        InitialSimplex::with_rng(center, step, &mut rng)
+    }
+
+    /// Builds a random simplex of size `step` centered on `center`, drawing
+    /// the vertices from a generator seeded with `seed`.
+    ///
+    /// The same seed always builds the same simplex.
+    pub fn seeded(center: Vec<T>, step: T, seed: u64) -> InitialSimplex<T>
+    where
+        T: SampleUniform,
+    {
+        InitialSimplex::with_rng(center, step, &mut StdRng::seed_from_u64(seed))
+    }
+
+    /// Builds a random simplex of size `step` centered on `center`, drawing
+    /// the vertices from `rng`.
+    ///
+    /// Unlike `new`, this doesn't need the operating system's random
+    /// generator, so it is available without the `std` feature.
+    pub fn with_rng(center: Vec<T>, step: T, rng: &mut impl Rng) -> InitialSimplex<T>
+    where
+        T: SampleUniform,
+    {
+        let points = (0..center.len() + 1)
+            .map(|_| {
+                center
+                    .iter()
+                    .map(|&x| x + rng.gen_range(-step, step))
+                    .collect()
+            })
+            .collect();
+        InitialSimplex { points }
+    }
+
+    /// Builds a simplex of size `step` centered on `center`, as configured
+    /// by `params`.
+    ///
+    /// The simplex is axis-aligned with `Params::axis_aligned_simplex`, and
+    /// otherwise random, seeded with `Params::seed` when it is set.
+    #[cfg(feature = "std")]
+    pub fn from_params(center: Vec<T>, step: T, params: &Params<T>) -> InitialSimplex<T>
+    where
+        T: Float,
+    {
+        if params.axis_aligned_simplex {
+            return InitialSimplex::axis_aligned(center, step);
+        }
+        match params.seed {
+            Some(seed) => InitialSimplex::seeded(center, step, seed),
+            None => InitialSimplex::new(center, step),
+        }
+    }
+
+    /// Builds a random simplex of size `step` centered on `center`, whose
+    /// vertices are all within `bounds` and satisfy `feasible`.
+    ///
+    /// Candidate vertices are sampled like in `new`, rejecting the infeasible
+    /// ones. Fails if fewer than `n + 1` feasible vertices are found after
+    /// `max_attempts` samples.
+    #[cfg(feature = "std")]
+    pub fn new_feasible(
+        center: Vec<T>,
+        step: T,
+        bounds: &Bounds<T>,
+        feasible: impl Fn(&[T]) -> bool,
+        max_attempts: u32,
+    ) -> Result<InitialSimplex<T>, NelderMeadError>
+    where
+        T: Float,
+    {
+        let mut rng = OsRng::new().expect("Failed to create the RNG");
+        let bounds_vec = bounds.as_vec();
+        let needed = center.len() + 1;
+        let mut points: Vec<Vec<T>> = Vec::new();
+        for _ in 0..max_attempts {
+            if points.len() == needed {
+                break;
+            }
+            let candidate: Vec<T> = center
+                .iter()
+                .map(|&x| x + rng.gen_range(-step, step))
+                .collect();
+            let within_bounds = candidate
+                .iter()
+                .zip(bounds_vec.iter())
+                .all(|(x, (min, max))| min <= x && x <= max);
+            if within_bounds && feasible(&candidate) {
+                points.push(candidate);
+            }
+        }
+        if points.len() < needed {
+            return Err(NelderMeadError::InfeasibleInitialSimplex {
+                found: points.len(),
+                needed,
+            });
+        }
+        Ok(InitialSimplex { points })
+    }
+
+    /// Builds a simplex whose vertices are `center` and `center` moved along
+    /// each axis, as in Pfeffer's method.
+    ///
+    /// The step along axis `i` is `pct * |center[i]|`, or `zero_step` when
+    /// `center[i]` is zero.
+    pub fn pfeffer(center: Vec<T>, pct: T, zero_step: T) -> InitialSimplex<T> {
+        let mut points = vec![center.clone()];
+        for i in 0..center.len() {
+            let mut point = center.clone();
+            point[i] = point[i]
+                + if center[i] == T::zero() {
+                    zero_step
+                } else {
+                    pct * center[i].abs()
+                };
+            points.push(point);
+        }
+        InitialSimplex { points }
+    }
+
+    /// Builds a simplex whose vertices are `center` and `center` moved by
+    /// `step` along each axis.
+    pub fn axis_aligned(center: Vec<T>, step: T) -> InitialSimplex<T> {
+        let mut points = vec![center.clone()];
+        for i in 0..center.len() {
+            let mut point = center.clone();
+            point[i] = point[i] + step;
+            points.push(point);
+        }
+        InitialSimplex { points }
+    }
+
+    /// Builds a simplex like `axis_aligned`, but moving along the axes of a
+    /// random orthonormal basis instead, drawn from a generator seeded with
+    /// `seed`.
+    ///
+    /// The basis is built by applying Gram-Schmidt to random vectors, so the
+    /// same seed always builds the same simplex.
+    #[cfg(feature = "std")]
+    pub fn rotated(center: Vec<T>, step: T, seed: u64) -> InitialSimplex<T>
+    where
+        T: Float,
+    {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let n = center.len();
+        let mut basis: Vec<Vec<T>> = Vec::new();
+        while basis.len() < n {
+            let mut v: Vec<T> = (0..n).map(|_| rng.gen_range(-T::one(), T::one())).collect();
+            for u in &basis {
+                let dot: T = v.iter().zip(u.iter()).map(|(&vi, &ui)| vi * ui).sum();
+                v = diff(&v, &mult(dot, u));
+            }
+            let norm = distance(&v, &vec![T::zero(); n]);
+            // retry nearly dependent vectors, which would lose precision
+            if norm > T::from_f64(1e-6) {
+                basis.push(mult(T::one() / norm, &v));
+            }
+        }
+        let mut points = vec![center.clone()];
+        for u in basis {
+            points.push(sum(&center, &mult(step, &u)));
+        }
+        InitialSimplex { points }
+    }
+
+    /// Builds a simplex whose vertices are `center` and `center + p` for each
+    /// of the `perturbations`.
+    ///
+    /// Fails unless there are exactly `n` perturbations of dimension `n`,
+    /// where `n` is the dimension of `center`.
+    pub fn from_perturbations(
+        center: Vec<T>,
+        perturbations: Vec<Vec<T>>,
+    ) -> Result<InitialSimplex<T>, NelderMeadError> {
+        let n = center.len();
+        if perturbations.len() != n {
+            return Err(NelderMeadError::DimensionMismatch {
+                expected: n,
+                found: perturbations.len(),
+            });
+        }
+        let mut points = vec![center.clone()];
+        for perturbation in perturbations {
+            if perturbation.len() != n {
+                return Err(NelderMeadError::DimensionMismatch {
+                    expected: n,
+                    found: perturbation.len(),
+                });
+            }
+            points.push(sum(&center, &perturbation));
+        }
+        Ok(InitialSimplex { points })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_from_user_rng() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let initial_simplex = InitialSimplex::with_rng(vec![1.0, 2.0], 0.5, &mut rng);
+        assert_eq!(
+            initial_simplex.points,
+            InitialSimplex::seeded(vec![1.0, 2.0], 0.5, 3).points
+        );
+        assert!(initial_simplex
+            .points
+            .iter()
+            .all(|x| (x[0] - 1.0).abs() <= 0.5 && (x[1] - 2.0).abs() <= 0.5));
+    }
+
+    #[test]
+    fn build_from_perturbations() {
+        let initial_simplex = InitialSimplex::from_perturbations(
+            vec![1.0, 2.0],
+            vec![vec![0.5, 0.0], vec![0.25, 1.0]],
+        )
+        .unwrap();
+        assert_eq!(
+            initial_simplex.points,
+            vec![vec![1.0, 2.0], vec![1.5, 2.0], vec![1.25, 3.0]]
+        );
+    }
+
+    #[test]
+    fn build_pfeffer_simplex() {
+        let initial_simplex = InitialSimplex::pfeffer(vec![2.0, 0.0, -10.0], 0.05, 0.00025);
+        assert_eq!(
+            initial_simplex.points,
+            vec![
+                vec![2.0, 0.0, -10.0],
+                vec![2.1, 0.0, -10.0],
+                vec![2.0, 0.00025, -10.0],
+                vec![2.0, 0.0, -9.5],
+            ]
+        );
+    }
+
+    #[test]
+    fn build_orthonormal_rotated_simplex() {
+        let center = vec![1.0, 2.0, 3.0];
+        let initial_simplex = InitialSimplex::rotated(center.clone(), 0.5, 7);
+        assert_eq!(initial_simplex.points[0], center);
+        let edges: Vec<Vec<f64>> = initial_simplex.points[1..]
+            .iter()
+            .map(|x| diff(x, &center))
+            .collect();
+        for (i, a) in edges.iter().enumerate() {
+            for (j, b) in edges.iter().enumerate() {
+                let dot: f64 = a.iter().zip(b.iter()).map(|(ai, bi)| ai * bi).sum();
+                let expected = if i == j { 0.25 } else { 0.0 };
+                assert!((dot - expected).abs() < 1e-12);
+            }
+        }
+        assert_eq!(
+            InitialSimplex::rotated(center.clone(), 0.5, 7).points,
+            initial_simplex.points
+        );
+        assert_ne!(
+            InitialSimplex::rotated(center, 0.5, 8).points,
+            initial_simplex.points
+        );
+    }
+
+    #[test]
+    fn seed_simplex_from_params() {
+        let params = Params {
+            seed: Some(42),
+            ..Default::default()
+        };
+        let seeded = InitialSimplex::seeded(vec![1.0, 2.0], 0.5, 42);
+        assert_eq!(
+            InitialSimplex::from_params(vec![1.0, 2.0], 0.5, &params).points,
+            seeded.points
+        );
+        assert_ne!(
+            InitialSimplex::from_params(vec![1.0, 2.0], 0.5, &Default::default()).points,
+            seeded.points
+        );
+    }
+
+    #[test]
+    fn choose_axis_aligned_simplex_from_params() {
+        let params = Params {
+            axis_aligned_simplex: true,
+            seed: Some(42),
+            ..Default::default()
+        };
+        let simplex = InitialSimplex::from_params(vec![1.0, 2.0, 3.0], 0.5, &params);
+        assert_eq!(
+            simplex.points,
+            vec![
+                vec![1.0, 2.0, 3.0],
+                vec![1.5, 2.0, 3.0],
+                vec![1.0, 2.5, 3.0],
+                vec![1.0, 2.0, 3.5],
+            ]
+        );
+    }
+
+    #[test]
+    fn reproduce_seeded_simplex() {
+        let first = InitialSimplex::seeded(vec![1.0, 2.0], 0.5, 42);
+        let second = InitialSimplex::seeded(vec![1.0, 2.0], 0.5, 42);
+        let other = InitialSimplex::seeded(vec![1.0, 2.0], 0.5, 43);
+        assert_eq!(first.points, second.points);
+        assert_ne!(first.points, other.points);
+    }
+
+    #[test]
+    fn sample_feasible_vertices() {
+        let bounds = Bounds {
+            min: vec![0.0, 0.0],
+            max: vec![2.0, 2.0],
+        };
+        // carve out the lower left part of the box
+        let feasible = |x: &[f64]| x[0] + x[1] >= 1.5;
+        let initial_simplex =
+            InitialSimplex::new_feasible(vec![1.0, 1.0], 1.5, &bounds, feasible, 10000).unwrap();
+        assert_eq!(initial_simplex.points.len(), 3);
+        assert!(initial_simplex
+            .points
+            .iter()
+            .all(|x| { feasible(x) && x.iter().all(|c| (0.0..=2.0).contains(c)) }));
+    }
+
+    #[test]
+    fn fail_without_enough_feasible_vertices() {
+        let result =
+            InitialSimplex::new_feasible(vec![1.0, 1.0], 0.5, &Bounds::none(2), |_| false, 100);
+        assert_eq!(
+            result.err(),
+            Some(NelderMeadError::InfeasibleInitialSimplex {
+                found: 0,
+                needed: 3
+            })
+        );
+    }
+
+    #[test]
+    fn reject_wrong_number_of_perturbations() {
+        assert_eq!(
+            InitialSimplex::from_perturbations(vec![1.0, 2.0], vec![vec![0.5, 0.0]]).err(),
+            Some(NelderMeadError::DimensionMismatch {
+                expected: 2,
+                found: 1
+            })
+        );
+    }
+
+    #[test]
+    fn reject_wrong_perturbation_dimension() {
+        assert_eq!(
+            InitialSimplex::from_perturbations(vec![1.0, 2.0], vec![vec![0.5, 0.0], vec![1.0]])
+                .err(),
+            Some(NelderMeadError::DimensionMismatch {
+                expected: 2,
+                found: 1
+            })
+        );
+    }
+}
@@ -1,21 +1,21 @@
 //! A [Nelder-Mead method][neldermead]implementation in Rust.
 //!
-//! Allows fast minimization/maximization of `Vec<f64> -> f64` functions.
+//! Allows fast minimization/maximization of `&[f64] -> f64` functions, or
+//! of `&[f32] -> f32` ones.
 //!
 //! # Basic usage
 //!
 //! ```
 //! use nelder_mead::*;
-//! use nelder_mead::params::*;
 //!
 //! use assert_approx_eq::assert_approx_eq;
 //!
 //! // minimize (x+1)^2 + y^2
 //! let (x, fx) = minimize_unbounded(
-//!    |args| (args[0]+1.0) * (args[0]+1.0) + args[1]*args[1],
+//!    |args: &[f64]| (args[0]+1.0) * (args[0]+1.0) + args[1]*args[1],
 //!    vec![5.0,5.0],
 //!    1.0,
-//!    Params::default(),
+//!    Default::default(),
 //!    1000);
 //!
 //! // expected minimum: f(-1, 0) = 0
@@ -24,171 +24,94 @@
 //! assert_approx_eq!(fx, 0.0);
 //! ```
 //!
+//! # Single precision
+//!
+//! The entry points, `Params`, `Bounds` and the results are generic over
+//! the `Float` type of the points and values, which defaults to `f64`. The
+//! type is inferred from the objective, so a closure calling methods on its
+//! arguments with untyped literals as the initial point needs its argument
+//! type, like `|args: &[f64]|` above, or the type on the entry point:
+//!
+//! ```
+//! use nelder_mead::*;
+//!
+//! let (x, fx) = minimize_unbounded::<f32>(
+//!    |args| (args[0] + 1.0).powi(2) + args[1].abs(),
+//!    vec![5.0, 5.0],
+//!    1.0,
+//!    Default::default(),
+//!    1000);
+//!
+//! assert!((x[0] + 1.0).abs() < 1e-3);
+//! assert!(fx < 1e-3);
+//! ```
+//!
+//! # `no_std`
+//!
+//! The entry points above need the standard library, through the `std`
+//! feature enabled by default. Without it, the crate only depends on
+//! `core` and `alloc`, and keeps the fixed-size optimizer of `array`, the
+//! `bounds`, `params`, `diff` and `util` modules, and the `InitialSimplex`
+//! geometries that don't need the operating system's random generator,
+//! such as `InitialSimplex::axis_aligned` or `InitialSimplex::with_rng`.
+//!
 //! [neldermead]: https://en.wikipedia.org/wiki/Nelder%E2%80%93Mead_method
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+extern crate rand;
+
 mod algebra;
+pub mod array;
 pub mod bounds;
+#[cfg(feature = "std")]
+pub mod callback;
+#[cfg(all(feature = "std", feature = "columnar"))]
+pub mod columnar;
+#[cfg(feature = "std")]
+mod constraints;
+pub mod diff;
+#[cfg(feature = "std")]
+mod entry_points;
+pub mod error;
+#[cfg(feature = "std")]
+mod evaluation;
+#[cfg(all(feature = "std", feature = "serde"))]
+pub mod experiment;
+pub mod initial_simplex;
+#[cfg(feature = "std")]
+pub mod multistart;
+#[cfg(feature = "std")]
+pub mod optimizer;
+#[cfg(all(feature = "std", feature = "rayon"))]
+pub mod parallel;
 pub mod params;
+#[cfg(feature = "std")]
+mod reduction;
+#[cfg(feature = "std")]
+pub mod result;
+#[cfg(feature = "serde")]
+mod serialization;
+#[cfg(feature = "std")]
 mod simplex;
+#[cfg(all(feature = "std", feature = "stream"))]
+pub mod stream;
+#[cfg(feature = "std")]
+mod transform;
+#[cfg(feature = "std")]
+pub mod typed;
+pub mod util;
 
-use crate::bounds::*;
-use crate::params::*;
-use crate::simplex::*;
-
-/// Minimizes a function `f`,
-/// starting with a simplex of size `initial_simplex_size` centered on
-/// `initial_point`.
-///
-/// The search space is bounded by a `Bounds` definition.
-///
-/// # Example
-///
-/// ```
-/// use nelder_mead::*;
-/// use nelder_mead::bounds::*;
-/// use nelder_mead::params::*;
-///
-/// use assert_approx_eq::assert_approx_eq;
-///
-/// // minimize (x+1)^2 + y^2
-/// let (x, fx) = minimize(
-///    |args| (args[0]+1.0) * (args[0]+1.0) + args[1]*args[1],
-///    vec![5.0,5.0],
-///    1.0,
-///    Params::default(),
-///    Bounds {min: vec![0.0, 0.0], max: vec![10.0, 10.0]},
-///    1000);
-///
-/// // expected bounded minimum: f(0, 0) = 1
-/// assert_approx_eq!(x[0], 0.0);
-/// assert_approx_eq!(x[1], 0.0);
-/// assert_approx_eq!(fx, 1.0);
-/// ```
-pub fn minimize(
-    f: impl Fn(&Vec<f64>) -> f64,
-    initial_point: Vec<f64>,
-    initial_simplex_size: f64,
-    params: Params,
-    bounds: Bounds,
-    max_iter: u32,
-) -> (Vec<f64>, f64) {
-    let initial_simplex = new_simplex(&f, initial_point, initial_simplex_size);
-    crate::simplex::minimize(&f, initial_simplex, params, bounds, max_iter)
-}
-
-/// Maximizes a function `f`,
-/// starting with a simplex of size `initial_simplex_size` centered on
-/// `initial_point`.
-///
-/// The search space is bounded by a `Bounds` definition.
-///
-/// # Example
-///
-/// ```
-/// use nelder_mead::*;
-/// use nelder_mead::bounds::*;
-/// use nelder_mead::params::*;
-///
-/// use assert_approx_eq::assert_approx_eq;
-///
-/// // maximize -2.0 * ((x+1)^2 + y^2)
-/// let (x, fx) = maximize(
-///    |args| -2.0 * ((args[0]+1.0) * (args[0]+1.0) + args[1]*args[1]),
-///    vec![5.0,5.0],
-///    1.0,
-///    Params::default(),
-///    Bounds {min: vec![0.0, 0.0], max: vec![10.0, 10.0]},
-///    1000);
-///
-/// // expected bounded maximum: f(0, 0) = -2
-/// assert_approx_eq!(x[0], 0.0);
-/// assert_approx_eq!(x[1], 0.0);
-/// assert_approx_eq!(fx, -2.0);
-/// ```
-pub fn maximize(
-    f: impl Fn(&Vec<f64>) -> f64,
-    initial_point: Vec<f64>,
-    initial_simplex_size: f64,
-    params: Params,
-    bounds: Bounds,
-    max_iter: u32,
-) -> (Vec<f64>, f64) {
-    let g: &(Fn(&Vec<f64>) -> f64) = &(|x| -1.0 * f(x));
-    let initial_simplex = new_simplex(&g, initial_point, initial_simplex_size);
-    let (x, gx) = crate::simplex::minimize(&g, initial_simplex, params, bounds, max_iter);
-    (x, -1.0 * gx)
-}
-
-/// Minimizes a function `f`,
-/// starting with a simplex of size `initial_simplex_size` centered on
-/// `initial_point`.
-///
-/// # Example
-///
-/// ```
-/// use nelder_mead::*;
-/// use nelder_mead::bounds::*;
-/// use nelder_mead::params::*;
-///
-/// use assert_approx_eq::assert_approx_eq;
-///
-/// // minimize (x+1)^2 + y^2
-/// let (x, fx) = minimize_unbounded(
-///    |args| (args[0]+1.0) * (args[0]+1.0) + args[1]*args[1],
-///    vec![5.0,5.0],
-///    1.0,
-///    Params::default(),
-///    1000);
-///
-/// // expected bounded minimum: f(-1, 0) = 0
-/// assert_approx_eq!(x[0], -1.0);
-/// assert_approx_eq!(x[1], 0.0);
-/// assert_approx_eq!(fx, 0.0);
-/// ```
-pub fn minimize_unbounded(
-    f: impl Fn(&Vec<f64>) -> f64,
-    initial_point: Vec<f64>,
-    initial_simplex_size: f64,
-    params: Params,
-    max_iter: u32,
-) -> (Vec<f64>, f64) {
-    let bounds = Bounds::none(initial_point.len());
-    minimize(f, initial_point, initial_simplex_size, params, bounds, max_iter)
-}
+#[cfg(feature = "std")]
+pub use crate::algebra::Float;
+#[cfg(feature = "std")]
+pub use crate::constraints::PenaltyGrowth;
+#[cfg(feature = "std")]
+pub use crate::entry_points::*;
+#[cfg(feature = "std")]
+pub use crate::simplex::Simplex;
 
-/// Maximizes a function `f`,
-/// starting with a simplex of size `initial_simplex_size` centered on
-/// `initial_point`.
-///
-/// # Example
-///
-/// ```
-/// use nelder_mead::*;
-/// use nelder_mead::bounds::*;
-/// use nelder_mead::params::*;
-///
-/// use assert_approx_eq::assert_approx_eq;
-///
-/// // maximize -2.0 * ((x+1)^2 + y^2)
-/// let (x, fx) = maximize_unbounded(
-///    |args| -2.0 * ((args[0]+1.0) * (args[0]+1.0) + args[1]*args[1]),
-///    vec![5.0,5.0],
-///    1.0,
-///    Params::default(),
-///    1000);
-///
-/// // expected bounded maximum: f(-1, 0) = 0
-/// assert_approx_eq!(x[0], -1.0);
-/// assert_approx_eq!(x[1], 0.0);
-/// assert_approx_eq!(fx, 0.0);
-/// ```
-pub fn maximize_unbounded(
-    f: impl Fn(&Vec<f64>) -> f64,
-    initial_point: Vec<f64>,
-    initial_simplex_size: f64,
-    params: Params,
-    max_iter: u32,
-) -> (Vec<f64>, f64) {
-    let bounds = Bounds::none(initial_point.len());
-    maximize(f, initial_point, initial_simplex_size, params, bounds, max_iter)
-}
+/// An objective or constraint function over a point.
+pub type Function<T = f64> = dyn Fn(&[T]) -> T;
@@ -0,0 +1,361 @@
+//! Optimization from several starting points, which run in parallel with
+//! the `rayon` feature.
+//!
+//! Every start builds its initial simplex from its own seed, derived from a
+//! base seed and the index of the start, and the best result is chosen by
+//! value and then by index. This way, the result does not depend on the
+//! order in which the starts finish, and parallel runs give the same results
+//! as sequential ones.
+
+#[cfg(feature = "rayon")]
+extern crate rayon;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::bounds::*;
+use crate::initial_simplex::*;
+use crate::params::*;
+use crate::result::*;
+use crate::simplex::*;
+
+/// Minimizes a function `f` from each of the `initial_points`, with
+/// simplices of size `initial_simplex_size`, returning the best result.
+///
+/// The simplex for the start at index `i` is seeded with
+/// `seed.wrapping_add(i)`. As long as `f` is deterministic and
+/// `Params::local_minimum_probes` is not set, the same seed always gives
+/// the same result, whether or not the `rayon` feature is enabled.
+///
+/// The search space is bounded by a `Bounds` definition.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::bounds::*;
+/// use nelder_mead::multistart::*;
+///
+/// use assert_approx_eq::assert_approx_eq;
+///
+/// // minimize a function with two basins, the deepest one at x = 2
+/// let result = minimize_multistart(
+///    |args| ((args[0] + 2.0).powi(2) + 1.0).min((args[0] - 2.0).powi(2)),
+///    &[vec![-3.0], vec![3.0]],
+///    0.5,
+///    &Default::default(),
+///    &Bounds::none(1),
+///    1000,
+///    42);
+///
+/// assert_approx_eq!(result.best_point()[0], 2.0);
+/// assert_approx_eq!(result.best_value(), 0.0);
+/// ```
+pub fn minimize_multistart(
+    f: impl Fn(&[f64]) -> f64 + Sync,
+    initial_points: &[Vec<f64>],
+    initial_simplex_size: f64,
+    params: &Params,
+    bounds: &Bounds,
+    max_iter: u32,
+    seed: u64,
+) -> OptimizationResult {
+    minimize_multistart_all(
+        f,
+        initial_points,
+        initial_simplex_size,
+        params,
+        bounds,
+        max_iter,
+        seed,
+    )
+    .into_iter()
+    .enumerate()
+    .min_by(|(i, x), (j, y)| x.value.total_cmp(&y.value).then(i.cmp(j)))
+    .map(|(_, result)| result)
+    .expect("There must be at least one initial point")
+}
+
+/// Minimizes a function `f` from each of the `initial_points`, like
+/// `minimize_multistart`, but returns the result of every start, in the
+/// order of `initial_points`.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::bounds::*;
+/// use nelder_mead::multistart::*;
+///
+/// use assert_approx_eq::assert_approx_eq;
+///
+/// // minimize a function with two basins, at x = -2 and x = 2
+/// let results = minimize_multistart_all(
+///    |args| ((args[0] + 2.0).powi(2) + 1.0).min((args[0] - 2.0).powi(2)),
+///    &[vec![-3.0], vec![3.0]],
+///    0.5,
+///    &Default::default(),
+///    &Bounds::none(1),
+///    1000,
+///    42);
+///
+/// assert_approx_eq!(results[0].best_point()[0], -2.0);
+/// assert_approx_eq!(results[1].best_point()[0], 2.0);
+/// ```
+pub fn minimize_multistart_all(
+    f: impl Fn(&[f64]) -> f64 + Sync,
+    initial_points: &[Vec<f64>],
+    initial_simplex_size: f64,
+    params: &Params,
+    bounds: &Bounds,
+    max_iter: u32,
+    seed: u64,
+) -> Vec<OptimizationResult> {
+    let run = |(i, initial_point): (usize, &Vec<f64>)| {
+        let initial_simplex = InitialSimplex::seeded(
+            initial_point.clone(),
+            initial_simplex_size,
+            seed.wrapping_add(i as u64),
+        );
+        minimize(
+            &f,
+            evaluate_initial_simplex(&f, &initial_simplex, params),
+            params.clone(),
+            bounds.clone(),
+            max_iter,
+        )
+    };
+    #[cfg(feature = "rayon")]
+    let results = initial_points.par_iter().enumerate().map(run).collect();
+    #[cfg(not(feature = "rayon"))]
+    let results = initial_points.iter().enumerate().map(run).collect();
+    results
+}
+
+/// Draws `starts` random points, uniformly within `bounds`, from a
+/// generator seeded with `seed`.
+///
+/// Along dimensions where a bound is infinite, the points are drawn within
+/// `radius` of `center` instead, still within the finite bound if there is
+/// one. The same seed always draws the same points.
+pub fn random_starts(
+    center: &[f64],
+    radius: f64,
+    bounds: &Bounds,
+    starts: u32,
+    seed: u64,
+) -> Vec<Vec<f64>> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let ranges: Vec<(f64, f64)> = bounds
+        .as_vec()
+        .into_iter()
+        .zip(center.iter())
+        .map(|((min, max), c)| {
+            if min.is_finite() && max.is_finite() {
+                (min, max)
+            } else {
+                ((c - radius).max(min), (c + radius).min(max))
+            }
+        })
+        .collect();
+    (0..starts)
+        .map(|_| {
+            ranges
+                .iter()
+                .map(|&(min, max)| {
+                    if min < max {
+                        rng.gen_range(min, max)
+                    } else {
+                        min
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Minimizes a function `f` from `starts` random initial points, drawn by
+/// `random_starts`, with simplices of size `initial_simplex_size`,
+/// returning the best result.
+///
+/// The points are drawn with `seed`, which also seeds the simplices like in
+/// `minimize_multistart`, so the same seed always gives the same result.
+/// To get the result of every start, pass the points of `random_starts` to
+/// `minimize_multistart_all`.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::bounds::*;
+/// use nelder_mead::multistart::*;
+///
+/// use assert_approx_eq::assert_approx_eq;
+///
+/// // minimize a function with two basins, the deepest one at x = 2
+/// let result = minimize_random_multistart(
+///    |args| ((args[0] + 2.0).powi(2) + 1.0).min((args[0] - 2.0).powi(2)),
+///    &[0.0],
+///    5.0,
+///    0.5,
+///    &Default::default(),
+///    &Bounds { min: vec![-5.0], max: vec![5.0] },
+///    10,
+///    1000,
+///    42);
+///
+/// assert_approx_eq!(result.best_point()[0], 2.0);
+/// assert_approx_eq!(result.best_value(), 0.0);
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn minimize_random_multistart(
+    f: impl Fn(&[f64]) -> f64 + Sync,
+    center: &[f64],
+    radius: f64,
+    initial_simplex_size: f64,
+    params: &Params,
+    bounds: &Bounds,
+    starts: u32,
+    max_iter: u32,
+    seed: u64,
+) -> OptimizationResult {
+    let initial_points = random_starts(center, radius, bounds, starts, seed);
+    minimize_multistart(
+        f,
+        &initial_points,
+        initial_simplex_size,
+        params,
+        bounds,
+        max_iter,
+        seed,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rastrigin(args: &[f64]) -> f64 {
+        args.iter()
+            .map(|x| x * x - 10.0 * (2.0 * std::f64::consts::PI * x).cos() + 10.0)
+            .sum()
+    }
+
+    #[test]
+    fn find_global_minimum_from_curated_starts() {
+        // two basins, the deepest one around (3, -1)
+        let f = |x: &[f64]| {
+            let shallow = (x[0] + 2.0).powi(2) + (x[1] - 2.0).powi(2) - 1.0;
+            let deep = (x[0] - 3.0).powi(2) + (x[1] + 1.0).powi(2) - 2.0;
+            shallow.min(deep)
+        };
+        let starts = [vec![-2.5, 2.5], vec![2.5, -0.5]];
+        let params = Params {
+            f_atol: 1e-12,
+            ..Default::default()
+        };
+        let bounds = Bounds::none(2);
+
+        let results = minimize_multistart_all(f, &starts, 0.3, &params, &bounds, 1000, 1);
+        assert_eq!(results.len(), 2);
+        assert!((results[0].point[0] + 2.0).abs() < 1e-4);
+        assert!((results[0].value + 1.0).abs() < 1e-6);
+        assert!((results[1].point[0] - 3.0).abs() < 1e-4);
+        assert!((results[1].value + 2.0).abs() < 1e-6);
+
+        let best = minimize_multistart(f, &starts, 0.3, &params, &bounds, 1000, 1);
+        assert_eq!(best.point, results[1].point);
+        assert_eq!(best.value, results[1].value);
+    }
+
+    #[test]
+    fn reproduce_results_with_the_same_seed() {
+        let initial_points: Vec<Vec<f64>> = (0..16)
+            .map(|i| vec![i as f64 - 8.0, 3.0 - i as f64 / 2.0])
+            .collect();
+        let params = Default::default();
+        let bounds = Bounds::none(2);
+        let f = |x: &[f64]| rastrigin(x);
+        let run = || minimize_multistart(f, &initial_points, 0.5, &params, &bounds, 300, 7);
+
+        let first = run();
+        for _ in 0..5 {
+            let other = run();
+            assert_eq!(other.point, first.point);
+            assert_eq!(other.value.to_bits(), first.value.to_bits());
+        }
+
+        // the same as running every start one after the other
+        let sequential = initial_points
+            .iter()
+            .enumerate()
+            .map(|(i, initial_point)| {
+                let initial_simplex =
+                    InitialSimplex::seeded(initial_point.clone(), 0.5, 7 + i as u64);
+                minimize(
+                    f,
+                    evaluate_simplex(f, &initial_simplex),
+                    params.clone(),
+                    bounds.clone(),
+                    300,
+                )
+            })
+            .fold(
+                None,
+                |best: Option<OptimizationResult>, result| match best {
+                    Some(best) if best.value <= result.value => Some(best),
+                    _ => Some(result),
+                },
+            )
+            .unwrap();
+        assert_eq!(first.point, sequential.point);
+        assert_eq!(first.value.to_bits(), sequential.value.to_bits());
+    }
+
+    #[test]
+    fn find_global_minimum_from_random_starts() {
+        let bounds = Bounds {
+            min: vec![-5.12, -5.12],
+            max: vec![5.12, 5.12],
+        };
+        let params = Params {
+            f_atol: 1e-12,
+            ..Default::default()
+        };
+        let run = || {
+            minimize_random_multistart(
+                rastrigin,
+                &[0.0, 0.0],
+                1.0,
+                0.3,
+                &params,
+                &bounds,
+                50,
+                1000,
+                3,
+            )
+        };
+
+        let best = run();
+        assert!(best.value < 1e-6);
+        assert!(best.point.iter().all(|x| x.abs() < 1e-3));
+        let other = run();
+        assert_eq!(other.point, best.point);
+        assert_eq!(other.value.to_bits(), best.value.to_bits());
+    }
+
+    #[test]
+    fn sample_around_center_along_infinite_bounds() {
+        let bounds = Bounds {
+            min: vec![-1.0, f64::NEG_INFINITY, 0.0],
+            max: vec![1.0, f64::INFINITY, f64::INFINITY],
+        };
+        let starts = random_starts(&[0.0, 100.0, 0.5], 2.0, &bounds, 200, 9);
+        assert_eq!(starts.len(), 200);
+        assert!(starts.iter().all(|x| -1.0 <= x[0] && x[0] <= 1.0));
+        assert!(starts.iter().all(|x| 98.0 <= x[1] && x[1] <= 102.0));
+        assert!(starts.iter().all(|x| 0.0 <= x[2] && x[2] <= 2.5));
+        assert_eq!(
+            starts,
+            random_starts(&[0.0, 100.0, 0.5], 2.0, &bounds, 200, 9)
+        );
+    }
+}
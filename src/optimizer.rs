@@ -0,0 +1,175 @@
+//! Step-by-step optimization, driven one iteration at a time by the caller.
+
+use crate::bounds::*;
+use crate::params::*;
+use crate::result::*;
+use crate::simplex::*;
+
+/// A Nelder-Mead optimization that performs one iteration on every call to
+/// `step`, leaving the termination to the caller.
+///
+/// As an `Iterator`, it steps on every call to `next`, yielding the best
+/// value after that step, and never ends on its own.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::bounds::*;
+/// use nelder_mead::optimizer::*;
+///
+/// use assert_approx_eq::assert_approx_eq;
+///
+/// // minimize (x+1)^2 + y^2, until the best value is below 1e-12
+/// let mut optimizer = Optimizer::new(
+///    |args| (args[0]+1.0) * (args[0]+1.0) + args[1]*args[1],
+///    vec![5.0, 5.0],
+///    1.0,
+///    Default::default(),
+///    Bounds::none(2));
+///
+/// while optimizer.best().1 > 1e-12 && optimizer.iteration() < 1000 {
+///     optimizer.step();
+/// }
+///
+/// let (point, value) = optimizer.best();
+/// assert_approx_eq!(point[0], -1.0);
+/// assert_approx_eq!(point[1], 0.0);
+/// assert!(value <= 1e-12);
+/// ```
+pub struct Optimizer<F: Fn(&[f64]) -> f64> {
+    f: F,
+    simplex: Simplex,
+    params: Params,
+    bounds_vec: Vec<(f64, f64)>,
+    iteration: u32,
+}
+
+impl<F: Fn(&[f64]) -> f64> Optimizer<F> {
+    /// Starts minimizing a function `f`,
+    /// with a simplex of size `initial_simplex_size` centered on
+    /// `initial_point`.
+    ///
+    /// The search space is bounded by a `Bounds` definition.
+    pub fn new(
+        f: F,
+        initial_point: Vec<f64>,
+        initial_simplex_size: f64,
+        params: Params,
+        bounds: Bounds,
+    ) -> Optimizer<F> {
+        let (simplex, _) =
+            crate::entry_points::simplex_around(&f, initial_point, initial_simplex_size, &params);
+        Optimizer::from_simplex(f, simplex, params, bounds)
+    }
+
+    /// Resumes minimizing a function `f` from an already evaluated
+    /// `simplex`, such as one saved from `simplex` at a checkpoint.
+    pub fn from_simplex(f: F, simplex: Simplex, params: Params, bounds: Bounds) -> Optimizer<F> {
+        let mut simplex = simplex;
+        sort_simplex(&mut simplex);
+        Optimizer {
+            f,
+            simplex,
+            params,
+            bounds_vec: bounds.as_vec(),
+            iteration: 0,
+        }
+    }
+
+    /// Performs exactly one Nelder-Mead iteration, returning the kind of
+    /// step it took.
+    pub fn step(&mut self) -> StepKind {
+        let n = self.simplex.len() - 1;
+        let simplex = std::mem::take(&mut self.simplex);
+        let (simplex, step_kind) = step(&self.f, simplex, &self.params, &self.bounds_vec, n);
+        self.simplex = simplex;
+        self.iteration += 1;
+        step_kind
+    }
+
+    /// The best vertex of the current simplex and its value.
+    pub fn best(&self) -> (&[f64], f64) {
+        let (x, fx) = &self.simplex[0];
+        (x, *fx)
+    }
+
+    /// The current simplex, sorted from best to worst vertex.
+    pub fn simplex(&self) -> &Simplex {
+        &self.simplex
+    }
+
+    /// Number of iterations performed so far.
+    pub fn iteration(&self) -> u32 {
+        self.iteration
+    }
+
+    /// Whether the spread of the current simplex falls within the
+    /// tolerances of `Params`.
+    pub fn converged(&self) -> bool {
+        converged(&self.simplex, &self.params, 1.0)
+    }
+}
+
+impl<F: Fn(&[f64]) -> f64> Iterator for Optimizer<F> {
+    type Item = f64;
+
+    fn next(&mut self) -> Option<f64> {
+        self.step();
+        Some(self.best().1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate assert_approx_eq;
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    fn f(args: &[f64]) -> f64 {
+        (args[0] - 1.0).powi(2) + (args[1] + 2.0).powi(2)
+    }
+
+    fn params() -> Params {
+        Params {
+            axis_aligned_simplex: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn match_minimize_step_by_step() {
+        let mut optimizer = Optimizer::new(f, vec![5.0, 5.0], 1.0, params(), Bounds::none(2));
+        for _ in 0..200 {
+            optimizer.step();
+        }
+        let (expected, fexpected) =
+            crate::minimize(f, vec![5.0, 5.0], 1.0, params(), Bounds::none(2), 200);
+        assert_eq!(optimizer.iteration(), 200);
+        assert_eq!(optimizer.best().0, expected.as_slice());
+        assert_eq!(optimizer.best().1, fexpected);
+    }
+
+    #[test]
+    fn yield_non_increasing_best_values() {
+        let optimizer = Optimizer::new(f, vec![5.0, 5.0], 1.0, params(), Bounds::none(2));
+        let values: Vec<f64> = optimizer.take(300).collect();
+        assert_eq!(values.len(), 300);
+        assert!(values.windows(2).all(|pair| pair[1] <= pair[0]));
+        assert_approx_eq!(values[299], 0.0);
+    }
+
+    #[test]
+    fn resume_from_checkpoint() {
+        let mut optimizer = Optimizer::new(f, vec![5.0, 5.0], 1.0, params(), Bounds::none(2));
+        for _ in 0..20 {
+            optimizer.step();
+        }
+        let mut resumed =
+            Optimizer::from_simplex(f, optimizer.simplex().clone(), params(), Bounds::none(2));
+        for _ in 0..20 {
+            assert_eq!(resumed.step(), optimizer.step());
+        }
+        assert_eq!(resumed.simplex(), optimizer.simplex());
+        assert_eq!(resumed.iteration(), 20);
+    }
+}
@@ -0,0 +1,132 @@
+//! Optimization that evaluates independent points in parallel, available
+//! with the `rayon` feature.
+//!
+//! Only the vertices of the initial simplex, the shrunk vertices of a
+//! shrink step and the vertices of a restarted simplex are independent of
+//! each other, so those are the evaluations spread over the threads. This
+//! pays off for objectives that are slow enough to dominate the cost of the
+//! parallelism.
+
+extern crate rayon;
+use rayon::prelude::*;
+
+use crate::bounds::*;
+use crate::callback::*;
+use crate::params::*;
+use crate::result::*;
+use crate::simplex::*;
+
+/// Minimizes a function `f`,
+/// starting with a simplex of size `initial_simplex_size` centered on
+/// `initial_point`, like `minimize_with_report`, but evaluating the initial
+/// and the shrunk vertices in parallel.
+///
+/// The search space is bounded by a `Bounds` definition.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::bounds::*;
+/// use nelder_mead::parallel::*;
+///
+/// use assert_approx_eq::assert_approx_eq;
+///
+/// // minimize (x+1)^2 + y^2
+/// let result = minimize_parallel(
+///    |args| (args[0]+1.0) * (args[0]+1.0) + args[1]*args[1],
+///    vec![5.0,5.0],
+///    1.0,
+///    Default::default(),
+///    Bounds::none(2),
+///    1000);
+///
+/// // expected minimum: f(-1, 0) = 0
+/// assert_approx_eq!(result.best_point()[0], -1.0);
+/// assert_approx_eq!(result.best_point()[1], 0.0);
+/// assert_approx_eq!(result.best_value(), 0.0);
+/// ```
+pub fn minimize_parallel(
+    f: impl Fn(&[f64]) -> f64 + Sync,
+    initial_point: Vec<f64>,
+    initial_simplex_size: f64,
+    params: Params,
+    bounds: Bounds,
+    max_iter: u32,
+) -> OptimizationResult {
+    let f_all = |xs: &[Vec<f64>]| -> Vec<f64> { xs.par_iter().map(|x| f(x)).collect() };
+    let (initial_simplex, initial_value) = crate::entry_points::simplex_around_with(
+        f_all,
+        initial_point.clone(),
+        initial_simplex_size,
+        &params,
+    );
+    let record_evaluations = params.record_evaluations;
+    let mut result = minimize_batched(&f, f_all, initial_simplex, params, bounds, max_iter, |_| {
+        Action::Continue
+    });
+    crate::entry_points::report_initial_value(
+        &f,
+        &mut result,
+        initial_point,
+        initial_value,
+        record_evaluations,
+    );
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[test]
+    fn match_sequential_run() {
+        let f = |args: &[f64]| (args[0] - 1.0).powi(2) + 10.0 * (args[1] + 2.0).powi(2);
+        let params = Params {
+            axis_aligned_simplex: true,
+            exact_cache: true,
+            max_evaluations: Some(150),
+            record_evaluations: true,
+            ..Default::default()
+        };
+        let parallel =
+            minimize_parallel(f, vec![5.0, 5.0], 1.0, params.clone(), Bounds::none(2), 300);
+        let sequential =
+            crate::minimize_with_report(f, vec![5.0, 5.0], 1.0, params, Bounds::none(2), 300);
+        assert_eq!(parallel.point, sequential.point);
+        assert_eq!(parallel.value, sequential.value);
+        assert_eq!(parallel.iterations, sequential.iterations);
+        assert_eq!(parallel.evaluations, sequential.evaluations);
+        assert_eq!(parallel.evaluation_history, sequential.evaluation_history);
+        assert_eq!(parallel.termination, TerminationReason::MaxEvaluations);
+    }
+
+    #[test]
+    fn evaluate_shrunk_vertices_concurrently() {
+        // shrinks on every step, since no trial point is ever better
+        let running = AtomicUsize::new(0);
+        let most_running = AtomicUsize::new(0);
+        let f = |args: &[f64]| {
+            let now_running = running.fetch_add(1, Ordering::SeqCst) + 1;
+            most_running.fetch_max(now_running, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(20));
+            running.fetch_sub(1, Ordering::SeqCst);
+            if args.iter().all(|x| *x == 0.0) {
+                0.0
+            } else {
+                1.0
+            }
+        };
+        let params = Params {
+            axis_aligned_simplex: true,
+            ..Default::default()
+        };
+        let result = minimize_parallel(f, vec![0.0; 8], 1.0, params, Bounds::none(8), 3);
+        assert_eq!(result.point, vec![0.0; 8]);
+        assert_eq!(result.value, 0.0);
+        if rayon::current_num_threads() > 1 {
+            assert!(most_running.load(Ordering::SeqCst) > 1);
+        }
+    }
+}
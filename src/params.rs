@@ -1,17 +1,614 @@
-pub struct Params {
-    pub alpha: f64,
-    pub gamma: f64,
-    pub rho: f64,
-    pub delta: f64,
+use alloc::vec::Vec;
+use core::time::Duration;
+use num_traits::float::FloatCore;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "std")]
+use crate::algebra::Float;
+use crate::error::*;
+
+/// Largest number of grid dimensions searched by `snap_result_to_grid`.
+///
+/// Beyond it, the `3^k` neighboring grid points are too many to evaluate,
+/// and the result is only rounded to the grid.
+pub const MAX_GRID_SEARCH_DIMENSIONS: usize = 8;
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        default,
+        deny_unknown_fields,
+        bound(deserialize = "T: FloatCore + Deserialize<'de>")
+    )
+)]
+pub struct Params<T = f64> {
+    pub alpha: T,
+    pub gamma: T,
+    pub rho: T,
+    pub delta: T,
+    /// Absolute tolerance on the spread of function values in the simplex.
+    ///
+    /// The optimization stops once the spread is at most
+    /// `f_atol + f_rtol * |best value|`. Setting both tolerances to 0
+    /// disables this check.
+    pub f_atol: T,
+    /// Relative tolerance on the spread of function values in the simplex.
+    pub f_rtol: T,
+    /// Tolerance on the diameter of the simplex.
+    ///
+    /// When above 0, the optimization only stops once the diameter is at
+    /// most `x_atol` as well as the values being within `f_atol` and
+    /// `f_rtol`, or once the diameter alone is small enough if both value
+    /// tolerances are 0.
+    pub x_atol: T,
+    /// Maximum number of calls to the objective.
+    ///
+    /// The search itself never exceeds the budget, even in the middle of an
+    /// iteration: once the next point doesn't fit in it, the objective isn't
+    /// called anymore, the remaining trial points count as infinitely bad
+    /// and the optimization stops before the next iteration. The final
+    /// checks and refinements of the result, such as `integer_refinement`
+    /// and `local_minimum_probes`, are skipped unless the rest of the budget
+    /// affords all of their evaluations. Only the entry points that evaluate
+    /// the result again without some penalty, like `minimize_with_penalty`,
+    /// make those calls on top of the budget.
+    ///
+    /// With `exact_cache` or `cache_tolerance`, values served from the cache
+    /// aren't calls, so only distinct points count against this budget.
+    pub max_evaluations: Option<u32>,
+    /// Maximum number of distinct points evaluated, counting the vertices
+    /// of the initial simplex.
+    ///
+    /// Unlike `max_evaluations`, a point resampled with `resample` counts
+    /// once. The budget is enforced like `max_evaluations`, and values
+    /// served from the cache of `exact_cache` or `cache_tolerance` don't
+    /// count against it either.
+    pub max_distinct_evals: Option<u64>,
+    /// Whether to loosen `f_atol` and `f_rtol` as the `max_evaluations`
+    /// budget runs out, scaling them by the inverse of the fraction of the
+    /// budget left.
+    pub adaptive_tolerance: bool,
+    /// Window size and flatness tolerance used to stop once every value
+    /// evaluated during the last `window` iterations differs by less than
+    /// `flat_tol`.
+    pub stop_on_plateau: Option<(u32, T)>,
+    /// Number of iterations without improving the best value after which
+    /// the simplex is rebuilt around the best vertex.
+    ///
+    /// The new simplex is built like the initial one, with the size of the
+    /// initial simplex, measured as the largest coordinate offset of a
+    /// vertex from its best vertex. Restarts are counted in
+    /// `OptimizationResult::restarts`.
+    pub restart_after: Option<u32>,
+    /// Whether to cache function values by the exact bit patterns of the
+    /// evaluated coordinates, so that no point is evaluated twice.
+    ///
+    /// Only meant for deterministic objectives.
+    pub exact_cache: bool,
+    /// Coordinate tolerance of a cache of function values, in which points
+    /// whose coordinates round to the same multiples of the tolerance share
+    /// the value of the first one evaluated. The result's value is still the
+    /// value at the result's point.
+    ///
+    /// Takes over `exact_cache` when both are set. Only meant for
+    /// objectives that are deterministic and barely change within the
+    /// tolerance, which must be finite and positive.
+    pub cache_tolerance: Option<T>,
+    /// When set to `Some((k, aggregate))`, evaluates the objective `k` times
+    /// at every point, the vertices of the initial simplex included, and uses
+    /// the mean or the median of the samples as the value of the point.
+    ///
+    /// For noisy objectives, whose single evaluations misrank the vertices.
+    /// Every sample counts as an evaluation.
+    pub resample: Option<(u32, Aggregate)>,
+    /// Whether to track how fast the simplex diameter shrinks, reported in
+    /// `OptimizationResult::diameter_ratio` and
+    /// `OptimizationResult::half_life_iteration`.
+    pub track_diameter: bool,
+    /// Tolerance used to check, before optimizing, that evaluating the
+    /// objective twice at the initial point gives the same value.
+    ///
+    /// Only checked by `try_minimize`.
+    pub check_determinism: Option<T>,
+    /// Mask of the dimensions that must take integer values in the result.
+    ///
+    /// After the optimization, those dimensions are rounded and every
+    /// combination of their neighboring integers (±1) is searched for the
+    /// best point within the bounds. This takes up to `3^k` evaluations,
+    /// where `k` is the number of integer dimensions, and is skipped
+    /// (keeping only the rounding) when `k` exceeds
+    /// `MAX_GRID_SEARCH_DIMENSIONS`.
+    pub integer_refinement: Option<Vec<bool>>,
+    /// Grid spacing along each dimension that the result must lie on, where
+    /// a non-positive spacing leaves that dimension continuous.
+    ///
+    /// After the optimization, those dimensions are rounded to the nearest
+    /// multiple of their spacing and every combination of ±1 grid steps is
+    /// searched for the best point within the bounds. This takes up to `3^k`
+    /// evaluations, where `k` is the number of grid dimensions, and is
+    /// skipped (keeping only the rounding) when `k` exceeds
+    /// `MAX_GRID_SEARCH_DIMENSIONS`.
+    pub snap_result_to_grid: Option<Vec<T>>,
+    /// Number of random probes and probe radius used to check, after the
+    /// optimization, whether the result is likely a local minimum.
+    ///
+    /// The probes are clamped to the bounds, drawn from a generator seeded
+    /// with `seed` when it is set, and count as evaluations.
+    pub local_minimum_probes: Option<(u32, T)>,
+    /// Whether to record the kind of every step taken in
+    /// `OptimizationResult::step_history`.
+    ///
+    /// With the `log` feature, every step is also logged at the debug level,
+    /// along with its centroid and the trial points it evaluated.
+    pub record_step_history: bool,
+    /// Number of best vertices that stay in place during a shrink, while
+    /// the others are contracted towards the best one.
+    ///
+    /// Values below 1 are treated as 1.
+    pub shrink_keep: usize,
+    /// Whether a shrink only replaces a vertex when the contracted point is
+    /// better, keeping the original vertex otherwise.
+    ///
+    /// A shrink that improves no vertex leaves the simplex unchanged.
+    pub greedy_shrink: bool,
+    /// When set to `Some(window)`, shrinks the simplex whenever its vertices,
+    /// compared at single precision, repeat those of one of the last `window`
+    /// iterations.
+    pub detect_cycles: Option<u32>,
+    /// Finite set of allowed values for each dimension, or `None` for
+    /// continuous dimensions.
+    ///
+    /// The simplex still moves continuously, but every point is snapped to
+    /// the nearest allowed values before being evaluated, and the result is
+    /// snapped as well.
+    pub allowed_values: Option<Vec<Option<Vec<T>>>>,
+    /// Whether to divide the objective by the magnitude of its value at the
+    /// best vertex of the initial simplex, so that the search works with
+    /// values around 1.
+    ///
+    /// The reported values are in the original scale, but `f_atol` applies
+    /// to the scaled values. Ignored when that value is zero or not finite,
+    /// and by `optimizer::Optimizer` and `stream::minimize_stream`, which
+    /// only apply the options that shape the iterations themselves.
+    pub scale_objective: bool,
+    /// Whether to center and scale the objective with the values of the
+    /// initial simplex, subtracting their mean and dividing by their range,
+    /// so that the search works with values around 0 and of spread 1.
+    ///
+    /// This conditions the tolerance checks of objectives with a large
+    /// offset, like values around `1e6 ± 1`. The reported values are in the
+    /// original scale, but `f_atol` and `f_rtol` apply to the transformed
+    /// values. Takes precedence over `scale_objective`, and is ignored when
+    /// the initial values are all equal or not finite, and by the same entry
+    /// points.
+    pub affine_objective: bool,
+    /// Which vertex is reflected on every iteration.
+    pub reflection_target: ReflectionTarget,
+    /// Time budget for the iterations.
+    ///
+    /// The average time per evaluation is measured as the optimization
+    /// runs, and no new iteration is started unless it, along with the final
+    /// evaluation of the result, is projected to finish within the budget.
+    /// When the budget stops the optimization, the best point so far is
+    /// returned with `TerminationReason::Deadline`.
+    ///
+    /// To stop at an absolute deadline, such as the end of a frame, set it
+    /// to the time left until then.
+    pub max_duration: Option<Duration>,
+    /// Monotone transform applied to the function values when comparing
+    /// them with `f_atol` and `f_rtol`.
+    ///
+    /// This only changes when the optimization stops, not the values it
+    /// minimizes.
+    pub convergence_transform: ConvergenceTransform,
+    /// Minimum improvement rate and maximum number of extra iterations used
+    /// to keep going past `max_iter` while the optimization is still making
+    /// fast progress.
+    ///
+    /// The improvement rate is the relative decrease of the mean value of
+    /// the simplex per iteration, averaged over the last `n + 1`
+    /// iterations. Once `max_iter` iterations are performed, the
+    /// optimization only continues while this rate is at least the
+    /// threshold, for up to the given number of extra iterations. A rate
+    /// that is not finite, as with infinite values, counts as no
+    /// improvement.
+    pub extend_if_improving: Option<(T, u32)>,
+    /// Simplex diameter below which the optimization stops, for objectives
+    /// whose noise makes smaller steps meaningless.
+    pub min_simplex_size: Option<T>,
+    /// Whether to record every call to the objective in
+    /// `OptimizationResult::evaluation_history`.
+    pub record_evaluations: bool,
+    /// Seed of the random initial simplex built by the entry points that
+    /// start from a single point, or `None` to draw it from the OS.
+    ///
+    /// With a seed and a deterministic objective, runs are reproducible.
+    pub seed: Option<u64>,
+    /// Whether the entry points that start from a single point build an
+    /// axis-aligned initial simplex, with vertices `center` and
+    /// `center + step * e_i`, instead of a random one.
+    ///
+    /// This simplex is deterministic and never degenerate.
+    pub axis_aligned_simplex: bool,
+    /// Whether the entry points that start from a single point make it a
+    /// vertex of the initial simplex, in place of one of the random ones, so
+    /// that its value is known without evaluating it again.
+    ///
+    /// Otherwise, the initial value is reported at the cost of one more
+    /// evaluation.
+    pub initial_point_as_vertex: bool,
+    /// How trial points that fall outside the bounds are brought back in.
+    pub boundary_mode: BoundaryMode,
+}
+
+/// A monotone transform of the function values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ConvergenceTransform {
+    /// Compare the values themselves.
+    Identity,
+    /// Compare the logarithm of the values, which must be positive.
+    Log,
+    /// Compare `sign(v) * ln(1 + |v|)`, which handles values of any sign.
+    SignedLog1p,
+}
+
+impl ConvergenceTransform {
+    #[cfg(feature = "std")]
+    pub fn apply<T: Float>(self, value: T) -> T {
+        match self {
+            ConvergenceTransform::Identity => value,
+            ConvergenceTransform::Log => value.ln(),
+            ConvergenceTransform::SignedLog1p => value.signum() * value.abs().ln_1p(),
+        }
+    }
+}
+
+/// How the samples of `Params::resample` are combined into one value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Aggregate {
+    /// The mean of the samples.
+    Mean,
+    /// The median of the samples, which outliers barely move.
+    Median,
 }
 
-impl Params {
-    pub fn default() -> Params {
+/// The vertex reflected through the centroid of the others.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ReflectionTarget {
+    /// Always reflect the worst vertex, as in the standard algorithm.
+    Worst,
+    /// Reflect the second worst vertex once every new vertex has ended up
+    /// being the worst for the given number of consecutive iterations.
+    SecondWorstWhenStuck(u32),
+}
+
+/// How a trial point outside the bounds is brought back in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum BoundaryMode {
+    /// Clamp every coordinate to its bounds.
+    Clamp,
+    /// Mirror the overshoot of every coordinate back inside its bounds.
+    ///
+    /// Unlike clamping, distinct trial points stay distinct instead of
+    /// piling up on the boundary, which keeps the simplex from flattening
+    /// against it.
+    Reflect,
+    /// Shorten the step from the centroid so that it stops at the boundary,
+    /// keeping its direction.
+    Truncate,
+    /// Run the given number of iterations with each of `Clamp`, `Reflect`
+    /// and `Truncate`, then continue with the one that reached the lowest
+    /// value, from where its probe stopped.
+    ///
+    /// The probes share the iterations, `max_evaluations` and
+    /// `max_duration` of the whole run.
+    Auto(u32),
+    /// Optimize without bounds over transformed coordinates, which map back
+    /// within the bounds through a `tanh` for dimensions bounded on both
+    /// sides and through `sqrt(u^2 + 1)` for dimensions bounded on one side.
+    ///
+    /// No trial point is ever moved onto the boundary, so the simplex never
+    /// sticks to it, although a minimum on the boundary is only reached
+    /// once the transformed coordinates saturate. Every point reported to
+    /// the callback and in the result is in the original coordinates.
+    /// `Action::SetBounds` is ignored, since the transform is fixed by the
+    /// initial bounds.
+    Transform,
+}
+
+/// What `minimize_fallible` does when the objective returns an error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorPolicy {
+    /// Treat the point as infeasible, sorting it after every other vertex
+    /// of the simplex.
+    Worst,
+    /// Stop the optimization at the end of the iteration, returning the
+    /// error without calling the objective again.
+    Abort,
+}
+
+/// The standard coefficients, with every optional feature turned off.
+///
+/// As a `Default` implementation, `Params` can be part of structs that
+/// derive `Default`:
+///
+/// ```
+/// use nelder_mead::params::*;
+///
+/// #[derive(Default)]
+/// struct Settings {
+///     params: Params,
+///     max_iter: u32,
+/// }
+///
+/// let settings = Settings::default();
+/// assert_eq!(settings.params.alpha, 1.0);
+/// assert_eq!(settings.params.gamma, 2.0);
+/// assert_eq!(settings.params.rho, 0.5);
+/// assert_eq!(settings.params.delta, 0.5);
+/// ```
+impl<T: FloatCore> Default for Params<T> {
+    fn default() -> Params<T> {
+        let half = T::one() / (T::one() + T::one());
         Params {
-            alpha: 1.0,
-            gamma: 2.0,
-            rho: 0.5,
-            delta: 0.5,
+            alpha: T::one(),
+            gamma: T::one() + T::one(),
+            rho: half,
+            delta: half,
+            f_atol: T::zero(),
+            f_rtol: T::zero(),
+            x_atol: T::zero(),
+            max_evaluations: None,
+            max_distinct_evals: None,
+            adaptive_tolerance: false,
+            stop_on_plateau: None,
+            restart_after: None,
+            exact_cache: false,
+            cache_tolerance: None,
+            resample: None,
+            track_diameter: false,
+            check_determinism: None,
+            integer_refinement: None,
+            snap_result_to_grid: None,
+            local_minimum_probes: None,
+            record_step_history: false,
+            shrink_keep: 1,
+            greedy_shrink: false,
+            detect_cycles: None,
+            allowed_values: None,
+            scale_objective: false,
+            affine_objective: false,
+            reflection_target: ReflectionTarget::Worst,
+            max_duration: None,
+            convergence_transform: ConvergenceTransform::Identity,
+            extend_if_improving: None,
+            min_simplex_size: None,
+            record_evaluations: false,
+            seed: None,
+            axis_aligned_simplex: false,
+            initial_point_as_vertex: false,
+            boundary_mode: BoundaryMode::Clamp,
+        }
+    }
+}
+
+impl<T: FloatCore> Params<T> {
+    /// The standard coefficients, like the `Default` implementation, which
+    /// replaces this method.
+    #[deprecated(note = "use the `Default` implementation instead")]
+    #[allow(clippy::should_implement_trait)]
+    pub fn default() -> Params<T> {
+        <Params<T> as Default>::default()
+    }
+
+    /// The adaptive coefficients of Gao and Han for a problem of `n`
+    /// dimensions: `alpha = 1`, `gamma = 1 + 2 / n`, `rho = 0.75 - 1 / (2n)`
+    /// and `delta = 1 - 1 / n`, with every optional feature turned off.
+    ///
+    /// The standard coefficients of the `Default` implementation make the
+    /// simplex collapse prematurely as the dimension grows, so these are
+    /// preferable from around 5 dimensions onwards. With `n = 2` they are the
+    /// standard coefficients, and they are degenerate with `n = 1`, where a
+    /// shrink collapses the simplex.
+    pub fn adaptive(n: usize) -> Params<T> {
+        let n: T = T::from(n).unwrap();
+        let (one, two) = (T::one(), T::one() + T::one());
+        Params {
+            alpha: one,
+            gamma: one + two / n,
+            rho: T::from(0.75).unwrap() - one / (two * n),
+            delta: one - one / n,
+            ..Default::default()
+        }
+    }
+
+    /// Starts building `Params` from the defaults, validating the
+    /// coefficients of the steps once built.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nelder_mead::params::*;
+    ///
+    /// let params = Params::builder().gamma(3.0).rho(0.25).build().unwrap();
+    /// assert_eq!(params.alpha, 1.0);
+    /// assert_eq!(params.gamma, 3.0);
+    /// assert_eq!(params.rho, 0.25);
+    ///
+    /// // expansions must go further than reflections
+    /// assert!(Params::builder().gamma(0.5).build().is_err());
+    /// ```
+    pub fn builder() -> ParamsBuilder<T> {
+        ParamsBuilder {
+            params: Default::default(),
         }
     }
 }
+
+/// Builds `Params`, checking that the coefficients keep the steps
+/// meaningful.
+///
+/// Every coefficient that isn't set keeps its default value.
+#[derive(Clone)]
+pub struct ParamsBuilder<T = f64> {
+    params: Params<T>,
+}
+
+impl<T: FloatCore> ParamsBuilder<T> {
+    /// Sets the reflection coefficient, which must be positive.
+    pub fn alpha(mut self, alpha: T) -> ParamsBuilder<T> {
+        self.params.alpha = alpha;
+        self
+    }
+
+    /// Sets the expansion coefficient, which must be greater than 1 and than
+    /// the reflection coefficient.
+    pub fn gamma(mut self, gamma: T) -> ParamsBuilder<T> {
+        self.params.gamma = gamma;
+        self
+    }
+
+    /// Sets the contraction coefficient, which must be between 0 and 1.
+    pub fn rho(mut self, rho: T) -> ParamsBuilder<T> {
+        self.params.rho = rho;
+        self
+    }
+
+    /// Sets the shrink coefficient, which must be between 0 and 1.
+    pub fn delta(mut self, delta: T) -> ParamsBuilder<T> {
+        self.params.delta = delta;
+        self
+    }
+
+    /// Builds the `Params`, failing on the first coefficient out of its
+    /// range.
+    pub fn build(self) -> Result<Params<T>, NelderMeadError> {
+        let params = self.params;
+        let unit = |x: T| x > T::zero() && x < T::one();
+        let checks = [
+            ("alpha", params.alpha, params.alpha > T::zero(), "> 0"),
+            ("gamma", params.gamma, params.gamma > T::one(), "> 1"),
+            (
+                "gamma",
+                params.gamma,
+                params.gamma > params.alpha,
+                "> alpha",
+            ),
+            ("rho", params.rho, unit(params.rho), "in (0, 1)"),
+            ("delta", params.delta, unit(params.delta), "in (0, 1)"),
+        ];
+        match checks.iter().find(|(_, _, valid, _)| !valid) {
+            Some((name, value, _, expected)) => Err(NelderMeadError::InvalidCoefficient {
+                name,
+                value: value.to_f64().unwrap(),
+                expected,
+            }),
+            None => Ok(params),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bounds::*;
+    use crate::initial_simplex::*;
+    use crate::simplex::{evaluate_simplex, minimize};
+
+    #[test]
+    fn scale_adaptive_coefficients_with_dimension() {
+        let params: Params = Params::adaptive(4);
+        assert_eq!(params.alpha, 1.0);
+        assert_eq!(params.gamma, 1.5);
+        assert_eq!(params.rho, 0.625);
+        assert_eq!(params.delta, 0.75);
+        assert!(Params::builder()
+            .gamma(params.gamma)
+            .rho(params.rho)
+            .delta(params.delta)
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn outperform_default_coefficients_in_high_dimension() {
+        let rosenbrock = |x: &[f64]| {
+            x.windows(2)
+                .map(|pair| 100.0 * (pair[1] - pair[0] * pair[0]).powi(2) + (1.0 - pair[0]).powi(2))
+                .sum::<f64>()
+        };
+        let run = |params: Params| {
+            let initial_simplex = InitialSimplex::axis_aligned(vec![-1.0; 10], 0.5);
+            minimize(
+                rosenbrock,
+                evaluate_simplex(rosenbrock, &initial_simplex),
+                params,
+                Bounds::none(10),
+                2000,
+            )
+        };
+        let standard = run(Default::default());
+        let adaptive = run(Params::adaptive(10));
+        assert!(adaptive.value < standard.value / 100.0);
+    }
+
+    #[test]
+    fn fill_unset_coefficients_with_defaults() {
+        let params = Params::builder().alpha(1.5).delta(0.25).build().unwrap();
+        assert_eq!(params.alpha, 1.5);
+        assert_eq!(params.gamma, 2.0);
+        assert_eq!(params.rho, 0.5);
+        assert_eq!(params.delta, 0.25);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn forward_deprecated_default() {
+        let deprecated: Params = Params::default();
+        let standard: Params = Default::default();
+        assert_eq!(deprecated.alpha, standard.alpha);
+        assert_eq!(deprecated.gamma, standard.gamma);
+        assert_eq!(deprecated.rho, standard.rho);
+        assert_eq!(deprecated.delta, standard.delta);
+    }
+
+    #[test]
+    fn reject_coefficients_out_of_range() {
+        let invalid = |name, value, expected| {
+            Err(NelderMeadError::InvalidCoefficient {
+                name,
+                value,
+                expected,
+            })
+        };
+        let build = |builder: ParamsBuilder| builder.build().map(|_| ());
+        assert_eq!(
+            build(Params::builder().alpha(0.0)),
+            invalid("alpha", 0.0, "> 0")
+        );
+        assert_eq!(
+            build(Params::builder().gamma(1.0)),
+            invalid("gamma", 1.0, "> 1")
+        );
+        assert_eq!(
+            build(Params::builder().alpha(3.0).gamma(2.0)),
+            invalid("gamma", 2.0, "> alpha")
+        );
+        assert_eq!(
+            build(Params::builder().rho(1.0)),
+            invalid("rho", 1.0, "in (0, 1)")
+        );
+        assert_eq!(
+            build(Params::builder().delta(-0.5)),
+            invalid("delta", -0.5, "in (0, 1)")
+        );
+        assert!(build(Params::builder().rho(f64::NAN)).is_err());
+    }
+}
@@ -0,0 +1,99 @@
+use num_traits::float::FloatCore;
+
+use crate::bounds::*;
+
+/// Maps points between the full search space and the reduced space of the
+/// dimensions that are not fixed by equal minimum and maximum bounds.
+pub struct Reduction<T = f64> {
+    fixed: Vec<Option<T>>,
+}
+
+impl<T: FloatCore> Reduction<T> {
+    /// Finds the fixed dimensions of `bounds`, returning `None` unless some,
+    /// but not all, of the dimensions are fixed.
+    pub fn from_bounds(bounds: &Bounds<T>) -> Option<Reduction<T>> {
+        let fixed: Vec<Option<T>> = bounds
+            .as_vec()
+            .into_iter()
+            .map(|(min, max)| if min == max { Some(min) } else { None })
+            .collect();
+        let free = fixed.iter().filter(|value| value.is_none()).count();
+        if free == 0 || free == fixed.len() {
+            None
+        } else {
+            Some(Reduction { fixed })
+        }
+    }
+
+    /// Number of dimensions that are not fixed.
+    pub fn free_dimensions(&self) -> usize {
+        self.fixed.iter().filter(|value| value.is_none()).count()
+    }
+
+    /// Keeps only the free elements of a per-dimension vector.
+    pub fn reduce<C: Clone>(&self, x: &[C]) -> Vec<C> {
+        x.iter()
+            .zip(self.fixed.iter())
+            .filter(|(_, value)| value.is_none())
+            .map(|(c, _)| c.clone())
+            .collect()
+    }
+
+    /// Rebuilds a full point from its free coordinates, filling the fixed
+    /// dimensions with their values.
+    pub fn expand(&self, x: &[T]) -> Vec<T> {
+        let mut free = x.iter();
+        self.fixed
+            .iter()
+            .map(|value| match value {
+                Some(value) => *value,
+                None => *free.next().expect("Missing free coordinate"),
+            })
+            .collect()
+    }
+
+    pub fn reduce_bounds(&self, bounds: &Bounds<T>) -> Bounds<T> {
+        Bounds {
+            min: self.reduce(&bounds.min),
+            max: self.reduce(&bounds.max),
+        }
+    }
+
+    pub fn expand_bounds(&self, bounds: &Bounds<T>) -> Bounds<T> {
+        Bounds {
+            min: self.expand(&bounds.min),
+            max: self.expand(&bounds.max),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_between_full_and_reduced_points() {
+        let bounds = Bounds {
+            min: vec![0.0, 2.0, -1.0],
+            max: vec![1.0, 2.0, 1.0],
+        };
+        let reduction = Reduction::from_bounds(&bounds).unwrap();
+        assert_eq!(reduction.free_dimensions(), 2);
+        assert_eq!(reduction.reduce(&[0.5, 2.0, 0.25]), vec![0.5, 0.25]);
+        assert_eq!(reduction.expand(&[0.5, 0.25]), vec![0.5, 2.0, 0.25]);
+        let reduced_bounds = reduction.reduce_bounds(&bounds);
+        assert_eq!(reduced_bounds.min, vec![0.0, -1.0]);
+        assert_eq!(reduced_bounds.max, vec![1.0, 1.0]);
+        assert_eq!(reduction.expand_bounds(&reduced_bounds).min, bounds.min);
+    }
+
+    #[test]
+    fn do_not_reduce_without_fixed_dimensions() {
+        assert!(Reduction::from_bounds(&Bounds::<f64>::none(2)).is_none());
+        let all_fixed = Bounds {
+            min: vec![1.0, 2.0],
+            max: vec![1.0, 2.0],
+        };
+        assert!(Reduction::from_bounds(&all_fixed).is_none());
+    }
+}
@@ -0,0 +1,821 @@
+extern crate rand;
+use rand::Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::algebra::*;
+use crate::bounds::*;
+use crate::initial_simplex::*;
+use crate::params::*;
+use crate::simplex::*;
+
+/// The outcome of an optimization run.
+///
+/// New diagnostics keep being added to this struct, so its fields are
+/// private and it is `#[non_exhaustive]`: outside of this crate it can only
+/// be built by the optimizer and read through its accessors, like
+/// `best_point` and `best_value`, which leaves its representation free to
+/// change.
+///
+/// ```compile_fail
+/// use nelder_mead::*;
+/// use nelder_mead::result::*;
+///
+/// let result = minimize_with_report(
+///    |args| args[0] * args[0],
+///    vec![1.0],
+///    0.5,
+///    Default::default(),
+///    bounds::Bounds::none(1),
+///    100);
+/// // fails to compile, since the fields are private, even when naming
+/// // every one of them
+/// let OptimizationResult {
+///     point,
+///     value,
+///     initial_point,
+///     initial_value,
+///     iterations,
+///     evaluations,
+///     termination,
+///     restarts,
+///     final_diameter,
+///     value_spread,
+///     feasible,
+///     cache_hits,
+///     cache_misses,
+///     nan_evaluations,
+///     likely_local_minimum,
+///     step_history,
+///     diameter_ratio,
+///     half_life_iteration,
+///     bounds,
+///     final_simplex,
+///     evaluation_history,
+///     boundary_mode,
+/// } = result;
+/// ```
+#[non_exhaustive]
+pub struct OptimizationResult<T = f64> {
+    pub(crate) point: Vec<T>,
+    pub(crate) value: T,
+    pub(crate) initial_point: Vec<T>,
+    pub(crate) initial_value: T,
+    pub(crate) iterations: u32,
+    pub(crate) evaluations: u32,
+    pub(crate) termination: TerminationReason,
+    pub(crate) restarts: u32,
+    pub(crate) final_diameter: T,
+    pub(crate) value_spread: T,
+    pub(crate) feasible: bool,
+    pub(crate) cache_hits: u32,
+    pub(crate) cache_misses: u32,
+    pub(crate) nan_evaluations: u32,
+    pub(crate) likely_local_minimum: bool,
+    pub(crate) step_history: Vec<StepKind>,
+    pub(crate) diameter_ratio: Option<T>,
+    pub(crate) half_life_iteration: Option<u32>,
+    pub(crate) bounds: Bounds<T>,
+    pub(crate) final_simplex: Simplex<T>,
+    pub(crate) evaluation_history: Vec<(Vec<T>, T)>,
+    pub(crate) boundary_mode: BoundaryMode,
+}
+
+/// Where a coordinate of the result sits relative to its bounds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoundStatus {
+    /// The coordinate is at its minimum.
+    Lower,
+    /// The coordinate is at its maximum.
+    Upper,
+    /// The coordinate is strictly between its minimum and maximum.
+    Interior,
+}
+
+/// The operation performed by a single Nelder-Mead iteration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StepKind {
+    /// The worst vertex was replaced by its reflection through the centroid.
+    Reflection,
+    /// The worst vertex was replaced by an expanded reflection.
+    Expansion,
+    /// The worst vertex was contracted towards the centroid.
+    Contraction,
+    /// Every vertex but the best was shrunk towards the best vertex.
+    Shrink,
+}
+
+/// The reason why an optimization stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TerminationReason {
+    /// `max_iter` iterations were performed.
+    MaxIterations,
+    /// `Params::max_evaluations` calls to the objective were made, or
+    /// `Params::max_distinct_evals` distinct points were evaluated.
+    MaxEvaluations,
+    /// The spread of function values fell within the tolerances.
+    Converged,
+    /// The evaluated values stayed flat, as configured by
+    /// `Params::stop_on_plateau`.
+    Plateau,
+    /// Another iteration would not have finished within
+    /// `Params::max_duration`.
+    Deadline,
+    /// The simplex diameter fell below `Params::min_simplex_size`.
+    SimplexTooSmall,
+    /// The per-iteration callback returned `Action::Abort`.
+    Aborted,
+}
+
+impl fmt::Display for TerminationReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let reason = match self {
+            TerminationReason::MaxIterations => "maximum iterations reached",
+            TerminationReason::MaxEvaluations => "maximum evaluations reached",
+            TerminationReason::Converged => "converged",
+            TerminationReason::Plateau => "plateau reached",
+            TerminationReason::Deadline => "deadline reached",
+            TerminationReason::SimplexTooSmall => "simplex too small",
+            TerminationReason::Aborted => "aborted",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+/// A report of the main diagnostics of an optimization run.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Summary<T = f64> {
+    pub point: Vec<T>,
+    pub value: T,
+    pub iterations: u32,
+    pub evaluations: u32,
+    pub termination: TerminationReason,
+    pub final_diameter: T,
+    pub value_spread: T,
+    pub feasible: bool,
+}
+
+impl<T: Float> fmt::Display for Summary<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "best point: {:?}", self.point)?;
+        writeln!(f, "best value: {}", self.value)?;
+        writeln!(f, "iterations: {}", self.iterations)?;
+        writeln!(f, "evaluations: {}", self.evaluations)?;
+        writeln!(f, "termination: {}", self.termination)?;
+        writeln!(f, "final diameter: {}", self.final_diameter)?;
+        writeln!(f, "value spread: {}", self.value_spread)?;
+        write!(f, "feasible: {}", self.feasible)
+    }
+}
+
+/// The number of evaluations a configuration takes, as estimated by
+/// `dry_run` without calling the objective.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EvaluationPlan {
+    /// Evaluations of the initial simplex.
+    pub initial: u32,
+    /// Evaluations of an iteration that ends with a reflection, or one more
+    /// when an expansion was tried and rejected.
+    pub reflection: u32,
+    /// Evaluations of an iteration that ends with an expansion.
+    pub expansion: u32,
+    /// Evaluations of an iteration that ends with a contraction.
+    pub contraction: u32,
+    /// Evaluations of an iteration that ends with a shrink, including the
+    /// extra shrink of `Params::detect_cycles`.
+    pub shrink: u32,
+    /// Evaluations after the iterations, to compare the centroid with the
+    /// best vertex, for the refinements and probes enabled in `Params` and
+    /// for the initial value, unless `Params::initial_point_as_vertex` is
+    /// set.
+    pub final_evaluations: u32,
+    /// Largest number of iterations, including the extension of
+    /// `Params::extend_if_improving` and the probes of
+    /// `BoundaryMode::Auto`.
+    pub max_iterations: u32,
+    /// Total evaluations when every iteration is a reflection.
+    pub best_case_total: u32,
+    /// Total evaluations when every iteration is a shrink, capped by
+    /// `Params::max_evaluations` and `Params::max_distinct_evals`.
+    pub worst_case_total: u32,
+}
+
+impl TerminationReason {
+    /// Whether the optimization stopped because one of its tolerances was
+    /// met, rather than because it ran out of iterations, evaluations or
+    /// time.
+    pub fn is_converged(self) -> bool {
+        match self {
+            TerminationReason::Converged
+            | TerminationReason::Plateau
+            | TerminationReason::SimplexTooSmall => true,
+            TerminationReason::MaxIterations
+            | TerminationReason::MaxEvaluations
+            | TerminationReason::Deadline
+            | TerminationReason::Aborted => false,
+        }
+    }
+}
+
+impl<T: Float> OptimizationResult<T> {
+    /// Whether the optimization stopped because one of its tolerances was
+    /// met, rather than because it ran out of its budget.
+    pub fn converged(&self) -> bool {
+        self.termination.is_converged()
+    }
+
+    /// The best point found.
+    pub fn best_point(&self) -> &[T] {
+        &self.point
+    }
+
+    /// The function value at `best_point`.
+    pub fn best_value(&self) -> T {
+        self.value
+    }
+
+    /// Number of iterations performed.
+    pub fn iterations(&self) -> u32 {
+        self.iterations
+    }
+
+    /// Number of calls to the objective, including the evaluation of the
+    /// initial simplex.
+    pub fn evaluations(&self) -> u32 {
+        self.evaluations
+    }
+
+    /// The vertices of the simplex at the end of the optimization, sorted
+    /// from best to worst, for instance to estimate the local curvature.
+    pub fn final_simplex(&self) -> &Simplex<T> {
+        &self.final_simplex
+    }
+
+    /// The point the optimization started from.
+    pub fn initial_point(&self) -> &[T] {
+        &self.initial_point
+    }
+
+    /// The function value at the initial point.
+    pub fn initial_value(&self) -> T {
+        self.initial_value
+    }
+
+    /// Why the optimization stopped.
+    pub fn termination(&self) -> TerminationReason {
+        self.termination
+    }
+
+    /// Number of times the simplex was rebuilt around the best vertex.
+    ///
+    /// Always 0 unless `Params::restart_after` is set.
+    pub fn restarts(&self) -> u32 {
+        self.restarts
+    }
+
+    /// The largest distance between two vertices of the final simplex.
+    pub fn final_diameter(&self) -> T {
+        self.final_diameter
+    }
+
+    /// The difference between the worst and the best function values of
+    /// the final simplex.
+    pub fn value_spread(&self) -> T {
+        self.value_spread
+    }
+
+    /// Whether `best_point` is within the bounds and `best_value` is finite.
+    pub fn feasible(&self) -> bool {
+        self.feasible
+    }
+
+    /// Number of evaluations served from the cache.
+    ///
+    /// Always 0 unless `Params::exact_cache` or `Params::cache_tolerance` is
+    /// set.
+    pub fn cache_hits(&self) -> u32 {
+        self.cache_hits
+    }
+
+    /// Number of evaluations that were not found in the cache.
+    ///
+    /// Always 0 unless `Params::exact_cache` or `Params::cache_tolerance` is
+    /// set.
+    pub fn cache_misses(&self) -> u32 {
+        self.cache_misses
+    }
+
+    /// Number of calls to the objective that returned `NaN`.
+    ///
+    /// Such points are treated as worse than any other, so they never
+    /// become the best vertex unless every value is `NaN`, but a non-zero
+    /// count means the objective misbehaved somewhere.
+    pub fn nan_evaluations(&self) -> u32 {
+        self.nan_evaluations
+    }
+
+    /// Whether random probes around `best_point` found a substantially
+    /// lower value, meaning that `best_point` is probably only a local
+    /// minimum.
+    ///
+    /// Always `false` unless `Params::local_minimum_probes` is set.
+    pub fn likely_local_minimum(&self) -> bool {
+        self.likely_local_minimum
+    }
+
+    /// The kind of step taken on every iteration, in order.
+    ///
+    /// Always empty unless `Params::record_step_history` is set.
+    pub fn step_history(&self) -> &[StepKind] {
+        &self.step_history
+    }
+
+    /// Ratio between the final and the initial diameter of the simplex.
+    ///
+    /// Always `None` unless `Params::track_diameter` is set.
+    pub fn diameter_ratio(&self) -> Option<T> {
+        self.diameter_ratio
+    }
+
+    /// First iteration at which the simplex diameter was at most half of
+    /// the initial one.
+    ///
+    /// Always `None` unless `Params::track_diameter` is set.
+    pub fn half_life_iteration(&self) -> Option<u32> {
+        self.half_life_iteration
+    }
+
+    /// The bounds in effect at the end of the optimization.
+    pub fn bounds(&self) -> &Bounds<T> {
+        &self.bounds
+    }
+
+    /// Every evaluated point and its value, in order, starting with the
+    /// vertices of the initial simplex.
+    ///
+    /// Holds one entry per call counted in `evaluations`, except that a
+    /// point resampled with `Params::resample` is recorded once, and that
+    /// the evaluations of the result without a penalty or a barrier, made
+    /// by the entry points that add one, aren't recorded.
+    ///
+    /// Always empty unless `Params::record_evaluations` is set.
+    pub fn evaluation_history(&self) -> &[(Vec<T>, T)] {
+        &self.evaluation_history
+    }
+
+    /// The boundary mode used, which is never `BoundaryMode::Auto`, since
+    /// that one resolves to the mode it selected.
+    pub fn boundary_mode(&self) -> BoundaryMode {
+        self.boundary_mode
+    }
+
+    /// Collects the main diagnostics of the run into a `Summary`, which can
+    /// be printed as a human-readable report.
+    pub fn summary(&self) -> Summary<T> {
+        Summary {
+            point: self.point.clone(),
+            value: self.value,
+            iterations: self.iterations,
+            evaluations: self.evaluations,
+            termination: self.termination,
+            final_diameter: self.final_diameter,
+            value_spread: self.value_spread,
+            feasible: self.feasible,
+        }
+    }
+
+    /// Checks, for every dimension, whether `point` is at one of its
+    /// bounds, meaning that the constraint is active.
+    ///
+    /// A coordinate counts as being at a bound when it is within `1e-8` of
+    /// it, relative to the magnitude of the bound.
+    pub fn active_constraints(&self) -> Vec<BoundStatus> {
+        let at = |x: T, bound: T| {
+            bound.is_finite() && (x - bound).abs() <= T::from_f64(1e-8) * bound.abs().max(T::one())
+        };
+        self.point
+            .iter()
+            .zip(self.bounds.as_vec())
+            .map(|(x, (min, max))| {
+                if at(*x, min) {
+                    BoundStatus::Lower
+                } else if at(*x, max) {
+                    BoundStatus::Upper
+                } else {
+                    BoundStatus::Interior
+                }
+            })
+            .collect()
+    }
+
+    /// Resumes the optimization from the final simplex, after re-evaluating
+    /// its vertices under a new objective `f`.
+    ///
+    /// Keeping the geometry of the simplex is cheaper than a cold restart
+    /// when `f` only changed slightly, like an objective that drifts over
+    /// time. The search space is bounded by the same `bounds`.
+    pub fn continue_with(
+        &self,
+        f: impl Fn(&[T]) -> T,
+        params: Params<T>,
+        max_iter: u32,
+    ) -> OptimizationResult<T> {
+        let points = self.final_simplex.iter().map(|(x, _)| x.clone()).collect();
+        let simplex = evaluate_initial_simplex(&f, &InitialSimplex { points }, &params);
+        minimize(&f, simplex, params, self.bounds.clone(), max_iter)
+    }
+
+    /// Estimates how much of the improvement from `initial_point` to `point`
+    /// comes from each coordinate.
+    ///
+    /// The contribution of a coordinate is the decrease of `f` when only
+    /// that coordinate of `initial_point` is moved to its final value. This
+    /// is a one-at-a-time approximation: it ignores the interactions between
+    /// coordinates, so the contributions only add up to the total
+    /// improvement when `f` is separable.
+    pub fn dimension_contributions(&self, f: impl Fn(&[T]) -> T) -> Vec<T> {
+        let initial_value = f(&self.initial_point);
+        (0..self.point.len())
+            .map(|i| {
+                let mut moved = self.initial_point.clone();
+                moved[i] = self.point[i];
+                initial_value - f(&moved)
+            })
+            .collect()
+    }
+
+    /// Finds the directions along which `f` is flat around `point`, leaving
+    /// the parameters along them unidentified.
+    ///
+    /// The curvature of `f` is estimated by central differences, stepping
+    /// along coordinate `i` by `step * max(|point[i]|, 1)`, and the
+    /// eigenvectors of this Hessian estimate whose eigenvalues are
+    /// negligible (at most `1e-6` times the largest one in magnitude) are
+    /// returned.
+    ///
+    /// This takes `2n^2 + 1` new evaluations of `f`: the `n + 1` vertices of
+    /// the final simplex are too few to fit the `(n + 1)(n + 2) / 2`
+    /// coefficients of a quadratic model.
+    pub fn unidentified_directions(&self, f: impl Fn(&[T]) -> T, step: T) -> Vec<Vec<T>> {
+        let n = self.point.len();
+        let h: Vec<T> = self
+            .point
+            .iter()
+            .map(|x| step * x.abs().max(T::one()))
+            .collect();
+        let moved = |offsets: &[(usize, T)]| {
+            let mut x = self.point.clone();
+            for &(i, k) in offsets {
+                x[i] += k * h[i];
+            }
+            f(&x)
+        };
+        let center = f(&self.point);
+        let mut hessian = vec![vec![T::zero(); n]; n];
+        for i in 0..n {
+            hessian[i][i] = (moved(&[(i, T::one())]) - T::from_f64(2.0) * center
+                + moved(&[(i, -T::one())]))
+                / (h[i] * h[i]);
+            for j in 0..i {
+                let hij = (moved(&[(i, T::one()), (j, T::one())])
+                    - moved(&[(i, T::one()), (j, -T::one())])
+                    - moved(&[(i, -T::one()), (j, T::one())])
+                    + moved(&[(i, -T::one()), (j, -T::one())]))
+                    / (T::from_f64(4.0) * h[i] * h[j]);
+                hessian[i][j] = hij;
+                hessian[j][i] = hij;
+            }
+        }
+        let eigen = symmetric_eigen(&hessian);
+        let largest = eigen
+            .iter()
+            .fold(T::zero(), |max: T, (lambda, _)| max.max(lambda.abs()));
+        eigen
+            .into_iter()
+            .filter(|(lambda, _)| lambda.abs() <= T::from_f64(1e-6) * largest)
+            .map(|(_, direction)| direction)
+            .collect()
+    }
+
+    /// Estimates how far `point` can be moved before `f` rises by more
+    /// than `threshold` above `value`.
+    ///
+    /// Probes outwards along `directions` random directions, drawn from
+    /// `rng`, and returns the average distance at which the threshold is
+    /// crossed, or infinity if it is never crossed along some direction.
+    /// Returns `None` if there are no directions to probe, because
+    /// `directions` is 0 or `point` has no dimensions.
+    pub fn basin_radius(
+        &self,
+        f: impl Fn(&[T]) -> T,
+        threshold: T,
+        directions: u32,
+        rng: &mut impl Rng,
+    ) -> Option<T> {
+        if directions == 0 || self.point.is_empty() {
+            return None;
+        }
+        let target = self.value + threshold;
+        let origin = vec![T::zero(); self.point.len()];
+        let above =
+            |direction: &[T], radius: T| f(&sum(&self.point, &mult(radius, direction))) > target;
+        let mut total = T::zero();
+        for _ in 0..directions {
+            let mut direction: Vec<T> = Vec::new();
+            while distance(&direction, &origin) == T::zero() {
+                direction = self
+                    .point
+                    .iter()
+                    .map(|_| rng.gen_range(-T::one(), T::one()))
+                    .collect();
+            }
+            let direction = mult(T::one() / distance(&direction, &origin), &direction);
+
+            // find a radius past the threshold, then bisect down to the crossing
+            let (mut inner, mut outer) = (T::zero(), T::one());
+            while !above(&direction, outer) {
+                if outer > T::max_value() / T::from_f64(2.0) {
+                    return Some(T::infinity());
+                }
+                inner = outer;
+                outer *= T::from_f64(2.0);
+            }
+            for _ in 0..64 {
+                let middle = (inner + outer) / T::from_f64(2.0);
+                if above(&direction, middle) {
+                    outer = middle;
+                } else {
+                    inner = middle;
+                }
+            }
+            total += (inner + outer) / T::from_f64(2.0);
+        }
+        Some(total / T::from_f64(directions as f64))
+    }
+
+    /// Measures, for every axis, how symmetrically `f` rises around `point`.
+    ///
+    /// The ratio for axis `i` is the increase of `f` at `point + delta * e_i`
+    /// over its increase at `point - delta * e_i`. Ratios near 1 mean the
+    /// objective is locally symmetric, as for a quadratic, while ratios far
+    /// from 1 hint at skewed, non-Gaussian uncertainties. This takes `2n`
+    /// evaluations.
+    pub fn axis_asymmetry(&self, f: impl Fn(&[T]) -> T, delta: T) -> Vec<T> {
+        (0..self.point.len())
+            .map(|i| {
+                let mut up = self.point.clone();
+                up[i] += delta;
+                let mut down = self.point.clone();
+                down[i] -= delta;
+                (f(&up) - self.value) / (f(&down) - self.value)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate assert_approx_eq;
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn result_at(initial_point: Vec<f64>, point: Vec<f64>, value: f64) -> OptimizationResult {
+        OptimizationResult {
+            bounds: Bounds::none(point.len()),
+            final_simplex: vec![(point.clone(), value)],
+            evaluation_history: Vec::new(),
+            boundary_mode: BoundaryMode::Clamp,
+            point,
+            value,
+            initial_point,
+            initial_value: value,
+            iterations: 0,
+            evaluations: 0,
+            termination: TerminationReason::Converged,
+            restarts: 0,
+            final_diameter: 0.0,
+            value_spread: 0.0,
+            feasible: true,
+            cache_hits: 0,
+            cache_misses: 0,
+            nan_evaluations: 0,
+            likely_local_minimum: false,
+            step_history: Vec::new(),
+            diameter_ratio: None,
+            half_life_iteration: None,
+        }
+    }
+
+    #[test]
+    fn destructure_every_field() {
+        // keeps the field list of the `compile_fail` example in sync, which
+        // must fail only because of `#[non_exhaustive]`
+        let OptimizationResult {
+            point,
+            value,
+            initial_point,
+            initial_value,
+            iterations,
+            evaluations,
+            termination,
+            restarts,
+            final_diameter,
+            value_spread,
+            feasible,
+            cache_hits,
+            cache_misses,
+            nan_evaluations,
+            likely_local_minimum,
+            step_history,
+            diameter_ratio,
+            half_life_iteration,
+            bounds,
+            final_simplex,
+            evaluation_history,
+            boundary_mode,
+        } = result_at(vec![0.0], vec![1.0], 2.0);
+        assert_eq!((point, value, initial_point), (vec![1.0], 2.0, vec![0.0]));
+        let _ = (
+            initial_value,
+            iterations,
+            evaluations,
+            termination,
+            restarts,
+        );
+        let _ = (
+            final_diameter,
+            value_spread,
+            feasible,
+            cache_hits,
+            cache_misses,
+        );
+        let _ = (
+            nan_evaluations,
+            likely_local_minimum,
+            step_history,
+            diameter_ratio,
+        );
+        let _ = (
+            half_life_iteration,
+            bounds,
+            final_simplex,
+            evaluation_history,
+        );
+        let _ = boundary_mode;
+    }
+
+    #[test]
+    fn access_core_results() {
+        let mut result = result_at(vec![0.0, 0.0], vec![1.0, 2.0], 3.0);
+        result.iterations = 12;
+        result.evaluations = 25;
+        assert_eq!(result.best_point(), &[1.0, 2.0]);
+        assert_eq!(result.best_value(), 3.0);
+        assert_eq!(result.iterations(), 12);
+        assert_eq!(result.evaluations(), 25);
+    }
+
+    #[test]
+    fn only_report_convergence_when_a_tolerance_is_met() {
+        let f = |x: &[f64]| (x[0] - 1.0) * (x[0] - 1.0) + x[1] * x[1];
+        let run = |params: Params, max_iter: u32| {
+            crate::minimize_with_report(f, vec![3.0, 2.0], 1.0, params, Bounds::none(2), max_iter)
+        };
+        let tolerant = Params {
+            f_atol: 1e-8,
+            ..Default::default()
+        };
+        let converged = run(tolerant.clone(), 1000);
+        assert!(converged.converged());
+        assert!(converged.iterations < 1000);
+
+        let exhausted = run(tolerant.clone(), 5);
+        assert_eq!(exhausted.termination, TerminationReason::MaxIterations);
+        assert!(!exhausted.converged());
+
+        let limited = run(
+            Params {
+                max_evaluations: Some(10),
+                ..tolerant
+            },
+            1000,
+        );
+        assert_eq!(limited.termination, TerminationReason::MaxEvaluations);
+        assert!(!limited.converged());
+
+        let untolerant = run(Default::default(), 100);
+        assert_eq!(untolerant.iterations, 100);
+        assert!(!untolerant.converged());
+    }
+
+    #[test]
+    fn measure_axis_asymmetry() {
+        let symmetric = |x: &[f64]| (x[0] - 1.0).powi(2) + 3.0 * (x[1] + 2.0).powi(2);
+        let result = result_at(vec![0.0, 0.0], vec![1.0, -2.0], 0.0);
+        for ratio in result.axis_asymmetry(symmetric, 0.1) {
+            assert_approx_eq!(ratio, 1.0);
+        }
+
+        // rises faster for larger x, and symmetrically along y
+        let skewed = |x: &[f64]| (x[0] - 1.0).powi(2) + (x[0] - 1.0).powi(3) + x[1] * x[1];
+        let result = result_at(vec![0.0, 0.0], vec![1.0, 0.0], 0.0);
+        let ratios = result.axis_asymmetry(skewed, 0.5);
+        assert!(ratios[0] > 2.0);
+        assert_approx_eq!(ratios[1], 1.0);
+    }
+
+    #[test]
+    fn track_moving_optimum() {
+        let target = |frame: u32| vec![1.0 + 0.001 * frame as f64, 2.0 - 0.002 * frame as f64];
+        let objective = |frame: u32| {
+            let c = target(frame);
+            move |x: &[f64]| (x[0] - c[0]) * (x[0] - c[0]) + 2.0 * (x[1] - c[1]) * (x[1] - c[1])
+        };
+        let params = Params {
+            f_atol: 1e-6,
+            ..Default::default()
+        };
+        let run_cold = |frame: u32| {
+            crate::minimize_with_report(
+                objective(frame),
+                vec![0.0, 0.0],
+                1.0,
+                params.clone(),
+                Bounds::none(2),
+                1000,
+            )
+        };
+
+        let mut result = run_cold(0);
+        let mut cold_evaluations = result.evaluations;
+        let mut warm_evaluations = result.evaluations;
+        for frame in 1..20 {
+            result = result.continue_with(objective(frame), params.clone(), 1000);
+            assert_eq!(result.termination, TerminationReason::Converged);
+            assert!(distance(&result.point, &target(frame)) < 1e-2);
+            warm_evaluations += result.evaluations;
+            cold_evaluations += run_cold(frame).evaluations;
+        }
+        assert!(warm_evaluations * 3 < cold_evaluations);
+    }
+
+    #[test]
+    fn report_flat_direction() {
+        let f = |x: &[f64]| x[0] * x[0] + 0.0 * x[1];
+        let result = result_at(vec![1.0, 1.0], vec![0.0, 3.0], 0.0);
+        let directions = result.unidentified_directions(f, 1e-3);
+        assert_eq!(directions.len(), 1);
+        assert!(directions[0][0].abs() < 1e-9);
+        assert!((directions[0][1].abs() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn identify_all_directions_of_bowl() {
+        let f = |x: &[f64]| x[0] * x[0] + (x[0] - x[1]) * (x[0] - x[1]);
+        let result = result_at(vec![1.0, 1.0], vec![0.0, 0.0], 0.0);
+        assert!(result.unidentified_directions(f, 1e-3).is_empty());
+    }
+
+    #[test]
+    fn measure_quadratic_basin_radius() {
+        let result = result_at(vec![0.0, 0.0, 0.0], vec![1.0, -2.0, 0.5], 3.0);
+        let f = |args: &[f64]| {
+            3.0 + (args[0] - 1.0).powi(2) + (args[1] + 2.0).powi(2) + (args[2] - 0.5).powi(2)
+        };
+        // the level set f = 3 + 4 is a sphere of radius 2 around the minimum
+        let mut rng = StdRng::seed_from_u64(0);
+        let radius = result.basin_radius(f, 4.0, 20, &mut rng).unwrap();
+        assert_approx_eq!(radius, 2.0, 1e-9);
+        let radius = result.basin_radius(f, 0.25, 20, &mut rng).unwrap();
+        assert_approx_eq!(radius, 0.5, 1e-9);
+    }
+
+    #[test]
+    fn reject_basin_radius_without_directions() {
+        let f = |args: &[f64]| args.iter().map(|x| x * x).sum::<f64>();
+        let mut rng = StdRng::seed_from_u64(0);
+        let result = result_at(vec![0.0, 0.0], vec![0.0, 0.0], 0.0);
+        assert_eq!(result.basin_radius(f, 1.0, 0, &mut rng), None);
+        let result = result_at(vec![], vec![], 0.0);
+        assert_eq!(result.basin_radius(f, 1.0, 20, &mut rng), None);
+    }
+
+    #[test]
+    fn split_improvement_of_separable_quadratic() {
+        let f = |args: &[f64]| 2.0 * (args[0] - 1.0).powi(2) + 0.5 * (args[1] + 2.0).powi(2);
+        let result = result_at(vec![3.0, 2.0], vec![1.0, -2.0], 0.0);
+        // from f(3, 2) = 8 + 8: moving x to 1 removes 8 and moving y to -2 removes 8
+        let contributions = result.dimension_contributions(f);
+        assert_approx_eq!(contributions[0], 8.0);
+        assert_approx_eq!(contributions[1], 8.0);
+
+        let result = result_at(vec![3.0, 2.0], vec![2.0, 2.0], 2.0);
+        let contributions = result.dimension_contributions(f);
+        assert_approx_eq!(contributions[0], 6.0);
+        assert_approx_eq!(contributions[1], 0.0);
+    }
+}
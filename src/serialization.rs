@@ -0,0 +1,155 @@
+//! Serde support for the configuration and the results, which derive
+//! their implementations when the `serde` feature is enabled.
+//!
+//! Enums are represented as their variant names, with the value of the
+//! variant when there is one. When deserializing `Params`, missing fields
+//! take their default values.
+
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use num_traits::float::FloatCore;
+use serde::{Deserialize, Serialize};
+
+use crate::bounds::*;
+use crate::error::*;
+
+/// The representation of `Bounds`, where infinite bounds are written as
+/// missing values, since formats like JSON have no representation for
+/// infinities.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Bounds", deny_unknown_fields)]
+pub struct BoundsRepr<T> {
+    min: Vec<Option<T>>,
+    max: Vec<Option<T>>,
+}
+
+fn finite_or_none<T: FloatCore>(values: Vec<T>) -> Vec<Option<T>> {
+    values
+        .into_iter()
+        .map(|v| if v.is_infinite() { None } else { Some(v) })
+        .collect()
+}
+
+fn finite_or<T: FloatCore>(values: Vec<Option<T>>, infinity: T) -> Vec<T> {
+    values.into_iter().map(|v| v.unwrap_or(infinity)).collect()
+}
+
+impl<T: FloatCore> From<Bounds<T>> for BoundsRepr<T> {
+    fn from(bounds: Bounds<T>) -> BoundsRepr<T> {
+        BoundsRepr {
+            min: finite_or_none(bounds.min),
+            max: finite_or_none(bounds.max),
+        }
+    }
+}
+
+impl<T: FloatCore> TryFrom<BoundsRepr<T>> for Bounds<T> {
+    type Error = NelderMeadError;
+
+    fn try_from(repr: BoundsRepr<T>) -> Result<Bounds<T>, NelderMeadError> {
+        let bounds = Bounds {
+            min: finite_or(repr.min, T::neg_infinity()),
+            max: finite_or(repr.max, T::infinity()),
+        };
+        // both lengths are checked against the number of minimums
+        bounds.validate(bounds.min.len())?;
+        Ok(bounds)
+    }
+}
+
+/// Serde support for an `f64` that may not be finite, which is written as a
+/// string like `"inf"` or `"NaN"` in that case, since formats like JSON
+/// have no representation for it.
+#[cfg(feature = "std")]
+pub mod non_finite {
+    use super::*;
+    use alloc::string::String;
+    use serde::{de, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+        if value.is_finite() {
+            serializer.serialize_f64(*value)
+        } else {
+            serializer.collect_str(value)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Number(f64),
+            Text(String),
+        }
+
+        match Repr::deserialize(deserializer)? {
+            Repr::Number(value) => Ok(value),
+            Repr::Text(text) => text.parse().map_err(de::Error::custom),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bounds::*;
+    use crate::params::*;
+
+    use std::time::Duration;
+
+    #[test]
+    fn round_trip_params() {
+        let params = Params {
+            alpha: 1.5,
+            stop_on_plateau: Some((10, 1e-9)),
+            allowed_values: Some(vec![None, Some(vec![0.0, 0.5])]),
+            reflection_target: ReflectionTarget::SecondWorstWhenStuck(3),
+            max_duration: Some(Duration::from_millis(1500)),
+            convergence_transform: ConvergenceTransform::Log,
+            boundary_mode: BoundaryMode::Auto(20),
+            resample: Some((5, Aggregate::Median)),
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&params).unwrap();
+        let round_trip: Params = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&round_trip).unwrap(), json);
+        assert_eq!(round_trip.alpha, 1.5);
+        assert_eq!(round_trip.stop_on_plateau, Some((10, 1e-9)));
+        assert_eq!(
+            round_trip.reflection_target,
+            ReflectionTarget::SecondWorstWhenStuck(3)
+        );
+        assert_eq!(round_trip.max_duration, Some(Duration::from_millis(1500)));
+        assert_eq!(round_trip.boundary_mode, BoundaryMode::Auto(20));
+        assert_eq!(round_trip.resample, Some((5, Aggregate::Median)));
+    }
+
+    #[test]
+    fn default_missing_params() {
+        let params: Params = serde_json::from_str("{\"gamma\": 3.0}").unwrap();
+        assert_eq!(params.gamma, 3.0);
+        assert_eq!(params.alpha, <Params as Default>::default().alpha);
+        assert!(serde_json::from_str::<Params>("{\"gama\": 3.0}").is_err());
+    }
+
+    #[test]
+    fn round_trip_bounds() {
+        let bounds = Bounds {
+            min: vec![-1.0, f64::NEG_INFINITY],
+            max: vec![f64::INFINITY, 2.5],
+        };
+        let json = serde_json::to_string(&bounds).unwrap();
+        let round_trip: Bounds = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_trip.as_vec(), bounds.as_vec());
+    }
+
+    #[test]
+    fn reject_invalid_bounds() {
+        assert!(serde_json::from_str::<Bounds>("{\"min\": [0.0, 1.0], \"max\": [1.0]}").is_err());
+        assert!(
+            serde_json::from_str::<Bounds>("{\"min\": [0.0, 3.0], \"max\": [1.0, 2.0]}").is_err()
+        );
+        assert!(
+            serde_json::from_str::<Bounds>("{\"min\": [0.0, 1.0], \"max\": [1.0, 1.0]}").is_ok()
+        );
+    }
+}
@@ -1,18 +1,36 @@
 extern crate rand;
-use rand::rngs::OsRng;
-use rand::Rng;
+use rand::rngs::{OsRng, StdRng};
+use rand::{Rng, SeedableRng};
 
 use crate::algebra::*;
 use crate::bounds::*;
+use crate::callback::*;
+use crate::error::*;
+use crate::evaluation::*;
+use crate::initial_simplex::*;
 use crate::params::*;
+use crate::reduction::*;
+use crate::result::*;
+use crate::transform::*;
+use crate::util::{centroid, centroid_excluding};
 
-type Simplex = Vec<(Vec<f64>, f64)>;
+use std::cell::{Cell, RefCell};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+use std::time::Instant;
 
-fn sort_simplex(simplex: &mut Simplex) {
-    simplex.sort_by(|(_, fx), (_, fy)| fx.partial_cmp(fy).unwrap());
+/// A list of vertices and their function values, sorted from the best to the
+/// worst vertex.
+pub type Simplex<T = f64> = Vec<(Vec<T>, T)>;
+
+/// Sorts the vertices from best to worst, with the ones whose value is
+/// `NaN` treated as worse than any other.
+pub fn sort_simplex<T: Float>(simplex: &mut Simplex<T>) {
+    simplex.sort_by(|(_, fx), (_, fy)| compare_values(*fx, *fy));
 }
 
-fn add_point(f: impl Fn(&Vec<f64>) -> f64, simplex: Simplex, point: Vec<f64>) -> Simplex {
+fn add_point<T: Float>(f: impl Fn(&[T]) -> T, simplex: Simplex<T>, point: Vec<T>) -> Simplex<T> {
     let mut new_simplex = simplex.clone();
     new_simplex.push((point.clone(), f(&point)));
     sort_simplex(&mut new_simplex);
@@ -20,103 +38,1554 @@ fn add_point(f: impl Fn(&Vec<f64>) -> f64, simplex: Simplex, point: Vec<f64>) ->
     new_simplex
 }
 
-fn step(
-    f: impl Fn(&Vec<f64>) -> f64,
-    simplex: Simplex,
-    params: &Params,
-    bounds_vec: &Vec<(f64, f64)>,
-) -> Simplex {
+/// The largest distance between two vertices of the simplex.
+fn diameter<T: Float>(simplex: &Simplex<T>) -> T {
+    simplex
+        .iter()
+        .enumerate()
+        .flat_map(|(i, (xi, _))| simplex[i + 1..].iter().map(move |(xj, _)| distance(xi, xj)))
+        .fold(T::zero(), T::max)
+}
+
+/// Value tolerance that stops the optimization for the current best value.
+fn value_tolerance<T: Float>(simplex: &Simplex<T>, params: &Params<T>, scale: T) -> T {
+    let fx1 = params.convergence_transform.apply(simplex[0].1);
+    scale * (params.f_atol + params.f_rtol * fx1.abs())
+}
+
+/// Spread of the (sorted) simplex values, after the convergence transform.
+fn transformed_spread<T: Float>(simplex: &Simplex<T>, params: &Params<T>) -> T {
+    let transform = params.convergence_transform;
+    transform.apply(simplex[simplex.len() - 1].1) - transform.apply(simplex[0].1)
+}
+
+/// Estimates the progress towards termination, between 0 and 1, after
+/// `iterations` iterations.
+fn progress<T: Float>(
+    params: &Params<T>,
+    iterations: u32,
+    max_iter: u32,
+    evaluations: u32,
+    initial_spread: T,
+    simplex: &Simplex<T>,
+) -> f64 {
+    let mut progress = iterations as f64 / max_iter as f64;
+    if let Some(max_evaluations) = params.max_evaluations {
+        progress = progress.max(evaluations as f64 / max_evaluations as f64);
+    }
+    if params.f_atol != T::zero() || params.f_rtol != T::zero() {
+        let scale = tolerance_scale(params, evaluations);
+        let tolerance = value_tolerance(simplex, params, scale);
+        let spread = transformed_spread(simplex, params);
+        if spread <= tolerance {
+            progress = 1.0;
+        } else if initial_spread > tolerance {
+            let reduction = (initial_spread / spread).ln() / (initial_spread / tolerance).ln();
+            progress = progress.max(reduction.as_f64());
+        }
+    }
+    progress.clamp(0.0, 1.0)
+}
+
+/// Whether the spread of function values in the (sorted) simplex is within
+/// `f_atol + f_rtol * |best value|`, after `convergence_transform`.
+///
+/// Always `false` when both tolerances are 0.
+pub fn values_converged<T: Float>(simplex: &Simplex<T>, params: &Params<T>, scale: T) -> bool {
+    if params.f_atol == T::zero() && params.f_rtol == T::zero() {
+        return false;
+    }
+    transformed_spread(simplex, params) <= value_tolerance(simplex, params, scale)
+}
+
+/// Whether the simplex has converged, with its values within the value
+/// tolerances and its diameter within `x_atol`.
+///
+/// Always `false` when every tolerance is 0.
+pub fn converged<T: Float>(simplex: &Simplex<T>, params: &Params<T>, scale: T) -> bool {
+    let values_enabled = params.f_atol != T::zero() || params.f_rtol != T::zero();
+    if params.x_atol > T::zero() {
+        diameter(simplex) <= params.x_atol
+            && (!values_enabled || values_converged(simplex, params, scale))
+    } else {
+        values_converged(simplex, params, scale)
+    }
+}
+
+/// Factor by which the tolerances are loosened after `evaluations` calls to
+/// the objective.
+///
+/// With `adaptive_tolerance`, this is the inverse of the fraction of the
+/// evaluation budget that is still left, so the tolerances grow without
+/// bound as the budget runs out.
+fn tolerance_scale<T: Float>(params: &Params<T>, evaluations: u32) -> T {
+    match params.max_evaluations {
+        Some(max_evaluations) if params.adaptive_tolerance => {
+            let remaining = max_evaluations.saturating_sub(evaluations);
+            T::from_f64(max_evaluations as f64 / remaining as f64)
+        }
+        _ => T::one(),
+    }
+}
+
+/// Relative decrease per iteration between the oldest and the newest of the
+/// `recent_means` values, or 0 when it is not finite.
+fn improvement_rate<T: Float>(recent_means: &VecDeque<T>) -> T {
+    match (recent_means.front(), recent_means.back()) {
+        (Some(&first), Some(&last)) if recent_means.len() > 1 && first != T::zero() => {
+            let rate = (first - last) / first.abs() / T::from_f64((recent_means.len() - 1) as f64);
+            if rate.is_finite() {
+                rate
+            } else {
+                T::zero()
+            }
+        }
+        _ => T::zero(),
+    }
+}
+
+/// Clamps every vertex of `simplex` into `bounds_vec`, re-evaluating the
+/// vertices that moved.
+fn clamp_simplex<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    simplex: Simplex<T>,
+    bounds_vec: &[(T, T)],
+) -> Simplex<T> {
+    let mut new_simplex: Simplex<T> = simplex
+        .into_iter()
+        .map(|(x, fx)| {
+            let clamped = clamp(&x, bounds_vec);
+            if clamped == x {
+                (x, fx)
+            } else {
+                let fclamped = f(&clamped);
+                (clamped, fclamped)
+            }
+        })
+        .collect();
+    sort_simplex(&mut new_simplex);
+    new_simplex
+}
+
+/// Whether all values evaluated over the `(lowest, highest)` ranges of the
+/// recent iterations differ by less than `flat_tol`.
+fn is_plateau<T: Float>(recent_ranges: &VecDeque<(T, T)>, flat_tol: T) -> bool {
+    let (lowest, highest) = recent_ranges.iter().fold(
+        (T::infinity(), T::neg_infinity()),
+        |(lowest, highest), (iter_lowest, iter_highest)| {
+            (lowest.min(*iter_lowest), highest.max(*iter_highest))
+        },
+    );
+    highest - lowest < flat_tol
+}
+
+/// Performs a single iteration, computing the centroid of every vertex but
+/// the one at index `excluded`.
+pub fn step<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    simplex: Simplex<T>,
+    params: &Params<T>,
+    bounds_vec: &[(T, T)],
+    excluded: usize,
+) -> (Simplex<T>, StepKind) {
+    step_with(
+        &f,
+        |xs| evaluate_all(&f, xs),
+        simplex,
+        params,
+        bounds_vec,
+        excluded,
+    )
+}
+
+/// Performs a single iteration like `step`, evaluating the shrunk vertices
+/// of a shrink step all at once with `f_all`.
+pub fn step_with<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    f_all: impl Fn(&[Vec<T>]) -> Vec<T>,
+    simplex: Simplex<T>,
+    params: &Params<T>,
+    bounds_vec: &[(T, T)],
+    excluded: usize,
+) -> (Simplex<T>, StepKind) {
+    let n = simplex.len() - 1;
+    reflect_vertex(f, f_all, simplex, params, bounds_vec, excluded, n)
+}
+
+/// Evaluates `f` at every point of `xs`, in order.
+fn evaluate_all<T: Float>(f: impl Fn(&[T]) -> T, xs: &[Vec<T>]) -> Vec<T> {
+    xs.iter().map(|x| f(x)).collect()
+}
+
+/// Brings a trial point `x`, stepped from the `centroid`, within the bounds
+/// as configured by `mode`.
+fn bring_into_bounds<T: Float>(
+    mode: BoundaryMode,
+    centroid: &[T],
+    x: &[T],
+    bounds_vec: &[(T, T)],
+) -> Vec<T> {
+    match mode {
+        BoundaryMode::Clamp | BoundaryMode::Auto(_) | BoundaryMode::Transform => {
+            clamp(x, bounds_vec)
+        }
+        BoundaryMode::Reflect => reflect_into(x, bounds_vec),
+        BoundaryMode::Truncate => truncate_into(centroid, x, bounds_vec),
+    }
+}
+
+/// Runs `probe_iterations` iterations with every concrete boundary mode,
+/// then continues with the one that reached the lowest value from where its
+/// probe stopped.
+///
+/// Ties are broken in favor of clamping, then reflecting. The probes share
+/// the `Params::max_evaluations` budget and the `Params::max_duration`
+/// deadline of the whole run, and count towards its iterations.
+#[allow(clippy::too_many_arguments)]
+fn minimize_auto<T: Float>(
+    f: &dyn Fn(&[T]) -> T,
+    f_all: &dyn Fn(&[Vec<T>]) -> Vec<T>,
+    initial_simplex: Simplex<T>,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+    probe_iterations: u32,
+    callback: impl FnMut(&IterationInfo<T>) -> Action<T>,
+) -> OptimizationResult<T> {
+    fn no_callback<T>(_: &IterationInfo<T>) -> Action<T> {
+        Action::Continue
+    }
+    let start = Instant::now();
+    // every run counts the vertices it starts from, which were already
+    // evaluated
+    let initial_evaluations = initial_simplex.len() as u32 * samples_per_point(&params);
+    let remaining = |params: &Params<T>, spent: u32| Params {
+        max_duration: params
+            .max_duration
+            .map(|max_duration| max_duration.saturating_sub(start.elapsed())),
+        ..charge(params.clone(), spent)
+    };
+    let mut spent = 0;
+    let mut histories = Vec::new();
+    let mut best: Option<OptimizationResult<T>> = None;
+    for mode in [
+        BoundaryMode::Clamp,
+        BoundaryMode::Reflect,
+        BoundaryMode::Truncate,
+    ] {
+        // the result of a probe is only compared, so it isn't refined
+        let probe_params = Params {
+            boundary_mode: mode,
+            integer_refinement: None,
+            snap_result_to_grid: None,
+            local_minimum_probes: None,
+            ..remaining(&params, spent)
+        };
+        let probe = minimize_unscaled(
+            f,
+            f_all,
+            initial_simplex.clone(),
+            probe_params,
+            bounds.clone(),
+            probe_iterations.min(max_iter),
+            no_callback as fn(&IterationInfo<T>) -> Action<T>,
+        );
+        spent += probe.evaluations - initial_evaluations;
+        let improves = match &best {
+            Some(best) => probe.value < best.value,
+            None => true,
+        };
+        histories.push(probe.evaluation_history.clone());
+        if improves {
+            best = Some(probe);
+        }
+    }
+    let best = best.unwrap();
+    let vertices = best.final_simplex.len();
+    // the rest of the run counts the vertices it resumes from, which can be
+    // fewer than the initial ones once the fixed dimensions are reduced
+    let resumed = vertices as u32 * samples_per_point(&params);
+    let spent = spent + initial_evaluations - resumed;
+    let main_params = Params {
+        boundary_mode: best.boundary_mode,
+        ..remaining(&params, spent)
+    };
+    let mut result = minimize_unscaled(
+        f,
+        f_all,
+        best.final_simplex,
+        main_params,
+        bounds,
+        max_iter.saturating_sub(best.iterations),
+        callback,
+    );
+    result.initial_point = initial_simplex[0].0.clone();
+    result.initial_value = initial_simplex[0].1;
+    result.iterations += best.iterations;
+    result.evaluations += spent;
+    let mut step_history = best.step_history;
+    step_history.append(&mut result.step_history);
+    result.step_history = step_history;
+    // the probes are recorded in order, before the rest of the run, without
+    // repeating the vertices every run starts from
+    let mut evaluation_history = Vec::new();
+    for (i, history) in histories.into_iter().enumerate() {
+        let skip = if i == 0 { 0 } else { initial_simplex.len() };
+        evaluation_history.extend(history.into_iter().skip(skip));
+    }
+    evaluation_history.extend(result.evaluation_history.drain(..).skip(vertices));
+    result.evaluation_history = evaluation_history;
+    result
+}
+
+/// Performs a single iteration that reflects the vertex at index `target`,
+/// computing the centroid of every vertex but the one at index `excluded`.
+///
+/// The new vertex replaces the target, which is the worst vertex in the
+/// standard algorithm.
+fn reflect_vertex<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    f_all: impl Fn(&[Vec<T>]) -> Vec<T>,
+    simplex: Simplex<T>,
+    params: &Params<T>,
+    bounds_vec: &[(T, T)],
+    excluded: usize,
+    target: usize,
+) -> (Simplex<T>, StepKind) {
     let n = simplex.len() - 1;
-    let x1 = simplex[0].0.clone();
     let fx1 = simplex[0].1;
-    let x0 = {
-        let median_list = &simplex.as_slice()[0..n];
-        avg(&median_list
-            .iter()
-            .map(|x| x.0.clone())
-            .collect::<Vec<Vec<f64>>>())
+    let x0 = centroid_excluding(&simplex, excluded);
+    // the worst of the vertices that are kept
+    let fxn = if target == n {
+        simplex[n - 1].1
+    } else {
+        simplex[n].1
     };
-    let (_xn, fxn) = simplex[n - 1].clone();
-    let (xn1, fxn1) = simplex[n].clone();
+    let (xt, fxt) = simplex[target].clone();
 
-    let xr = clamp(
-        &sum(&x0, &mult(params.alpha, &diff(&x0, &xn1))),
-        &bounds_vec,
-    );
+    let bound = |x: Vec<T>| bring_into_bounds(params.boundary_mode, &x0, &x, bounds_vec);
+    let xr = bound(sum(&x0, &mult(params.alpha, &diff(&x0, &xt))));
     let fxr = f(&xr);
-    let xe = clamp(&sum(&x0, &mult(params.gamma, &diff(&xr, &x0))), &bounds_vec);
-    let fxe = f(&xe);
-    let xc = clamp(&sum(&x0, &mult(params.rho, &diff(&xn1, &x0))), &bounds_vec);
-    let fxc = f(&xc);
-
-    if fx1 <= fxr && fxr < fxn {
-        // Reflection
-        add_point(f, simplex, xr)
-    } else if fxe < fxn1 {
-        // Expansion
+
+    let replace = |simplex: Simplex<T>, vertex: (Vec<T>, T)| {
+        let mut new_simplex = simplex;
+        if target == n {
+            new_simplex.push(vertex);
+            sort_simplex(&mut new_simplex);
+            new_simplex.truncate(n + 1);
+        } else {
+            new_simplex.remove(target);
+            new_simplex.push(vertex);
+            sort_simplex(&mut new_simplex);
+        }
+        new_simplex
+    };
+    // the expansion and contraction points are only evaluated when needed
+    let mut expansion = None;
+    let mut contraction = None;
+    let kind = if fxr < fx1 {
+        let xe = bound(sum(&x0, &mult(params.gamma, &diff(&xr, &x0))));
+        let fxe = f(&xe);
+        expansion = Some((xe, fxe));
         if fxe < fxr {
-            add_point(f, simplex, xe)
+            StepKind::Expansion
         } else {
-            add_point(f, simplex, xr)
+            StepKind::Reflection
         }
-    } else if fxc < fxn1 {
-        // Contraction
-        add_point(f, simplex, xc)
+    } else if fxr < fxn {
+        StepKind::Reflection
     } else {
-        // Shrink
-        let mut new_points: Vec<(Vec<f64>, f64)> = simplex
-            .iter()
-            .skip(1)
-            .map(|(xi, _)| sum(&x1, &mult(params.delta, &diff(&xi, &x1))))
-            .map(|xi| (xi.clone(), f(&xi)))
-            .collect();
-        new_points.push((x1, fx1));
-        sort_simplex(&mut new_points);
-        new_points
+        let xc = bound(sum(&x0, &mult(params.rho, &diff(&xt, &x0))));
+        let fxc = f(&xc);
+        contraction = Some((xc, fxc));
+        if fxc < fxt {
+            StepKind::Contraction
+        } else {
+            StepKind::Shrink
+        }
+    };
+    log_step(&x0, (&xr, fxr), &expansion, &contraction, kind);
+
+    let new_simplex = match (kind, expansion, contraction) {
+        (StepKind::Expansion, Some(vertex), _) => replace(simplex, vertex),
+        (StepKind::Contraction, _, Some(vertex)) => replace(simplex, vertex),
+        (StepKind::Shrink, _, _) => shrink_with(f_all, simplex, params),
+        _ => replace(simplex, (xr, fxr)),
+    };
+    (new_simplex, kind)
+}
+
+/// Logs the centroid, the trial points and the outcome of a step at the
+/// debug level, with the `log` feature.
+#[cfg(feature = "log")]
+fn log_step<T: Float>(
+    centroid: &[T],
+    (xr, fxr): (&[T], T),
+    expansion: &Option<(Vec<T>, T)>,
+    contraction: &Option<(Vec<T>, T)>,
+    kind: StepKind,
+) {
+    log::debug!(
+        "centroid={:?} reflection={:?} f(reflection)={} expansion={:?} f(expansion)={:?} \
+         contraction={:?} f(contraction)={:?} step={:?}",
+        centroid,
+        xr,
+        fxr,
+        expansion.as_ref().map(|(x, _)| x),
+        expansion.as_ref().map(|(_, fx)| fx),
+        contraction.as_ref().map(|(x, _)| x),
+        contraction.as_ref().map(|(_, fx)| fx),
+        kind
+    );
+}
+
+#[cfg(not(feature = "log"))]
+fn log_step<T: Float>(
+    _centroid: &[T],
+    _reflection: (&[T], T),
+    _expansion: &Option<(Vec<T>, T)>,
+    _contraction: &Option<(Vec<T>, T)>,
+    _kind: StepKind,
+) {
+}
+
+/// Contracts every vertex but the `Params::shrink_keep` best ones towards
+/// the best vertex, evaluating the shrunk vertices all at once with
+/// `f_all`.
+///
+/// With `Params::greedy_shrink`, each vertex is only replaced by its
+/// contracted point if the latter is better.
+fn shrink_with<T: Float>(
+    f_all: impl Fn(&[Vec<T>]) -> Vec<T>,
+    simplex: Simplex<T>,
+    params: &Params<T>,
+) -> Simplex<T> {
+    let x1 = simplex[0].0.clone();
+    let keep = params.shrink_keep.max(1);
+    let shrunk: Vec<Vec<T>> = simplex
+        .iter()
+        .skip(keep)
+        .map(|(xi, _)| sum(&x1, &mult(params.delta, &diff(xi, &x1))))
+        .collect();
+    let values = f_all(&shrunk);
+    let mut new_points: Vec<(Vec<T>, T)> = simplex
+        .iter()
+        .skip(keep)
+        .zip(shrunk.into_iter().zip(values))
+        .map(|((xi, fxi), (xs, fxs))| {
+            if params.greedy_shrink && *fxi <= fxs {
+                (xi.clone(), *fxi)
+            } else {
+                (xs, fxs)
+            }
+        })
+        .collect();
+    new_points.extend(simplex.into_iter().take(keep));
+    sort_simplex(&mut new_points);
+    new_points
+}
+
+/// Hash of the vertices of `simplex` at single precision, independent of
+/// their order.
+fn simplex_signature<T: Float>(simplex: &Simplex<T>) -> u64 {
+    let mut vertices: Vec<Vec<u32>> = simplex
+        .iter()
+        .map(|(x, _)| x.iter().map(|c| (c.as_f64() as f32).to_bits()).collect())
+        .collect();
+    vertices.sort();
+    let mut hasher = DefaultHasher::new();
+    vertices.hash(&mut hasher);
+    hasher.finish()
+}
+
+pub fn minimize<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    initial_simplex: Simplex<T>,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+) -> OptimizationResult<T> {
+    minimize_with_callback(f, initial_simplex, params, bounds, max_iter, |_| {
+        Action::Continue
+    })
+}
+
+/// Minimizes `f`, scaling the objective as set by `Params::scale_objective`
+/// and `Params::affine_objective`.
+pub fn minimize_with_callback<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    initial_simplex: Simplex<T>,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+    callback: impl FnMut(&IterationInfo<T>) -> Action<T>,
+) -> OptimizationResult<T> {
+    minimize_batched(
+        &f,
+        |xs| evaluate_all(&f, xs),
+        initial_simplex,
+        params,
+        bounds,
+        max_iter,
+        callback,
+    )
+}
+
+/// Minimizes `f` like `minimize_with_callback`, evaluating the points that
+/// don't depend on each other, like the shrunk vertices, all at once with
+/// `f_all`.
+pub fn minimize_batched<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    f_all: impl Fn(&[Vec<T>]) -> Vec<T>,
+    initial_simplex: Simplex<T>,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+    callback: impl FnMut(&IterationInfo<T>) -> Action<T>,
+) -> OptimizationResult<T> {
+    let transform = if params.affine_objective {
+        affine_estimate(&initial_simplex)
+    } else if params.scale_objective && initial_simplex[0].1.is_normal() {
+        Some((T::zero(), initial_simplex[0].1.abs()))
+    } else {
+        None
+    };
+    if let Some((offset, scale)) = transform {
+        return minimize_scaled(
+            &f,
+            &f_all,
+            initial_simplex,
+            offset,
+            scale,
+            params,
+            bounds,
+            max_iter,
+            callback,
+        );
+    }
+    minimize_unscaled(
+        f,
+        f_all,
+        initial_simplex,
+        params,
+        bounds,
+        max_iter,
+        callback,
+    )
+}
+
+/// Minimizes `f`, searching only over the dimensions that are not fixed by
+/// equal minimum and maximum bounds.
+fn minimize_unscaled<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    f_all: impl Fn(&[Vec<T>]) -> Vec<T>,
+    initial_simplex: Simplex<T>,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+    mut callback: impl FnMut(&IterationInfo<T>) -> Action<T>,
+) -> OptimizationResult<T> {
+    if let BoundaryMode::Auto(probe_iterations) = params.boundary_mode {
+        return minimize_auto(
+            &f,
+            &f_all,
+            initial_simplex,
+            params,
+            bounds,
+            max_iter,
+            probe_iterations,
+            callback,
+        );
+    }
+    if params.boundary_mode == BoundaryMode::Transform {
+        return minimize_transformed(
+            &f,
+            &f_all,
+            initial_simplex,
+            params,
+            bounds,
+            max_iter,
+            &mut callback,
+        );
+    }
+    let reduction = match Reduction::from_bounds(&bounds) {
+        Some(reduction) => reduction,
+        None => {
+            return minimize_all(
+                f,
+                f_all,
+                initial_simplex,
+                params,
+                bounds,
+                max_iter,
+                callback,
+            )
+        }
+    };
+
+    let reduced_params = Params {
+        integer_refinement: params
+            .integer_refinement
+            .as_ref()
+            .map(|mask| reduction.reduce(mask)),
+        snap_result_to_grid: params
+            .snap_result_to_grid
+            .as_ref()
+            .map(|spacing| reduction.reduce(spacing)),
+        allowed_values: params
+            .allowed_values
+            .as_ref()
+            .map(|allowed_values| reduction.reduce(allowed_values)),
+        ..params.clone()
+    };
+    let (reduced_simplex, reevaluated) =
+        reduce_simplex(&f, &initial_simplex, &reduction, &bounds, &reduced_params);
+    let reevaluations = reevaluated.len() as u32 * samples_per_point(&params);
+    // the reduced run only counts the vertices it starts from, while the
+    // dropped ones were evaluated too
+    let dropped = initial_simplex.len() - reduction.free_dimensions() - 1;
+    let outside_evaluations = dropped as u32 * samples_per_point(&params) + reevaluations;
+    let reduced_params = charge(reduced_params, outside_evaluations);
+
+    let mut result = minimize_all(
+        |x: &[T]| f(&reduction.expand(x)),
+        |xs: &[Vec<T>]| f_all(&xs.iter().map(|x| reduction.expand(x)).collect::<Vec<_>>()),
+        reduced_simplex,
+        reduced_params,
+        reduction.reduce_bounds(&bounds),
+        max_iter,
+        |info| {
+            let simplex: Simplex<T> = info
+                .simplex
+                .iter()
+                .map(|(x, fx)| (reduction.expand(x), *fx))
+                .collect();
+            let full_info = IterationInfo {
+                iteration: info.iteration,
+                simplex: &simplex,
+                progress: info.progress,
+            };
+            match callback(&full_info) {
+                Action::Continue => Action::Continue,
+                Action::SetBounds(new_bounds) => {
+                    Action::SetBounds(reduction.reduce_bounds(&new_bounds))
+                }
+                Action::TryPoint(candidate) => Action::TryPoint(reduction.reduce(&candidate)),
+                Action::Abort => Action::Abort,
+            }
+        },
+    );
+    result.point = reduction.expand(&result.point);
+    result.initial_point = initial_simplex[0].0.clone();
+    result.initial_value = initial_simplex[0].1;
+    result.bounds = reduction.expand_bounds(&result.bounds);
+    result.final_simplex = result
+        .final_simplex
+        .iter()
+        .map(|(x, fx)| (reduction.expand(x), *fx))
+        .collect();
+    // the reduced run only records the vertices it starts from, while every
+    // initial vertex was evaluated, and some vertices again
+    if params.record_evaluations {
+        let mut evaluation_history = initial_simplex.clone();
+        evaluation_history.extend(
+            reevaluated
+                .iter()
+                .chain(
+                    result
+                        .evaluation_history
+                        .iter()
+                        .skip(reduction.free_dimensions() + 1),
+                )
+                .map(|(x, fx)| (reduction.expand(x), *fx)),
+        );
+        result.evaluation_history = evaluation_history;
+    }
+    result.evaluations += outside_evaluations;
+    result
+}
+
+/// Reduces `initial_simplex` to a simplex over the free dimensions of
+/// `reduction`, returning it along with the vertices it evaluated.
+///
+/// Pinning the fixed coordinates collapses the vertices that only step
+/// along fixed dimensions, so only the vertices that are affinely
+/// independent once reduced are kept, from best to worst. The missing ones
+/// step from the best vertex along the free axes, by the largest offset of
+/// a vertex from it, going backwards when going forwards would leave the
+/// bounds. Vertices whose coordinates moved are evaluated again.
+fn reduce_simplex<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    initial_simplex: &Simplex<T>,
+    reduction: &Reduction<T>,
+    bounds: &Bounds<T>,
+    reduced_params: &Params<T>,
+) -> (Simplex<T>, Simplex<T>) {
+    let f = |x: &[T]| f(&reduction.expand(x));
+    let mut sorted = initial_simplex.clone();
+    sort_simplex(&mut sorted);
+    let n = reduction.free_dimensions();
+    let center = reduction.reduce(&sorted[0].0);
+    let mut basis: Vec<Vec<T>> = Vec::new();
+    // the component of `v` orthogonal to the directions kept so far
+    let residual = |basis: &[Vec<T>], v: Vec<T>| {
+        basis.iter().fold(v, |v, u| {
+            let dot: T = v.iter().zip(u.iter()).map(|(&vi, &ui)| vi * ui).sum();
+            diff(&v, &mult(dot, u))
+        })
+    };
+    let zero = vec![T::zero(); n];
+    let mut kept = Vec::new();
+    let mut moved = Vec::new();
+    for (i, (x, fx)) in sorted.iter().enumerate() {
+        if kept.len() + moved.len() == n + 1 {
+            break;
+        }
+        let reduced = reduction.reduce(x);
+        if i > 0 {
+            let offset = diff(&reduced, &center);
+            let v = residual(&basis, offset.clone());
+            let norm = distance(&v, &zero);
+            // nearly dependent vertices would flatten the simplex
+            if norm <= T::from_f64(1e-6) * distance(&offset, &zero) || norm == T::zero() {
+                continue;
+            }
+            basis.push(mult(T::one() / norm, &v));
+        }
+        if reduction.expand(&reduced) == *x {
+            kept.push((reduced, *fx));
+        } else {
+            moved.push(reduced);
+        }
+    }
+    let size = sorted
+        .iter()
+        .flat_map(|(x, _)| {
+            x.iter()
+                .zip(sorted[0].0.iter())
+                .map(|(&xi, &x0i)| (xi - x0i).abs())
+        })
+        .fold(T::zero(), T::max);
+    let step = if size > T::zero() { size } else { T::one() };
+    let reduced_bounds = reduction.reduce_bounds(bounds).as_vec();
+    for i in 0..n {
+        if kept.len() + moved.len() == n + 1 {
+            break;
+        }
+        let mut axis = zero.clone();
+        axis[i] = T::one();
+        let v = residual(&basis, axis);
+        let norm = distance(&v, &zero);
+        if norm <= T::from_f64(1e-6) {
+            continue;
+        }
+        basis.push(mult(T::one() / norm, &v));
+        let mut point = center.clone();
+        point[i] = step_within_bounds(center[i], step, reduced_bounds[i]);
+        moved.push(point);
+    }
+    let evaluated = evaluate_initial_simplex(f, &InitialSimplex { points: moved }, reduced_params);
+    let mut simplex = evaluated.clone();
+    simplex.extend(kept);
+    sort_simplex(&mut simplex);
+    (simplex, evaluated)
+}
+
+/// The coordinate `step` away from `center` along an axis bounded by
+/// `(min, max)`: forwards if it stays within the bounds, else backwards,
+/// else the farthest bound.
+pub(crate) fn step_within_bounds<T: Float>(center: T, step: T, (min, max): (T, T)) -> T {
+    if center + step <= max {
+        center + step
+    } else if center - step >= min {
+        center - step
+    } else if max - center >= center - min {
+        max
+    } else {
+        min
+    }
+}
+
+/// Takes the `spent` evaluations made outside of a run out of its
+/// `Params::max_evaluations` and `Params::max_distinct_evals` budgets.
+fn charge<T: Float>(params: Params<T>, spent: u32) -> Params<T> {
+    let points = (spent / samples_per_point(&params)) as u64;
+    Params {
+        max_evaluations: params
+            .max_evaluations
+            .map(|max_evaluations| max_evaluations.saturating_sub(spent)),
+        max_distinct_evals: params
+            .max_distinct_evals
+            .map(|max_distinct_evals| max_distinct_evals.saturating_sub(points)),
+        ..params
     }
 }
 
-pub fn minimize(
-    f: impl Fn(&Vec<f64>) -> f64,
-    initial_simplex: Simplex,
-    params: Params,
-    bounds: Bounds,
+/// Minimizes `f` without bounds over the coordinates of a `Transform` of
+/// `bounds`, reporting every point to the callback and in the result in the
+/// original coordinates.
+///
+/// The integer, grid and allowed value constraints of `params` are applied
+/// to the original coordinates.
+fn minimize_transformed<T: Float>(
+    f: &dyn Fn(&[T]) -> T,
+    f_all: &dyn Fn(&[Vec<T>]) -> Vec<T>,
+    initial_simplex: Simplex<T>,
+    params: Params<T>,
+    bounds: Bounds<T>,
     max_iter: u32,
-) -> (Vec<f64>, f64) {
+    callback: &mut dyn FnMut(&IterationInfo<T>) -> Action<T>,
+) -> OptimizationResult<T> {
+    let transform = Transform::new(&bounds);
     let bounds_vec = bounds.as_vec();
+    let extra_evaluations = Cell::new(0);
+    let recorded = RefCell::new(Vec::new());
+    let f = |x: &[T]| {
+        extra_evaluations.set(extra_evaluations.get() + 1);
+        let fx = match &params.allowed_values {
+            Some(allowed_values) => f(&snap(x, allowed_values)),
+            None => f(x),
+        };
+        if params.record_evaluations {
+            recorded.borrow_mut().push((x.to_vec(), fx));
+        }
+        fx
+    };
+    let snapped_all = |xs: &[Vec<T>]| match &params.allowed_values {
+        Some(allowed_values) => f_all(
+            &xs.iter()
+                .map(|x| snap(x, allowed_values))
+                .collect::<Vec<_>>(),
+        ),
+        None => f_all(xs),
+    };
+    let given = if params.record_evaluations {
+        initial_simplex.clone()
+    } else {
+        Vec::new()
+    };
+    // vertices outside the bounds move onto them, and must be evaluated again
+    let initial_simplex = clamp_simplex(f, initial_simplex, &bounds_vec);
+    let transformed_simplex: Simplex<T> = initial_simplex
+        .iter()
+        .map(|(x, fx)| (transform.to_unbounded(x), *fx))
+        .collect();
+    let reevaluations = extra_evaluations.get();
+    let transformed_params = Params {
+        boundary_mode: BoundaryMode::Clamp,
+        integer_refinement: None,
+        snap_result_to_grid: None,
+        allowed_values: None,
+        ..charge(params.clone(), reevaluations)
+    };
+    let mut result = minimize_unscaled(
+        |u: &[T]| f(&transform.to_bounded(u)),
+        |us: &[Vec<T>]| {
+            snapped_all(
+                &us.iter()
+                    .map(|u| transform.to_bounded(u))
+                    .collect::<Vec<_>>(),
+            )
+        },
+        transformed_simplex,
+        transformed_params,
+        transform.unbounded(),
+        max_iter,
+        |info| {
+            let simplex: Simplex<T> = info
+                .simplex
+                .iter()
+                .map(|(u, fu)| (transform.to_bounded(u), *fu))
+                .collect();
+            let original_info = IterationInfo {
+                iteration: info.iteration,
+                simplex: &simplex,
+                progress: info.progress,
+            };
+            match callback(&original_info) {
+                Action::SetBounds(_) => Action::Continue,
+                Action::TryPoint(candidate) => Action::TryPoint(transform.to_unbounded(&candidate)),
+                action => action,
+            }
+        },
+    );
+    let to_bounded = |simplex: &Simplex<T>| -> Simplex<T> {
+        simplex
+            .iter()
+            .map(|(u, fu)| (transform.to_bounded(u), *fu))
+            .collect()
+    };
+    let point = transform.to_bounded(&result.point);
+    extra_evaluations.set(0);
+    let refined_from = recorded.borrow().len();
+    // like in `minimize_all`, the refinements must fit in the budget
+    let affords = |points: u32| {
+        let spent = reevaluations + extra_evaluations.get() + points;
+        let within_calls = match params.max_evaluations {
+            Some(max_evaluations) => result.evaluations + spent <= max_evaluations,
+            None => true,
+        };
+        // the points outside of the run are evaluated once
+        let within_points = match params.max_distinct_evals {
+            Some(max_distinct_evals) => {
+                (result.evaluations / samples_per_point(&params) + spent) as u64
+                    <= max_distinct_evals
+            }
+            None => true,
+        };
+        within_calls && within_points
+    };
+    let (point, value) = match &params.integer_refinement {
+        Some(mask)
+            if affords(refine_on_grid_evaluations(&integer_spacing::<T>(
+                mask,
+                point.len(),
+            ))) =>
+        {
+            refine_integers(f, &point, mask, &bounds_vec)
+        }
+        _ => (point, result.value),
+    };
+    let (point, value) = match &params.snap_result_to_grid {
+        Some(spacing) if affords(refine_on_grid_evaluations(spacing)) => {
+            refine_on_grid(f, &point, spacing, &bounds_vec)
+        }
+        _ => (point, value),
+    };
+    result.point = match &params.allowed_values {
+        Some(allowed_values) => snap(&point, allowed_values),
+        None => point,
+    };
+    result.value = value;
+    result.feasible = value.is_finite()
+        && result
+            .point
+            .iter()
+            .zip(bounds_vec.iter())
+            .all(|(x, (min, max))| min <= x && x <= max);
+    result.initial_point = initial_simplex[0].0.clone();
+    result.final_simplex = to_bounded(&result.final_simplex);
+    result.final_diameter = diameter(&result.final_simplex);
+    // the run only records the clamped vertices it starts from, and the
+    // vertices moved by the clamping and the refinements were evaluated
+    // outside of it
+    if params.record_evaluations {
+        let recorded = recorded.into_inner();
+        let mut evaluation_history = given;
+        evaluation_history.extend_from_slice(&recorded[..reevaluations as usize]);
+        evaluation_history.extend(
+            to_bounded(&result.evaluation_history)
+                .into_iter()
+                .skip(initial_simplex.len()),
+        );
+        evaluation_history.extend_from_slice(&recorded[refined_from..]);
+        result.evaluation_history = evaluation_history;
+    }
+    result.bounds = bounds;
+    result.boundary_mode = BoundaryMode::Transform;
+    result.evaluations += reevaluations + extra_evaluations.get();
+    result
+}
+
+/// Estimates an offset and a scale that bring the values of `simplex`
+/// around 0, with a spread of 1: their mean and their range.
+///
+/// Returns `None` when the values don't have a finite, non-zero range.
+pub fn affine_estimate<T: Float>(simplex: &Simplex<T>) -> Option<(T, T)> {
+    let values = simplex.iter().map(|(_, fx)| *fx);
+    let mean = values.clone().sum::<T>() / T::from_f64(simplex.len() as f64);
+    let lowest = values.clone().fold(T::infinity(), T::min);
+    let highest = values.fold(T::neg_infinity(), T::max);
+    let scale = highest - lowest;
+    if mean.is_finite() && scale.is_normal() {
+        Some((mean, scale))
+    } else {
+        None
+    }
+}
+
+/// Minimizes `(f - offset) / scale`, reporting the values to the callback
+/// and in the result in the original scale of `f`.
+#[allow(clippy::too_many_arguments)]
+pub fn minimize_scaled<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    f_all: impl Fn(&[Vec<T>]) -> Vec<T>,
+    initial_simplex: Simplex<T>,
+    offset: T,
+    scale: T,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+    mut callback: impl FnMut(&IterationInfo<T>) -> Action<T>,
+) -> OptimizationResult<T> {
+    let scaled_simplex = initial_simplex
+        .into_iter()
+        .map(|(x, fx)| (x, (fx - offset) / scale))
+        .collect();
+    let unscale = |fx: T| fx * scale + offset;
+    let mut result = minimize_unscaled(
+        |x: &[T]| (f(x) - offset) / scale,
+        |xs: &[Vec<T>]| {
+            f_all(xs)
+                .into_iter()
+                .map(|fx| (fx - offset) / scale)
+                .collect()
+        },
+        scaled_simplex,
+        params,
+        bounds,
+        max_iter,
+        |info| {
+            let simplex: Simplex<T> = info
+                .simplex
+                .iter()
+                .map(|(x, fx)| (x.clone(), unscale(*fx)))
+                .collect();
+            callback(&IterationInfo {
+                iteration: info.iteration,
+                simplex: &simplex,
+                progress: info.progress,
+            })
+        },
+    );
+    result.value = unscale(result.value);
+    result.initial_value = unscale(result.initial_value);
+    result.value_spread *= scale;
+    for (_, fx) in result
+        .final_simplex
+        .iter_mut()
+        .chain(result.evaluation_history.iter_mut())
+    {
+        *fx = unscale(*fx);
+    }
+    result
+}
+
+/// Minimizes `f` over every dimension of the simplex.
+fn minimize_all<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    f_all: impl Fn(&[Vec<T>]) -> Vec<T>,
+    initial_simplex: Simplex<T>,
+    params: Params<T>,
+    bounds: Bounds<T>,
+    max_iter: u32,
+    mut callback: impl FnMut(&IterationInfo<T>) -> Action<T>,
+) -> OptimizationResult<T> {
+    let evaluator = Evaluator::new(f, &params, &initial_simplex);
+    let f = |x: &[T]| evaluator.eval(x);
+    let f_all = |xs: &[Vec<T>]| evaluator.eval_all(xs, &f_all);
+    let mut bounds_vec = bounds.as_vec();
     let mut curr_simplex = initial_simplex.clone();
     let n = curr_simplex.len() - 1;
-    for _ in 0..max_iter {
-        curr_simplex = step(&f, curr_simplex, &params, &bounds_vec);
+    let mut step_history = Vec::new();
+    let mut recent_ranges = VecDeque::new();
+    let mut recent_signatures = VecDeque::new();
+    let mut stuck = 0;
+    let start = Instant::now();
+    let initial_evaluations = evaluator.evaluations.get();
+    let initial_diameter = diameter(&curr_simplex);
+    let mut half_life_iteration = None;
+    let mut iterations = 0;
+    let mut termination = TerminationReason::MaxIterations;
+    let initial_spread = transformed_spread(&curr_simplex, &params);
+    let mut curr_progress = 0.0;
+    let mut recent_means = VecDeque::new();
+    let restart_size = initial_simplex
+        .iter()
+        .flat_map(|(x, _)| {
+            x.iter()
+                .zip(initial_simplex[0].0.iter())
+                .map(|(&xi, &x0i)| (xi - x0i).abs())
+        })
+        .fold(T::zero(), T::max);
+    let mut restarts = 0;
+    let mut stagnant = 0;
+    let mut best_value = curr_simplex[0].1;
+    let hard_cap = match params.extend_if_improving {
+        Some((_, max_extension)) => max_iter.saturating_add(max_extension),
+        None => max_iter,
+    };
+    for iteration in 0..hard_cap {
+        if let Some((min_rate, _)) = params.extend_if_improving {
+            if iteration >= max_iter && improvement_rate(&recent_means) < min_rate {
+                break;
+            }
+            let mean =
+                curr_simplex.iter().map(|(_, fx)| *fx).sum::<T>() / T::from_f64((n + 1) as f64);
+            recent_means.push_back(mean);
+            if recent_means.len() > n + 2 {
+                recent_means.pop_front();
+            }
+        }
+        if !evaluator.affords(1) {
+            termination = TerminationReason::MaxEvaluations;
+            break;
+        }
+        if let Some(max_duration) = params.max_duration {
+            let loop_evaluations = evaluator.evaluations.get() - initial_evaluations;
+            if loop_evaluations > 0 {
+                let elapsed = start.elapsed();
+                let cost = elapsed / loop_evaluations;
+                // the worst case is a shrink, followed by the final evaluation
+                if elapsed + cost * (n as u32 + 4) > max_duration {
+                    termination = TerminationReason::Deadline;
+                    break;
+                }
+            }
+        }
+        evaluator.reset_range();
+        let (new_simplex, step_kind) = match params.reflection_target {
+            ReflectionTarget::SecondWorstWhenStuck(patience) if n >= 2 => {
+                let target = if stuck >= patience { n - 1 } else { n };
+                let (new_simplex, step_kind) = reflect_vertex(
+                    f,
+                    f_all,
+                    curr_simplex.clone(),
+                    &params,
+                    &bounds_vec,
+                    target,
+                    target,
+                );
+                // the worst vertex is stuck while every new vertex ends up being the worst
+                if target < n || curr_simplex.iter().any(|(x, _)| *x == new_simplex[n].0) {
+                    stuck = 0;
+                } else {
+                    stuck += 1;
+                }
+                (new_simplex, step_kind)
+            }
+            _ => step_with(f, f_all, curr_simplex, &params, &bounds_vec, n),
+        };
+        curr_simplex = new_simplex;
+        iterations = iteration + 1;
+        if params.record_step_history {
+            step_history.push(step_kind);
+        }
+        if let Some(window) = params.detect_cycles {
+            let signature = simplex_signature(&curr_simplex);
+            if recent_signatures.contains(&signature) {
+                curr_simplex = shrink_with(f_all, curr_simplex, &params);
+                recent_signatures.clear();
+            } else {
+                recent_signatures.push_back(signature);
+                if recent_signatures.len() > window as usize {
+                    recent_signatures.pop_front();
+                }
+            }
+        }
+        curr_progress = progress(
+            &params,
+            iterations,
+            max_iter,
+            evaluator.evaluations.get(),
+            initial_spread,
+            &curr_simplex,
+        )
+        .max(curr_progress);
+        let info = IterationInfo {
+            iteration,
+            simplex: &curr_simplex,
+            progress: curr_progress,
+        };
+        match callback(&info) {
+            Action::Continue => {}
+            Action::SetBounds(new_bounds) => {
+                bounds_vec = new_bounds.as_vec();
+                curr_simplex = clamp_simplex(f, curr_simplex, &bounds_vec);
+            }
+            Action::TryPoint(candidate) => {
+                curr_simplex = add_point(f, curr_simplex, clamp(&candidate, &bounds_vec));
+            }
+            Action::Abort => {
+                termination = TerminationReason::Aborted;
+                break;
+            }
+        }
+        if params.track_diameter
+            && half_life_iteration.is_none()
+            && diameter(&curr_simplex) <= initial_diameter / T::from_f64(2.0)
+        {
+            half_life_iteration = Some(iteration);
+        }
+        if let Some((window, flat_tol)) = params.stop_on_plateau {
+            recent_ranges.push_back(evaluator.range.get());
+            if recent_ranges.len() > window as usize {
+                recent_ranges.pop_front();
+            }
+            if recent_ranges.len() == window as usize && is_plateau(&recent_ranges, flat_tol) {
+                termination = TerminationReason::Plateau;
+                break;
+            }
+        }
+        let scale = tolerance_scale(&params, evaluator.evaluations.get());
+        if converged(&curr_simplex, &params, scale) {
+            termination = TerminationReason::Converged;
+            break;
+        }
+        if let Some(min_simplex_size) = params.min_simplex_size {
+            if diameter(&curr_simplex) < min_simplex_size {
+                termination = TerminationReason::SimplexTooSmall;
+                break;
+            }
+        }
+        if let Some(patience) = params.restart_after {
+            if curr_simplex[0].1 < best_value {
+                best_value = curr_simplex[0].1;
+                stagnant = 0;
+            } else {
+                stagnant += 1;
+            }
+            if stagnant >= patience {
+                curr_simplex =
+                    restart_simplex(f_all, &curr_simplex[0], restart_size, &params, &bounds_vec);
+                restarts += 1;
+                stagnant = 0;
+            }
+        }
     }
+    let final_diameter = diameter(&curr_simplex);
+    let value_spread = curr_simplex[n].1 - curr_simplex[0].1;
+    let diameter_ratio = if params.track_diameter {
+        Some(final_diameter / initial_diameter)
+    } else {
+        None
+    };
+    // the final steps are skipped unless the budget affords every one of
+    // their evaluations, since the points past it would be infinitely bad
     let x1 = curr_simplex[0].0.clone();
     let fx1 = curr_simplex[0].1;
-    let x0 = {
-        let median_list = &curr_simplex.as_slice()[0..n];
-        avg(&median_list
+    let (point, value) = if evaluator.affords(1) {
+        let x0 = centroid(&curr_simplex);
+        let fx0 = f(&x0);
+        if fx1 < fx0 {
+            (x1, fx1)
+        } else {
+            (x0, fx0)
+        }
+    } else {
+        (x1, fx1)
+    };
+    let (point, value) = match &params.integer_refinement {
+        Some(mask)
+            if evaluator.affords(refine_on_grid_evaluations(&integer_spacing::<T>(mask, n))) =>
+        {
+            refine_integers(f, &point, mask, &bounds_vec)
+        }
+        _ => (point, value),
+    };
+    let (point, value) = match &params.snap_result_to_grid {
+        Some(spacing) if evaluator.affords(refine_on_grid_evaluations(spacing)) => {
+            refine_on_grid(f, &point, spacing, &bounds_vec)
+        }
+        _ => (point, value),
+    };
+    let point = match &params.allowed_values {
+        Some(allowed_values) => snap(&point, allowed_values),
+        None => point,
+    };
+    let (point, value) = evaluator.settle(point, value);
+    let feasible = value.is_finite()
+        && point
             .iter()
-            .map(|x| x.0.clone())
-            .collect::<Vec<Vec<f64>>>())
+            .zip(bounds_vec.iter())
+            .all(|(x, (min, max))| min <= x && x <= max);
+    let cache_hits = evaluator.cache_hits.get();
+    let cache_misses = evaluator.cache_misses.get();
+    let likely_local_minimum = match params.local_minimum_probes {
+        Some((probes, radius)) if evaluator.affords(probes) => {
+            let probe = |x: &[T]| evaluator.probe(x);
+            match params.seed {
+                Some(seed) => probe_local_minimum(
+                    probe,
+                    &point,
+                    value,
+                    (probes, radius),
+                    &bounds_vec,
+                    &mut StdRng::seed_from_u64(seed),
+                ),
+                None => probe_local_minimum(
+                    probe,
+                    &point,
+                    value,
+                    (probes, radius),
+                    &bounds_vec,
+                    &mut OsRng::new().expect("Failed to create the RNG"),
+                ),
+            }
+        }
+        _ => false,
     };
-    let fx0 = f(&x0);
-    if fx1 < fx0 {
-        (x1, fx1)
+    OptimizationResult {
+        point,
+        value,
+        // overridden by the entry points that start from a single point
+        initial_point: initial_simplex[0].0.clone(),
+        initial_value: initial_simplex[0].1,
+        iterations,
+        evaluations: evaluator.evaluations.get(),
+        termination,
+        restarts,
+        final_diameter,
+        value_spread,
+        feasible,
+        cache_hits,
+        cache_misses,
+        nan_evaluations: evaluator.nan_evaluations.get(),
+        likely_local_minimum,
+        step_history,
+        diameter_ratio,
+        half_life_iteration,
+        bounds: Bounds {
+            min: bounds_vec.iter().map(|(min, _)| *min).collect(),
+            max: bounds_vec.iter().map(|(_, max)| *max).collect(),
+        },
+        final_simplex: curr_simplex,
+        evaluation_history: evaluator.history.into_inner(),
+        boundary_mode: params.boundary_mode,
+    }
+}
+
+/// Builds a new simplex of size `size` around the `best` vertex, like the
+/// initial one, keeping `best` as a vertex.
+fn restart_simplex<T: Float>(
+    f_all: impl Fn(&[Vec<T>]) -> Vec<T>,
+    best: &(Vec<T>, T),
+    size: T,
+    params: &Params<T>,
+    bounds_vec: &[(T, T)],
+) -> Simplex<T> {
+    let initial_simplex = InitialSimplex::from_params(best.0.clone(), size, params);
+    let points: Vec<Vec<T>> = initial_simplex
+        .points
+        .iter()
+        .skip(1)
+        .map(|x| clamp(x, bounds_vec))
+        .collect();
+    let values = f_all(&points);
+    let mut simplex: Simplex<T> = vec![best.clone()];
+    simplex.extend(points.into_iter().zip(values));
+    sort_simplex(&mut simplex);
+    simplex
+}
+
+/// Rounds the dimensions of `point` flagged in `integer_mask` and searches
+/// every combination of -1, 0 and +1 offsets on those dimensions, returning
+/// the best point within the bounds.
+///
+/// Evaluates `f` at up to `3^k` points, where `k` is the number of integer
+/// dimensions, and only rounds `point` when `k` exceeds
+/// `MAX_GRID_SEARCH_DIMENSIONS`.
+fn refine_integers<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    point: &[T],
+    integer_mask: &[bool],
+    bounds_vec: &[(T, T)],
+) -> (Vec<T>, T) {
+    refine_on_grid(
+        f,
+        point,
+        &integer_spacing(integer_mask, point.len()),
+        bounds_vec,
+    )
+}
+
+/// The grid spacing of the dimensions flagged in `integer_mask`, out of
+/// `dimensions`.
+fn integer_spacing<T: Float>(integer_mask: &[bool], dimensions: usize) -> Vec<T> {
+    (0..dimensions)
+        .map(|i| match integer_mask.get(i) {
+            Some(true) => T::one(),
+            _ => T::zero(),
+        })
+        .collect()
+}
+
+/// Like `search_grid`, but only rounds `point` to the grid when it has more
+/// than `MAX_GRID_SEARCH_DIMENSIONS` grid dimensions.
+fn refine_on_grid<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    point: &[T],
+    spacing: &[T],
+    bounds_vec: &[(T, T)],
+) -> (Vec<T>, T) {
+    if spacing.iter().filter(|s| **s > T::zero()).count() > MAX_GRID_SEARCH_DIMENSIONS {
+        let rounded = round_to_grid(point, spacing);
+        let frounded = f(&rounded);
+        (rounded, frounded)
+    } else {
+        search_grid(f, point, spacing, bounds_vec)
+    }
+}
+
+/// The maximum number of points evaluated by `refine_on_grid`.
+fn refine_on_grid_evaluations<T: Float>(spacing: &[T]) -> u32 {
+    if spacing.iter().filter(|s| **s > T::zero()).count() > MAX_GRID_SEARCH_DIMENSIONS {
+        1
     } else {
-        (x0, fx0)
+        grid_evaluations(spacing)
+    }
+}
+
+/// The maximum number of points evaluated by `search_grid`.
+fn grid_evaluations<T: Float>(spacing: &[T]) -> u32 {
+    3u32.saturating_pow(spacing.iter().filter(|s| **s > T::zero()).count() as u32)
+}
+
+/// Rounds the dimensions of `point` with a positive `spacing` to the nearest
+/// multiple of it.
+fn round_to_grid<T: Float>(point: &[T], spacing: &[T]) -> Vec<T> {
+    point
+        .iter()
+        .enumerate()
+        .map(|(i, x)| match spacing.get(i) {
+            Some(&s) if s > T::zero() => (*x / s).round() * s,
+            _ => *x,
+        })
+        .collect()
+}
+
+/// Rounds the dimensions of `point` with a positive `spacing` to that grid
+/// and searches every combination of -1, 0 and +1 grid steps on those
+/// dimensions, returning the best point within the bounds.
+///
+/// Evaluates `f` at up to `3^k` points, where `k` is the number of grid
+/// dimensions.
+fn search_grid<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    point: &[T],
+    spacing: &[T],
+    bounds_vec: &[(T, T)],
+) -> (Vec<T>, T) {
+    let rounded = round_to_grid(point, spacing);
+    let grid_dims: Vec<usize> = (0..point.len())
+        .filter(|&i| spacing.get(i).is_some_and(|s| *s > T::zero()))
+        .collect();
+    let mut best: Option<(Vec<T>, T)> = None;
+    for combination in 0..3usize.pow(grid_dims.len() as u32) {
+        let mut candidate = rounded.clone();
+        let mut offsets = combination;
+        for &i in &grid_dims {
+            candidate[i] += T::from_f64((offsets % 3) as f64 - 1.0) * spacing[i];
+            offsets /= 3;
+        }
+        let in_bounds = candidate
+            .iter()
+            .zip(bounds_vec.iter())
+            .all(|(x, (min, max))| min <= x && x <= max);
+        if in_bounds {
+            let fcandidate = f(&candidate);
+            let improves = match &best {
+                Some((_, fbest)) => fcandidate < *fbest,
+                None => true,
+            };
+            if improves {
+                best = Some((candidate, fcandidate));
+            }
+        }
     }
+    best.unwrap_or_else(|| {
+        let frounded = f(&rounded);
+        (rounded, frounded)
+    })
 }
 
-pub fn new_simplex(f: impl Fn(&Vec<f64>) -> f64, center: Vec<f64>, step: f64) -> Simplex {
-    let mut rng = OsRng::new().expect("Failed to create the RNG");
-    let mut unsorted_points: Vec<Vec<f64>> = Vec::new();
-    for _ in 0..center.len() + 1 {
-        let new_point = center
+/// Evaluates up to `probes` random points within `radius` of `point`,
+/// clamped to the bounds, returning `true` if any of them is substantially
+/// lower than `value`.
+fn probe_local_minimum<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    point: &[T],
+    value: T,
+    (probes, radius): (u32, T),
+    bounds_vec: &[(T, T)],
+    rng: &mut impl Rng,
+) -> bool {
+    let margin = T::from_f64(1e-6) * (T::one() + value.abs());
+    (0..probes).any(|_| {
+        let probe: Vec<T> = point
             .iter()
-            .map(|x| x + rng.gen_range(-step, step))
+            .map(|&x| x + rng.gen_range(-radius, radius))
             .collect();
-        unsorted_points.push(new_point);
+        f(&clamp(&probe, bounds_vec)) < value - margin
+    })
+}
+
+/// Evaluates `f` twice at `point`, failing if the two values differ by more
+/// than `epsilon`.
+pub fn check_determinism<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    point: &[T],
+    epsilon: T,
+) -> Result<(), NelderMeadError> {
+    let first = f(point);
+    let second = f(point);
+    if (first - second).abs() > epsilon {
+        Err(NelderMeadError::NonDeterministicObjective {
+            first: first.as_f64(),
+            second: second.as_f64(),
+        })
+    } else {
+        Ok(())
     }
-    let mut simplex: Simplex = unsorted_points.iter().map(|x| (x.clone(), f(x))).collect();
+}
+
+/// Evaluates the vertices of `initial_simplex`, snapping them to
+/// `Params::allowed_values` before evaluating `f`, and resampling them as
+/// set by `Params::resample`.
+pub fn evaluate_initial_simplex<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    initial_simplex: &InitialSimplex<T>,
+    params: &Params<T>,
+) -> Simplex<T> {
+    evaluate_initial_simplex_with(|xs| evaluate_all(&f, xs), initial_simplex, params)
+}
+
+/// Evaluates the vertices of `initial_simplex` like
+/// `evaluate_initial_simplex`, all at once with `f_all`.
+pub fn evaluate_initial_simplex_with<T: Float>(
+    f_all: impl Fn(&[Vec<T>]) -> Vec<T>,
+    initial_simplex: &InitialSimplex<T>,
+    params: &Params<T>,
+) -> Simplex<T> {
+    let (simplex, _) = evaluate_initial_simplex_from_first(f_all, initial_simplex, params);
+    simplex
+}
+
+/// Evaluates the vertices of `initial_simplex` like
+/// `evaluate_initial_simplex_with`, also returning the value of its first
+/// vertex, which may no longer be first once the simplex is sorted, or NaN
+/// if it has no vertices.
+pub(crate) fn evaluate_initial_simplex_from_first<T: Float>(
+    f_all: impl Fn(&[Vec<T>]) -> Vec<T>,
+    initial_simplex: &InitialSimplex<T>,
+    params: &Params<T>,
+) -> (Simplex<T>, T) {
+    let values = match &params.allowed_values {
+        Some(allowed_values) => {
+            let snapped: Vec<Vec<T>> = initial_simplex
+                .points
+                .iter()
+                .map(|x| snap(x, allowed_values))
+                .collect();
+            sample_all(f_all, &snapped, params)
+        }
+        None => sample_all(f_all, &initial_simplex.points, params),
+    };
+    let first_value = values.first().copied().unwrap_or(T::nan());
+    let mut simplex: Simplex<T> = initial_simplex.points.iter().cloned().zip(values).collect();
+    sort_simplex(&mut simplex);
+    (simplex, first_value)
+}
+
+pub fn evaluate_simplex<T: Float>(
+    f: impl Fn(&[T]) -> T,
+    initial_simplex: &InitialSimplex<T>,
+) -> Simplex<T> {
+    let mut simplex: Simplex<T> = initial_simplex
+        .points
+        .iter()
+        .map(|x| (x.clone(), f(x)))
+        .collect();
     sort_simplex(&mut simplex);
     simplex
 }
@@ -126,12 +1595,19 @@ mod tests {
     extern crate assert_approx_eq;
     use super::*;
     use assert_approx_eq::assert_approx_eq;
+    use std::cell::{Cell, RefCell};
+    use std::time::Duration;
+
+    fn new_simplex(f: impl Fn(&[f64]) -> f64, center: Vec<f64>, step: f64) -> Simplex {
+        evaluate_simplex(f, &InitialSimplex::new(center, step))
+    }
 
     #[test]
     fn minimize_square() {
-        let f: &Fn(&Vec<f64>) -> f64 = &(|args| args[0] * args[0] + args[1] * args[1] + 5.0);
+        let f: &dyn Fn(&[f64]) -> f64 = &(|args| args[0] * args[0] + args[1] * args[1] + 5.0);
         let initial_simplex = new_simplex(f, vec![2.0, 2.0], 0.5);
-        let (point, value) = minimize(f, initial_simplex, Params::default(), Bounds::none(2), 500);
+        let OptimizationResult { point, value, .. } =
+            minimize(f, initial_simplex, Default::default(), Bounds::none(2), 500);
         assert_approx_eq!(point[0], 0.0);
         assert_approx_eq!(point[1], 0.0);
         assert_approx_eq!(value, 5.0);
@@ -139,15 +1615,2010 @@ mod tests {
 
     #[test]
     fn minimize_with_bounds() {
-        let f: &Fn(&Vec<f64>) -> f64 = &(|args| args[0] + args[1] + 5.0);
+        let f: &dyn Fn(&[f64]) -> f64 = &(|args| args[0] + args[1] + 5.0);
         let bounds = Bounds {
             min: vec![-1.0, 0.5],
             max: vec![10.0, 10.0],
         };
         let initial_simplex = new_simplex(f, vec![2.0, 2.0], 0.5);
-        let (point, value) = minimize(f, initial_simplex, Params::default(), bounds, 500);
+        let OptimizationResult { point, value, .. } =
+            minimize(f, initial_simplex, Default::default(), bounds, 500);
         assert_approx_eq!(point[0], -1.0);
         assert_approx_eq!(point[1], 0.5);
         assert_approx_eq!(value, 4.5);
     }
+
+    #[test]
+    fn minimize_two_objectives_from_same_geometry() {
+        let initial_simplex = InitialSimplex::new(vec![2.0, 2.0], 0.5);
+        let f: &dyn Fn(&[f64]) -> f64 = &(|args| args[0] * args[0] + args[1] * args[1]);
+        let g: &dyn Fn(&[f64]) -> f64 =
+            &(|args| (args[0] - 1.0) * (args[0] - 1.0) + (args[1] + 3.0) * (args[1] + 3.0) + 2.0);
+        let f_result = minimize(
+            f,
+            evaluate_simplex(f, &initial_simplex),
+            Default::default(),
+            Bounds::none(2),
+            500,
+        );
+        let g_result = minimize(
+            g,
+            evaluate_simplex(g, &initial_simplex),
+            Default::default(),
+            Bounds::none(2),
+            500,
+        );
+        assert_approx_eq!(f_result.point[0], 0.0);
+        assert_approx_eq!(f_result.point[1], 0.0);
+        assert_approx_eq!(f_result.value, 0.0);
+        assert_approx_eq!(g_result.point[0], 1.0);
+        assert_approx_eq!(g_result.point[1], -3.0);
+        assert_approx_eq!(g_result.value, 2.0);
+    }
+
+    #[test]
+    fn minimize_with_value_tolerances() {
+        let params = || Params {
+            f_atol: 1e-20,
+            f_rtol: 1e-10,
+            ..Default::default()
+        };
+        let huge_evals = Cell::new(0);
+        let huge = |args: &[f64]| {
+            huge_evals.set(huge_evals.get() + 1);
+            1e12 * (1.0 + (args[0] - 1.0) * (args[0] - 1.0) + (args[1] - 2.0) * (args[1] - 2.0))
+        };
+        let tiny_evals = Cell::new(0);
+        let tiny = |args: &[f64]| {
+            tiny_evals.set(tiny_evals.get() + 1);
+            1e-12 * ((args[0] - 1.0) * (args[0] - 1.0) + (args[1] - 2.0) * (args[1] - 2.0))
+        };
+        let huge_result = minimize(
+            huge,
+            new_simplex(huge, vec![3.0, 3.0], 0.5),
+            params(),
+            Bounds::none(2),
+            5000,
+        );
+        let tiny_result = minimize(
+            tiny,
+            new_simplex(tiny, vec![3.0, 3.0], 0.5),
+            params(),
+            Bounds::none(2),
+            5000,
+        );
+        assert_approx_eq!(huge_result.point[0], 1.0, 1e-3);
+        assert_approx_eq!(huge_result.point[1], 2.0, 1e-3);
+        assert_approx_eq!(huge_result.value, 1e12, 1e4);
+        assert_approx_eq!(tiny_result.point[0], 1.0, 1e-3);
+        assert_approx_eq!(tiny_result.point[1], 2.0, 1e-3);
+        assert_approx_eq!(tiny_result.value, 0.0);
+        assert!(huge_evals.get() < 5000);
+        assert!(tiny_evals.get() < 5000);
+    }
+
+    #[test]
+    fn flag_local_minimum_in_shallow_basin() {
+        // double well with a shallow basin near x = 2 and a deeper one near x = -2
+        let f: &dyn Fn(&[f64]) -> f64 =
+            &(|args| (args[0] * args[0] - 4.0) * (args[0] * args[0] - 4.0) + args[0]);
+        let params = Params {
+            local_minimum_probes: Some((200, 5.0)),
+            ..Default::default()
+        };
+        let initial_simplex = new_simplex(f, vec![3.0], 0.5);
+        let result = minimize(f, initial_simplex, params, Bounds::none(1), 500);
+        assert!(result.point[0] > 0.0);
+        assert!(result.likely_local_minimum);
+    }
+
+    #[test]
+    fn probe_within_bounds_from_seed() {
+        let probed = RefCell::new(Vec::new());
+        let f = |args: &[f64]| {
+            probed.borrow_mut().push(args.to_vec());
+            (args[0] - 1.0).powi(2)
+        };
+        let params = Params {
+            local_minimum_probes: Some((50, 5.0)),
+            seed: Some(7),
+            ..Default::default()
+        };
+        let bounds = Bounds {
+            min: vec![0.0],
+            max: vec![2.0],
+        };
+        let run = || {
+            probed.borrow_mut().clear();
+            let initial_simplex =
+                evaluate_simplex(f, &InitialSimplex::axis_aligned(vec![1.5], 0.25));
+            let result = minimize(f, initial_simplex, params.clone(), bounds.clone(), 500);
+            let probed = probed.borrow();
+            let probes = probed[probed.len() - 50..].to_vec();
+            assert!(probes.iter().all(|x| 0.0 <= x[0] && x[0] <= 2.0));
+            assert_eq!(result.evaluations as usize, probed.len());
+            assert!(!result.likely_local_minimum);
+            probes
+        };
+        assert_eq!(run(), run());
+    }
+
+    #[test]
+    fn do_not_flag_global_minimum() {
+        let f: &dyn Fn(&[f64]) -> f64 = &(|args| args[0] * args[0] + args[1] * args[1]);
+        let params = Params {
+            local_minimum_probes: Some((200, 5.0)),
+            ..Default::default()
+        };
+        let initial_simplex = new_simplex(f, vec![2.0, 2.0], 0.5);
+        let result = minimize(f, initial_simplex, params, Bounds::none(2), 500);
+        assert!(!result.likely_local_minimum);
+    }
+
+    #[test]
+    fn tighten_bounds_from_callback() {
+        let f: &dyn Fn(&[f64]) -> f64 = &(|args| args[0] * args[0] + args[1] * args[1]);
+        let initial_simplex = new_simplex(f, vec![2.0, 2.0], 0.5);
+        let mut tightened = false;
+        let result = minimize_with_callback(
+            f,
+            initial_simplex,
+            Default::default(),
+            Bounds::none(2),
+            500,
+            |info| {
+                if tightened {
+                    assert!(info.simplex.iter().all(|(x, _)| x[0] >= 0.5));
+                    Action::Continue
+                } else if info.iteration == 10 {
+                    tightened = true;
+                    Action::SetBounds(Bounds {
+                        min: vec![0.5, -5.0],
+                        max: vec![5.0, 5.0],
+                    })
+                } else {
+                    Action::Continue
+                }
+            },
+        );
+        assert!(tightened);
+        assert_approx_eq!(result.point[0], 0.5);
+        assert_approx_eq!(result.point[1], 0.0);
+        assert_approx_eq!(result.value, 0.25);
+    }
+
+    #[test]
+    fn inject_candidate_from_callback() {
+        let f: &dyn Fn(&[f64]) -> f64 = &(|args| (args[0] - 3.0).powi(2) + (args[1] + 1.0).powi(2));
+        let initial_simplex = new_simplex(f, vec![-5.0, 5.0], 0.5);
+        let mut incumbents = Vec::new();
+        minimize_with_callback(
+            f,
+            initial_simplex,
+            Default::default(),
+            Bounds::none(2),
+            3,
+            |info| {
+                incumbents.push(info.simplex[0].clone());
+                if info.iteration == 0 {
+                    Action::TryPoint(vec![3.0, -1.0])
+                } else {
+                    Action::Continue
+                }
+            },
+        );
+        assert!(incumbents[0].1 > 0.0);
+        assert_eq!(incumbents[1], (vec![3.0, -1.0], 0.0));
+    }
+
+    #[test]
+    fn treat_nan_as_worst_value() {
+        // NaN outside of the unit disk, where the minimum is on the boundary
+        let f = |x: &[f64]| {
+            if x[0] * x[0] + x[1] * x[1] > 1.0 {
+                f64::NAN
+            } else {
+                (x[0] - 2.0).powi(2) + x[1] * x[1]
+            }
+        };
+        let initial_simplex =
+            evaluate_simplex(f, &InitialSimplex::axis_aligned(vec![0.0, 0.0], 0.5));
+        let result = minimize(f, initial_simplex, Default::default(), Bounds::none(2), 500);
+        assert!(result.value.is_finite());
+        assert!(result.nan_evaluations > 0);
+        assert!(result.point[0] > 0.99);
+        assert!(result.point[1].abs() < 0.1);
+        assert!(result.final_simplex.iter().all(|(_, fx)| !fx.is_nan()));
+
+        let well_behaved = |x: &[f64]| x[0] * x[0] + x[1] * x[1];
+        let initial_simplex = evaluate_simplex(
+            well_behaved,
+            &InitialSimplex::axis_aligned(vec![1.0, 1.0], 0.5),
+        );
+        let result = minimize(
+            well_behaved,
+            initial_simplex,
+            Default::default(),
+            Bounds::none(2),
+            100,
+        );
+        assert_eq!(result.nan_evaluations, 0);
+    }
+
+    #[test]
+    fn abort_from_callback() {
+        let f: &dyn Fn(&[f64]) -> f64 = &(|args| args[0] * args[0] + args[1] * args[1]);
+        let initial_simplex =
+            evaluate_simplex(f, &InitialSimplex::axis_aligned(vec![2.0, 2.0], 0.5));
+        let mut best_values = Vec::new();
+        let result = minimize_with_callback(
+            f,
+            initial_simplex,
+            Default::default(),
+            Bounds::none(2),
+            500,
+            |info| {
+                best_values.push(info.simplex[0].1);
+                if info.iteration == 5 {
+                    Action::Abort
+                } else {
+                    Action::Continue
+                }
+            },
+        );
+        assert_eq!(result.termination, TerminationReason::Aborted);
+        assert!(!result.converged());
+        assert_eq!(result.iterations, 6);
+        assert_eq!(best_values.len(), 6);
+        assert!(result.value <= best_values[5]);
+        assert!(best_values.windows(2).all(|w| w[1] <= w[0]));
+    }
+
+    #[test]
+    fn record_step_history_on_rosenbrock() {
+        let f: &dyn Fn(&[f64]) -> f64 = &(|args| {
+            (1.0 - args[0]) * (1.0 - args[0])
+                + 100.0 * (args[1] - args[0] * args[0]) * (args[1] - args[0] * args[0])
+        });
+        let params = Params {
+            f_atol: 1e-12,
+            record_step_history: true,
+            ..Default::default()
+        };
+        let initial_simplex = new_simplex(f, vec![-1.2, 1.0], 0.1);
+        let result = minimize(f, initial_simplex, params, Bounds::none(2), 5000);
+        assert_approx_eq!(result.point[0], 1.0, 1e-3);
+        assert_approx_eq!(result.point[1], 1.0, 1e-3);
+        let history = &result.step_history;
+        let count =
+            |steps: &[StepKind], kind: StepKind| steps.iter().filter(|&&k| k == kind).count();
+        assert!(count(history, StepKind::Reflection) > 0);
+        assert!(count(history, StepKind::Expansion) > 0);
+        assert!(count(history, StepKind::Contraction) > 0);
+        let tail = &history[history.len() / 2..];
+        assert!(
+            count(tail, StepKind::Reflection) + count(tail, StepKind::Contraction)
+                > count(tail, StepKind::Expansion) + count(tail, StepKind::Shrink)
+        );
+    }
+
+    #[test]
+    fn do_not_record_step_history_by_default() {
+        let f: &dyn Fn(&[f64]) -> f64 = &(|args| args[0] * args[0] + args[1] * args[1]);
+        let initial_simplex = new_simplex(f, vec![2.0, 2.0], 0.5);
+        let result = minimize(f, initial_simplex, Default::default(), Bounds::none(2), 100);
+        assert!(result.step_history.is_empty());
+    }
+
+    #[test]
+    fn loosen_tolerance_as_budget_runs_out() {
+        let f: &dyn Fn(&[f64]) -> f64 = &(|args| args[0] * args[0] + args[1] * args[1]);
+        let initial_simplex = InitialSimplex {
+            points: vec![vec![2.0, 2.0], vec![2.5, 2.0], vec![2.0, 2.5]],
+        };
+        let params = |adaptive_tolerance| Params {
+            f_atol: 1e-14,
+            max_evaluations: Some(100),
+            adaptive_tolerance,
+            ..Default::default()
+        };
+        let strict = minimize(
+            f,
+            evaluate_simplex(f, &initial_simplex),
+            params(false),
+            Bounds::none(2),
+            5000,
+        );
+        let adaptive = minimize(
+            f,
+            evaluate_simplex(f, &initial_simplex),
+            params(true),
+            Bounds::none(2),
+            5000,
+        );
+        assert!(strict.evaluations >= 100);
+        assert!(adaptive.evaluations < 100);
+        assert_approx_eq!(adaptive.point[0], 0.0, 1e-2);
+        assert_approx_eq!(adaptive.point[1], 0.0, 1e-2);
+    }
+
+    #[test]
+    fn exclude_vertex_from_centroid() {
+        let evaluated = RefCell::new(Vec::new());
+        let f = |args: &[f64]| {
+            evaluated.borrow_mut().push(args.to_vec());
+            args[0] * args[0] + args[1] * args[1]
+        };
+        let simplex = evaluate_simplex(
+            f,
+            &InitialSimplex {
+                points: vec![vec![1.0, 0.0], vec![0.0, 2.0], vec![3.0, 3.0]],
+            },
+        );
+        let bounds_vec = Bounds::none(2).as_vec();
+
+        // excluding the worst vertex reflects it through the centroid of the others
+        evaluated.borrow_mut().clear();
+        step(f, simplex.clone(), &Default::default(), &bounds_vec, 2);
+        assert_eq!(evaluated.borrow()[0], vec![-2.0, -1.0]);
+
+        // excluding the best vertex reflects the worst one through the centroid of [(0, 2), (3, 3)]
+        evaluated.borrow_mut().clear();
+        step(f, simplex, &Default::default(), &bounds_vec, 0);
+        assert_eq!(evaluated.borrow()[0], vec![0.0, 2.0]);
+    }
+
+    #[test]
+    fn keep_top_vertices_on_shrink() {
+        // every reflection, expansion and contraction point is worse than the worst vertex
+        let f = |args: &[f64]| {
+            if args[0] > 1.0 || args[1] > 1.0 || args[0] < 0.0 || args[1] < 0.0 {
+                100.0
+            } else {
+                -(args[0] - 0.5).abs() - (args[1] - 0.5).abs()
+            }
+        };
+        let simplex = evaluate_simplex(
+            f,
+            &InitialSimplex {
+                points: vec![vec![0.0, 0.0], vec![1.0, 0.0], vec![0.5, 1.0]],
+            },
+        );
+        let params = Params {
+            shrink_keep: 2,
+            ..Default::default()
+        };
+        let (new_simplex, kind) = step(f, simplex.clone(), &params, &Bounds::none(2).as_vec(), 2);
+        assert_eq!(kind, StepKind::Shrink);
+        let points: Vec<Vec<f64>> = new_simplex.into_iter().map(|(x, _)| x).collect();
+        // the two best vertices stay put, the worst moves halfway towards the best one
+        assert!(points.contains(&simplex[0].0));
+        assert!(points.contains(&simplex[1].0));
+        assert!(!points.contains(&simplex[2].0));
+        let best = &simplex[0].0;
+        let shrunk = sum(best, &mult(0.5, &diff(&simplex[2].0, best)));
+        assert!(points.contains(&shrunk));
+    }
+
+    #[test]
+    fn stop_once_values_and_size_converge() {
+        let evaluations = Cell::new(0);
+        let f = |x: &[f64]| {
+            evaluations.set(evaluations.get() + 1);
+            (x[0] - 1.0) * (x[0] - 1.0) + 3.0 * (x[1] + 2.0) * (x[1] + 2.0)
+        };
+        let initial_simplex = InitialSimplex::axis_aligned(vec![4.0, 3.0], 1.0);
+        let loose = Params {
+            f_atol: 1e-4,
+            ..Default::default()
+        };
+        let tight = Params {
+            x_atol: 1e-6,
+            ..loose.clone()
+        };
+
+        let loose_result = minimize(
+            f,
+            evaluate_simplex(f, &initial_simplex),
+            loose,
+            Bounds::none(2),
+            1000,
+        );
+        assert_eq!(loose_result.termination, TerminationReason::Converged);
+        assert!(loose_result.iterations < 1000);
+
+        evaluations.set(0);
+        let tight_result = minimize(
+            f,
+            evaluate_simplex(f, &initial_simplex),
+            tight,
+            Bounds::none(2),
+            1000,
+        );
+        assert_eq!(tight_result.termination, TerminationReason::Converged);
+        assert!(tight_result.iterations > loose_result.iterations);
+        assert!(tight_result.iterations < 1000);
+        assert!(tight_result.final_diameter <= 1e-6);
+        assert!(evaluations.get() < 1000);
+        assert_approx_eq!(tight_result.point[0], 1.0, 1e-5);
+        assert_approx_eq!(tight_result.point[1], -2.0, 1e-5);
+    }
+
+    #[test]
+    fn stop_degenerate_simplex_on_first_iteration() {
+        let f = |_: &[f64]| 1.0;
+        let collapsed = evaluate_simplex(
+            f,
+            &InitialSimplex {
+                points: vec![vec![2.0, 2.0]; 3],
+            },
+        );
+        let params = Params {
+            x_atol: 1e-8,
+            ..Default::default()
+        };
+        let result = minimize(f, collapsed, params, Bounds::none(2), 1000);
+        assert_eq!(result.termination, TerminationReason::Converged);
+        assert_eq!(result.iterations, 1);
+
+        let flat = evaluate_simplex(f, &InitialSimplex::axis_aligned(vec![2.0, 2.0], 1.0));
+        let params = Params {
+            f_atol: 1e-8,
+            ..Default::default()
+        };
+        let result = minimize(f, flat, params, Bounds::none(2), 1000);
+        assert_eq!(result.termination, TerminationReason::Converged);
+        assert_eq!(result.iterations, 1);
+    }
+
+    #[test]
+    fn select_boundary_mode_by_early_progress() {
+        // a narrow valley that ends on the x = 0 bound, where clamping flattens
+        // the simplex against the bound
+        let f = |x: &[f64]| 50.0 * (x[0] + x[1] - 0.2).powi(2) + x[0];
+        let bounds = Bounds {
+            min: vec![0.0, 0.0],
+            max: vec![0.9, 1.0],
+        };
+        let initial_simplex = InitialSimplex::axis_aligned(vec![0.3, 0.6], 0.2);
+        let run = |mode: BoundaryMode, max_iter: u32| {
+            let params = Params {
+                boundary_mode: mode,
+                ..Default::default()
+            };
+            minimize(
+                f,
+                evaluate_simplex(f, &initial_simplex),
+                params,
+                bounds.clone(),
+                max_iter,
+            )
+        };
+        let clamped = run(BoundaryMode::Clamp, 10);
+        let truncated = run(BoundaryMode::Truncate, 10);
+        assert!(truncated.value < clamped.value);
+
+        let auto = run(BoundaryMode::Auto(10), 100);
+        assert_eq!(auto.boundary_mode, BoundaryMode::Truncate);
+        assert_eq!(auto.point[0], 0.0);
+        assert_approx_eq!(auto.point[1], 0.2, 1e-6);
+        assert!(auto.evaluations > run(BoundaryMode::Truncate, 100).evaluations);
+    }
+
+    #[test]
+    fn share_the_budget_with_the_boundary_mode_probes() {
+        let calls = Cell::new(0);
+        let f = |x: &[f64]| {
+            calls.set(calls.get() + 1);
+            50.0 * (x[0] + x[1] - 0.2).powi(2) + x[0]
+        };
+        let bounds = Bounds {
+            min: vec![0.0, 0.0],
+            max: vec![0.9, 1.0],
+        };
+        let initial_simplex =
+            evaluate_simplex(f, &InitialSimplex::axis_aligned(vec![0.3, 0.6], 0.2));
+        calls.set(3);
+        let result = minimize(
+            f,
+            initial_simplex,
+            Params {
+                boundary_mode: BoundaryMode::Auto(20),
+                max_evaluations: Some(50),
+                record_evaluations: true,
+                ..Default::default()
+            },
+            bounds,
+            1000,
+        );
+        assert!(calls.get() <= 50);
+        assert_eq!(result.evaluations, calls.get());
+        assert_eq!(result.evaluation_history.len(), calls.get() as usize);
+        assert_eq!(result.termination, TerminationReason::MaxEvaluations);
+    }
+
+    #[test]
+    fn continue_from_the_winning_probe() {
+        let f = |x: &[f64]| 50.0 * (x[0] + x[1] - 0.2).powi(2) + x[0];
+        let bounds = Bounds {
+            min: vec![0.0, 0.0],
+            max: vec![0.9, 1.0],
+        };
+        let initial_simplex = InitialSimplex::axis_aligned(vec![0.3, 0.6], 0.2);
+        let run = |mode, max_iter| {
+            let params = Params {
+                boundary_mode: mode,
+                ..Default::default()
+            };
+            minimize(
+                f,
+                evaluate_simplex(f, &initial_simplex),
+                params,
+                bounds.clone(),
+                max_iter,
+            )
+        };
+        // the probes count towards the iterations, and the chosen mode picks
+        // up where its probe stopped
+        let auto = run(BoundaryMode::Auto(10), 30);
+        let truncated = run(BoundaryMode::Truncate, 30);
+        assert_eq!(auto.boundary_mode, BoundaryMode::Truncate);
+        assert_eq!(auto.iterations, truncated.iterations);
+        assert_eq!(auto.point, truncated.point);
+    }
+
+    #[test]
+    fn reach_bounds_through_transform() {
+        // the narrow valley of auto_select_boundary_mode, where clamping
+        // flattens the simplex against the x = 0 bound
+        let f = |x: &[f64]| 50.0 * (x[0] + x[1] - 0.2).powi(2) + x[0];
+        let bounds = Bounds {
+            min: vec![0.0, 0.0],
+            max: vec![0.9, 1.0],
+        };
+        let initial_simplex = InitialSimplex::axis_aligned(vec![0.3, 0.6], 0.2);
+        let mut within_bounds = true;
+        let result = minimize_with_callback(
+            f,
+            evaluate_simplex(f, &initial_simplex),
+            Params {
+                boundary_mode: BoundaryMode::Transform,
+                ..Default::default()
+            },
+            bounds.clone(),
+            1000,
+            |info| {
+                within_bounds &= info.simplex.iter().all(|(x, _)| {
+                    bounds
+                        .as_vec()
+                        .iter()
+                        .zip(x.iter())
+                        .all(|((min, max), x)| min <= x && x <= max)
+                });
+                Action::Continue
+            },
+        );
+        assert!(within_bounds);
+        assert_eq!(result.boundary_mode, BoundaryMode::Transform);
+        assert_eq!(result.bounds.as_vec(), bounds.as_vec());
+        assert!(result.feasible);
+        assert_approx_eq!(result.point[0], 0.0, 1e-6);
+        assert_approx_eq!(result.point[1], 0.2, 1e-6);
+        assert_eq!(result.value, f(&result.point));
+        assert_eq!(result.initial_point, vec![0.3, 0.6]);
+    }
+
+    #[test]
+    fn reach_corner_through_transform() {
+        // decreasing along both axes, down to the (2, 3) corner, which the
+        // transformed coordinates only reach once they saturate
+        let f = |x: &[f64]| -x[0] - 2.0 * x[1];
+        let bounds = Bounds {
+            min: vec![-1.0, -1.0],
+            max: vec![2.0, 3.0],
+        };
+        let params = Params {
+            boundary_mode: BoundaryMode::Transform,
+            ..Default::default()
+        };
+        let initial_simplex = InitialSimplex::axis_aligned(vec![0.0, 0.0], 0.5);
+        let result = minimize(
+            f,
+            evaluate_simplex(f, &initial_simplex),
+            params,
+            bounds,
+            1000,
+        );
+        assert_approx_eq!(result.point[0], 2.0, 1e-6);
+        assert_approx_eq!(result.point[1], 3.0, 1e-6);
+        assert!(result.feasible);
+    }
+
+    #[test]
+    fn reach_corner_when_reflecting_off_bounds() {
+        // decreasing along both axes, down to the (2, 3) corner
+        let f = |x: &[f64]| -x[0] - 2.0 * x[1];
+        let bounds = Bounds {
+            min: vec![-1.0, -1.0],
+            max: vec![2.0, 3.0],
+        };
+        let params = Params {
+            boundary_mode: BoundaryMode::Reflect,
+            ..Default::default()
+        };
+        let initial_simplex = InitialSimplex::axis_aligned(vec![0.0, 0.0], 0.5);
+        let result = minimize(
+            f,
+            evaluate_simplex(f, &initial_simplex),
+            params,
+            bounds,
+            1000,
+        );
+        assert_approx_eq!(result.point[0], 2.0, 1e-6);
+        assert_approx_eq!(result.point[1], 3.0, 1e-6);
+        assert_approx_eq!(result.value, -8.0, 1e-6);
+        assert!(result.feasible);
+    }
+
+    #[cfg(feature = "log")]
+    #[test]
+    fn log_every_step() {
+        thread_local! {
+            static RECORDS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+        }
+        struct Capture;
+        impl log::Log for Capture {
+            fn enabled(&self, metadata: &log::Metadata) -> bool {
+                metadata.level() <= log::Level::Debug
+            }
+            fn log(&self, record: &log::Record) {
+                if record.target() == module_path!().trim_end_matches("::tests") {
+                    RECORDS.with(|records| records.borrow_mut().push(record.args().to_string()));
+                }
+            }
+            fn flush(&self) {}
+        }
+        static CAPTURE: Capture = Capture;
+        let _ = log::set_logger(&CAPTURE);
+        log::set_max_level(log::LevelFilter::Debug);
+
+        let f = |args: &[f64]| args[0] * args[0] + args[1] * args[1];
+        let params = Params {
+            record_step_history: true,
+            ..Default::default()
+        };
+        let initial_simplex =
+            evaluate_simplex(f, &InitialSimplex::axis_aligned(vec![2.0, 2.0], 0.5));
+        let result = minimize(f, initial_simplex, params, Bounds::none(2), 20);
+        let records = RECORDS.with(|records| records.borrow().clone());
+        assert_eq!(records.len(), 20);
+        for (record, kind) in records.iter().zip(result.step_history.iter()) {
+            assert!(record.starts_with("centroid=["));
+            assert!(record.contains(" reflection=["));
+            assert!(record.contains(" f(reflection)="));
+            assert!(record.contains(" expansion="));
+            assert!(record.contains(" contraction="));
+            assert!(record.ends_with(&format!(" step={:?}", kind)));
+        }
+    }
+
+    #[test]
+    fn report_sorted_final_simplex() {
+        let f = |args: &[f64]| (args[0] - 1.0).powi(2) + 4.0 * (args[1] + 2.0).powi(2);
+        let initial_simplex =
+            evaluate_simplex(f, &InitialSimplex::axis_aligned(vec![3.0, 3.0], 1.0));
+        let result = minimize(f, initial_simplex, Default::default(), Bounds::none(2), 40);
+        let final_simplex = result.final_simplex();
+        assert_eq!(final_simplex.len(), 3);
+        assert!(final_simplex.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+        assert!(final_simplex.iter().all(|(x, fx)| f(x) == *fx));
+        assert!(final_simplex[0].1 >= result.value);
+    }
+
+    #[test]
+    fn stop_calling_the_objective_once_the_budget_is_spent() {
+        let calls = Cell::new(0);
+        let f = |args: &[f64]| {
+            calls.set(calls.get() + 1);
+            (args[0] - 1.0).powi(2) + (args[1] + 2.0).powi(2) + (args[2] - 0.5).powi(2)
+        };
+        // the budgets run out in the middle of every kind of step
+        for budget in 4..80 {
+            let initial_simplex =
+                evaluate_simplex(f, &InitialSimplex::axis_aligned(vec![3.0, 3.0, 3.0], 1.0));
+            calls.set(4);
+            let result = minimize(
+                f,
+                initial_simplex,
+                Params {
+                    max_evaluations: Some(budget),
+                    ..Default::default()
+                },
+                Bounds::none(3),
+                1000,
+            );
+            assert_eq!(calls.get(), budget);
+            assert_eq!(result.evaluations, budget);
+            assert_eq!(result.termination, TerminationReason::MaxEvaluations);
+            assert!(result.value.is_finite());
+        }
+    }
+
+    #[test]
+    fn fit_the_final_steps_in_the_budget() {
+        let calls = Cell::new(0);
+        let g = |args: &[f64]| (args[0] - 2.2).powi(2) + (args[1] - 1.0).powi(2);
+        let f = |args: &[f64]| {
+            calls.set(calls.get() + 1);
+            g(args)
+        };
+        let configurations = vec![
+            Params {
+                integer_refinement: Some(vec![true, false]),
+                ..Default::default()
+            },
+            Params {
+                integer_refinement: Some(vec![true, false]),
+                boundary_mode: BoundaryMode::Transform,
+                ..Default::default()
+            },
+            Params {
+                resample: Some((2, Aggregate::Mean)),
+                local_minimum_probes: Some((5, 0.1)),
+                ..Default::default()
+            },
+        ];
+        for params in configurations {
+            for budget in 6..60 {
+                let params = Params {
+                    max_evaluations: Some(budget),
+                    ..params.clone()
+                };
+                calls.set(0);
+                let initial_simplex = evaluate_initial_simplex(
+                    f,
+                    &InitialSimplex::axis_aligned(vec![0.0, 0.0], 1.0),
+                    &params,
+                );
+                let result = minimize(
+                    f,
+                    initial_simplex,
+                    params,
+                    Bounds {
+                        min: vec![-5.0, -5.0],
+                        max: vec![5.0, 5.0],
+                    },
+                    1000,
+                );
+                assert!(calls.get() <= budget);
+                assert_eq!(result.evaluations, calls.get());
+                assert_eq!(result.value, g(&result.point));
+            }
+        }
+    }
+
+    #[test]
+    fn only_evaluate_the_points_each_step_needs() {
+        let evaluations = Cell::new(0);
+        let f = |args: &[f64]| {
+            evaluations.set(evaluations.get() + 1);
+            (args[0] - 3.0).powi(2) + 2.0 * (args[1] + 1.0).powi(2)
+        };
+        let mut simplex = evaluate_simplex(f, &InitialSimplex::axis_aligned(vec![0.0, 0.0], 1.0));
+        let bounds_vec = Bounds::none(2).as_vec();
+        let mut kinds = Vec::new();
+        for _ in 0..50 {
+            evaluations.set(0);
+            let (new_simplex, kind) = step(f, simplex, &Default::default(), &bounds_vec, 2);
+            // a reflection costs a second evaluation when an expansion was
+            // tried and rejected
+            let expected = match kind {
+                StepKind::Reflection => 1..=2,
+                StepKind::Expansion | StepKind::Contraction => 2..=2,
+                StepKind::Shrink => 4..=4,
+            };
+            assert!(expected.contains(&evaluations.get()));
+            kinds.push(kind);
+            simplex = new_simplex;
+        }
+        assert!(kinds.contains(&StepKind::Expansion));
+        assert!(kinds.contains(&StepKind::Contraction));
+    }
+
+    #[test]
+    fn keep_better_vertices_on_greedy_shrink() {
+        // every point away from the simplex is worse than the worst vertex,
+        // and shrinking only improves the top vertex
+        let evaluations = Cell::new(0);
+        let f = |args: &[f64]| {
+            evaluations.set(evaluations.get() + 1);
+            match (args[0], args[1]) {
+                (x, y) if x == 0.0 && y == 0.0 => 0.0,
+                (x, y) if x == 1.0 && y == 0.0 => 1.0,
+                (x, y) if x == 0.0 && y == 1.0 => 2.0,
+                (x, y) if x == 0.5 && y == 0.0 => 3.0,
+                (x, y) if x == 0.0 && y == 0.5 => 1.5,
+                _ => 100.0,
+            }
+        };
+        let simplex = evaluate_simplex(
+            f,
+            &InitialSimplex {
+                points: vec![vec![0.0, 0.0], vec![1.0, 0.0], vec![0.0, 1.0]],
+            },
+        );
+        let params = Params {
+            greedy_shrink: true,
+            ..Default::default()
+        };
+        evaluations.set(0);
+        let (new_simplex, kind) = step(f, simplex.clone(), &params, &Bounds::none(2).as_vec(), 2);
+        assert_eq!(kind, StepKind::Shrink);
+        // reflection and contraction, then one point per shrunk vertex
+        assert_eq!(evaluations.get(), 2 + 2);
+        for ((_, old), (_, new)) in simplex.iter().zip(new_simplex.iter()) {
+            assert!(new <= old);
+        }
+        // the second vertex got worse when shrunk, so it is kept
+        assert_eq!(
+            new_simplex,
+            vec![
+                (vec![0.0, 0.0], 0.0),
+                (vec![1.0, 0.0], 1.0),
+                (vec![0.0, 0.5], 1.5)
+            ]
+        );
+    }
+
+    #[test]
+    fn converge_with_greedy_shrink() {
+        let params = Params {
+            greedy_shrink: true,
+            ..Default::default()
+        };
+        let initial_simplex = InitialSimplex::axis_aligned(vec![-1.2, 1.0], 0.5);
+        let rosenbrock = |x: &[f64]| {
+            (1.0 - x[0]) * (1.0 - x[0]) + 100.0 * (x[1] - x[0] * x[0]) * (x[1] - x[0] * x[0])
+        };
+        let OptimizationResult { point, .. } = minimize(
+            rosenbrock,
+            evaluate_simplex(rosenbrock, &initial_simplex),
+            params.clone(),
+            Bounds::none(2),
+            5000,
+        );
+        assert_approx_eq!(point[0], 1.0, 1e-3);
+        assert_approx_eq!(point[1], 1.0, 1e-3);
+
+        let square = |x: &[f64]| (x[0] - 2.0) * (x[0] - 2.0) + (x[1] + 1.0) * (x[1] + 1.0);
+        let OptimizationResult { point, .. } = minimize(
+            square,
+            evaluate_simplex(square, &InitialSimplex::axis_aligned(vec![5.0, 5.0], 1.0)),
+            params,
+            Bounds::none(2),
+            1000,
+        );
+        assert_approx_eq!(point[0], 2.0, 1e-3);
+        assert_approx_eq!(point[1], -1.0, 1e-3);
+    }
+
+    #[test]
+    fn break_reflection_cycle() {
+        // the outer values drift down over time, so that the oldest vertex is
+        // always the worst and keeps being reflected around the origin
+        let calls = Cell::new(0);
+        let f = |args: &[f64]| {
+            calls.set(calls.get() + 1);
+            if args[0] == 0.0 && args[1] == 0.0 {
+                0.0
+            } else {
+                1.0 - 1e-9 * calls.get() as f64
+            }
+        };
+        let initial_simplex = InitialSimplex {
+            points: vec![vec![0.0, 0.0], vec![1.0, 0.0], vec![0.0, 1.0]],
+        };
+
+        let result = minimize(
+            f,
+            evaluate_simplex(f, &initial_simplex),
+            Default::default(),
+            Bounds::none(2),
+            100,
+        );
+        assert_approx_eq!(result.final_diameter, 2.0f64.sqrt());
+
+        let params = Params {
+            detect_cycles: Some(10),
+            ..Default::default()
+        };
+        let result = minimize(
+            f,
+            evaluate_simplex(f, &initial_simplex),
+            params,
+            Bounds::none(2),
+            100,
+        );
+        assert!(result.final_diameter < 1e-3);
+        assert_eq!(result.point, vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn only_evaluate_allowed_values() {
+        let seen = RefCell::new(Vec::new());
+        let f = |args: &[f64]| {
+            seen.borrow_mut().push(args[0]);
+            (args[0] - 4.0).powi(2) + (args[1] - 0.3).powi(2)
+        };
+        let params = Params {
+            allowed_values: Some(vec![Some(vec![1.0, 2.0, 5.0]), None]),
+            ..Default::default()
+        };
+        let initial_simplex = evaluate_initial_simplex(
+            f,
+            &InitialSimplex {
+                points: vec![vec![1.5, 1.0], vec![3.0, 0.0], vec![4.5, 1.5]],
+            },
+            &params,
+        );
+        let result = minimize(f, initial_simplex, params, Bounds::none(2), 500);
+        assert!(seen.borrow().iter().all(|x| [1.0, 2.0, 5.0].contains(x)));
+        assert!([1.0, 2.0, 5.0].contains(&result.point[0]));
+        assert_approx_eq!(result.point[1], 0.3);
+    }
+
+    #[test]
+    fn freeze_equality_bounded_dimension() {
+        let f = |args: &[f64]| {
+            (args[0] - 1.0).powi(2) + (args[1] - 2.0).powi(2) + (args[2] - 3.0).powi(2)
+        };
+        let bounds = Bounds {
+            min: vec![-10.0, 0.5, -10.0],
+            max: vec![10.0, 0.5, 10.0],
+        };
+        let initial_simplex = new_simplex(f, vec![0.0, 0.5, 0.0], 1.0);
+        let result = minimize_with_callback(
+            f,
+            initial_simplex,
+            Default::default(),
+            bounds,
+            500,
+            |info| {
+                // a 2-D simplex, with the fixed coordinate pinned
+                assert_eq!(info.simplex.len(), 3);
+                assert!(info.simplex.iter().all(|(x, _)| x[1] == 0.5));
+                Action::Continue
+            },
+        );
+        assert_approx_eq!(result.point[0], 1.0);
+        assert_eq!(result.point[1], 0.5);
+        assert_approx_eq!(result.point[2], 3.0);
+        assert_approx_eq!(result.value, 2.25);
+    }
+
+    #[test]
+    fn replace_vertices_that_collapse_once_pinned() {
+        let f = |args: &[f64]| (args[0] - 3.0).powi(2) + (args[2] + 2.0).powi(2) - 10.0 * args[1];
+        let bounds = Bounds {
+            min: vec![-10.0, 0.0, -10.0],
+            max: vec![10.0, 0.0, 10.0],
+        };
+        // the vertex stepped along the fixed dimension collapses onto the
+        // first one once pinned
+        let initial_simplex =
+            evaluate_simplex(f, &InitialSimplex::axis_aligned(vec![0.0, 0.0, 0.0], 1.0));
+        let result = minimize(
+            f,
+            initial_simplex,
+            Params {
+                f_atol: 1e-10,
+                ..Default::default()
+            },
+            bounds,
+            500,
+        );
+        assert_eq!(result.termination, TerminationReason::Converged);
+        assert_approx_eq!(result.point[0], 3.0, 1e-4);
+        assert_eq!(result.point[1], 0.0);
+        assert_approx_eq!(result.point[2], -2.0, 1e-4);
+        assert_approx_eq!(result.value, 0.0, 1e-8);
+    }
+
+    #[test]
+    fn record_every_evaluation_outside_of_the_search() {
+        let calls = Cell::new(0);
+        let f = |args: &[f64]| {
+            calls.set(calls.get() + 1);
+            (args[0] - 0.3).powi(2) + (args[1] - 2.0).powi(2) + (args[2] + 0.4).powi(2)
+        };
+        // the fixed dimension is reduced, and the vertices outside the bounds
+        // are clamped by the transform
+        let bounds = Bounds {
+            min: vec![-1.0, 0.5, -1.0],
+            max: vec![1.0, 0.5, 1.0],
+        };
+        for boundary_mode in [
+            BoundaryMode::Clamp,
+            BoundaryMode::Auto(5),
+            BoundaryMode::Transform,
+        ] {
+            let initial_simplex =
+                evaluate_simplex(f, &InitialSimplex::axis_aligned(vec![0.8, 0.0, 0.8], 0.5));
+            calls.set(4);
+            let result = minimize(
+                f,
+                initial_simplex,
+                Params {
+                    boundary_mode,
+                    integer_refinement: Some(vec![false, false, true]),
+                    record_evaluations: true,
+                    ..Default::default()
+                },
+                bounds.clone(),
+                200,
+            );
+            assert_eq!(result.evaluations, calls.get());
+            assert_eq!(result.evaluation_history.len(), calls.get() as usize);
+        }
+    }
+
+    #[test]
+    fn report_active_constraints() {
+        let f: &dyn Fn(&[f64]) -> f64 = &(|args| (args[0] + 1.0).powi(2) + (args[1] - 2.0).powi(2));
+        let bounds = Bounds {
+            min: vec![0.0, -5.0],
+            max: vec![5.0, 5.0],
+        };
+        let initial_simplex = evaluate_simplex(
+            f,
+            &InitialSimplex {
+                points: vec![vec![1.0, 1.0], vec![1.5, 1.0], vec![1.0, 1.5]],
+            },
+        );
+        let result = minimize(f, initial_simplex, Default::default(), bounds, 1000);
+        assert_eq!(
+            result.active_constraints(),
+            vec![BoundStatus::Lower, BoundStatus::Interior]
+        );
+    }
+
+    #[test]
+    fn search_only_free_dimensions() {
+        let center = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let f = |args: &[f64]| {
+            args.iter()
+                .zip(center.iter())
+                .map(|(x, c)| (x - c).powi(2))
+                .sum::<f64>()
+        };
+        let bounds = Bounds {
+            min: vec![-10.0, 2.0, -10.0, 4.0, -10.0],
+            max: vec![10.0, 2.0, 10.0, 4.0, 10.0],
+        };
+        let full_simplex = evaluate_simplex(
+            f,
+            &InitialSimplex {
+                points: vec![
+                    vec![0.0, 2.0, 0.0, 4.0, 0.0],
+                    vec![1.0, 2.0, 0.0, 4.0, 0.0],
+                    vec![0.0, 2.0, 1.0, 4.0, 0.0],
+                    vec![0.0, 2.0, 0.0, 4.0, 1.0],
+                    vec![-9.0, 2.0, -9.0, 4.0, -9.0],
+                    vec![9.0, 2.0, -9.0, 4.0, -9.0],
+                ],
+            },
+        );
+        let result =
+            minimize_with_callback(f, full_simplex, Default::default(), bounds, 200, |info| {
+                assert_eq!(info.simplex.len(), 4);
+                assert!(info.simplex.iter().all(|(x, _)| x.len() == 5));
+                Action::Continue
+            });
+
+        // the same search on the 3 free dimensions
+        let g = |args: &[f64]| {
+            (args[0] - 1.0).powi(2) + (args[1] - 3.0).powi(2) + (args[2] - 5.0).powi(2)
+        };
+        let reduced_simplex = evaluate_simplex(
+            g,
+            &InitialSimplex {
+                points: vec![
+                    vec![0.0, 0.0, 0.0],
+                    vec![1.0, 0.0, 0.0],
+                    vec![0.0, 1.0, 0.0],
+                    vec![0.0, 0.0, 1.0],
+                ],
+            },
+        );
+        let expected = minimize(g, reduced_simplex, Default::default(), Bounds::none(3), 200);
+
+        assert_eq!(
+            result.point,
+            vec![
+                expected.point[0],
+                2.0,
+                expected.point[1],
+                4.0,
+                expected.point[2]
+            ]
+        );
+        assert_eq!(result.value, expected.value);
+        assert_eq!(result.iterations, expected.iterations);
+        assert_approx_eq!(result.point[0], 1.0);
+        assert_approx_eq!(result.point[2], 3.0);
+        assert_approx_eq!(result.point[4], 5.0);
+    }
+
+    #[test]
+    fn converge_on_centered_objective() {
+        let f = |args: &[f64]| 1e6 + (args[0] - 1.0).powi(2) + (args[1] - 2.0).powi(2);
+        let simplex = evaluate_simplex(f, &InitialSimplex::axis_aligned(vec![0.0, 0.0], 0.5));
+        let (offset, scale) = affine_estimate(&simplex).unwrap();
+        assert_approx_eq!(offset, 1e6 + 12.5 / 3.0);
+        assert_approx_eq!(scale, 1.75);
+        let params = Params {
+            f_rtol: 1e-6,
+            ..Default::default()
+        };
+
+        let g = |args: &[f64]| (f(args) - offset) / scale;
+        let expected = minimize(
+            g,
+            evaluate_simplex(g, &InitialSimplex::axis_aligned(vec![0.0, 0.0], 0.5)),
+            params.clone(),
+            Bounds::none(2),
+            1000,
+        );
+        let unscaled = minimize(f, simplex.clone(), params.clone(), Bounds::none(2), 1000);
+        let centered = minimize(
+            f,
+            simplex.clone(),
+            Params {
+                affine_objective: true,
+                ..params.clone()
+            },
+            Bounds::none(2),
+            1000,
+        );
+        let result = minimize_scaled(
+            f,
+            |xs: &[Vec<f64>]| evaluate_all(f, xs),
+            simplex,
+            offset,
+            scale,
+            params,
+            Bounds::none(2),
+            1000,
+            |info| {
+                assert!(info.simplex[0].1 >= 1e6);
+                Action::Continue
+            },
+        );
+        // the relative tolerance is meaningless around 1e6, but not for the
+        // values centered around 0
+        assert_eq!(result.iterations, expected.iterations);
+        assert_eq!(result.point, expected.point);
+        assert!(unscaled.iterations < result.iterations);
+        assert_eq!(centered.iterations, result.iterations);
+        assert_eq!(centered.point, result.point);
+        assert_eq!(result.termination, TerminationReason::Converged);
+        assert_approx_eq!(result.point[0], 1.0, 1e-3);
+        assert_approx_eq!(result.point[1], 2.0, 1e-3);
+        assert_approx_eq!(result.value, 1e6);
+        assert!(affine_estimate(&vec![(vec![0.0], 1.0), (vec![1.0], 1.0)]).is_none());
+    }
+
+    #[test]
+    fn converge_on_rescaled_objective() {
+        let g = |args: &[f64]| (args[0] - 1.0).powi(2) + (args[1] - 2.0).powi(2) + 1.0;
+        let f = |args: &[f64]| 1e10 * g(args);
+        let initial_simplex = InitialSimplex {
+            points: vec![vec![0.0, 0.0], vec![0.5, 0.0], vec![0.0, 0.5]],
+        };
+        let params = Params {
+            f_atol: 1e-8,
+            ..Default::default()
+        };
+
+        let expected = minimize(
+            g,
+            evaluate_simplex(g, &initial_simplex),
+            params.clone(),
+            Bounds::none(2),
+            1000,
+        );
+        let unscaled = minimize(
+            f,
+            evaluate_simplex(f, &initial_simplex),
+            params.clone(),
+            Bounds::none(2),
+            1000,
+        );
+        let result = minimize_scaled(
+            f,
+            |xs: &[Vec<f64>]| evaluate_all(f, xs),
+            evaluate_simplex(f, &initial_simplex),
+            0.0,
+            1e10,
+            params,
+            Bounds::none(2),
+            1000,
+            |info| {
+                assert!(info.simplex[0].1 >= 1e10);
+                Action::Continue
+            },
+        );
+        // the rescaled search stops like the one on the original O(1) objective,
+        // while the absolute tolerance is much stricter for the unscaled values
+        assert_eq!(result.iterations, expected.iterations);
+        assert!(unscaled.iterations > result.iterations);
+        assert_eq!(result.termination, TerminationReason::Converged);
+        assert_approx_eq!(result.point[0], 1.0, 1e-3);
+        assert_approx_eq!(result.point[1], 2.0, 1e-3);
+        assert_approx_eq!(result.value / 1e10, 1.0);
+    }
+
+    #[test]
+    fn reflect_second_worst_vertex_when_stuck() {
+        let evaluated = RefCell::new(Vec::new());
+        let f = |args: &[f64]| {
+            evaluated.borrow_mut().push(args.to_vec());
+            args[0] * args[0] + args[1] * args[1]
+        };
+        let simplex = evaluate_simplex(
+            f,
+            &InitialSimplex {
+                points: vec![vec![1.0, 0.0], vec![0.0, 2.0], vec![3.0, 3.0]],
+            },
+        );
+        let params = Params {
+            reflection_target: ReflectionTarget::SecondWorstWhenStuck(0),
+            ..Default::default()
+        };
+
+        // (0, 2) is reflected through the centroid of (1, 0) and (3, 3)
+        evaluated.borrow_mut().clear();
+        minimize(f, simplex, params, Bounds::none(2), 1);
+        assert_eq!(evaluated.borrow()[0], vec![4.0, 1.0]);
+    }
+
+    #[test]
+    fn converge_reflecting_second_worst_vertex() {
+        let f = |args: &[f64]| {
+            (1.0 - args[0]) * (1.0 - args[0])
+                + 100.0 * (args[1] - args[0] * args[0]) * (args[1] - args[0] * args[0])
+        };
+        let initial_simplex = InitialSimplex {
+            points: vec![vec![-1.2, 1.0], vec![-1.0, 1.0], vec![-1.2, 1.2]],
+        };
+        let params = Params {
+            reflection_target: ReflectionTarget::SecondWorstWhenStuck(3),
+            ..Default::default()
+        };
+        let result = minimize(
+            f,
+            evaluate_simplex(f, &initial_simplex),
+            params,
+            Bounds::none(2),
+            2000,
+        );
+        assert_approx_eq!(result.point[0], 1.0, 1e-4);
+        assert_approx_eq!(result.point[1], 1.0, 1e-4);
+
+        let g = |args: &[f64]| (args[0] - 3.0).powi(2) + 2.0 * (args[1] + 1.0).powi(2);
+        let params = Params {
+            reflection_target: ReflectionTarget::SecondWorstWhenStuck(1),
+            ..Default::default()
+        };
+        let result = minimize(
+            g,
+            evaluate_simplex(g, &initial_simplex),
+            params,
+            Bounds::none(2),
+            1000,
+        );
+        assert_approx_eq!(result.point[0], 3.0);
+        assert_approx_eq!(result.point[1], -1.0);
+    }
+
+    #[test]
+    fn stop_before_deadline() {
+        let f = |args: &[f64]| {
+            std::thread::sleep(Duration::from_millis(2));
+            args[0] * args[0] + args[1] * args[1]
+        };
+        let params = Params {
+            max_duration: Some(Duration::from_millis(100)),
+            ..Default::default()
+        };
+        let initial_simplex = new_simplex(f, vec![2.0, 2.0], 0.5);
+        let start = Instant::now();
+        let result = minimize(f, initial_simplex, params, Bounds::none(2), 1000);
+        assert!(start.elapsed() < Duration::from_millis(100));
+        assert_eq!(result.termination, TerminationReason::Deadline);
+        assert!(result.iterations > 0);
+    }
+
+    /// Positively homogeneous around the origin, decreasing only towards
+    /// `(-1, -1)`, but increasing along every point an axis-aligned simplex
+    /// at the origin evaluates, so that simplex only ever shrinks.
+    fn orientation_trap(args: &[f64]) -> f64 {
+        let r = args[0].hypot(args[1]);
+        if r == 0.0 {
+            return 0.0;
+        }
+        let theta = args[1].atan2(args[0]);
+        let pi = std::f64::consts::PI;
+        r * (1.0 - 2.0 * (theta - 1.25 * pi).cos()
+            + 1.5 * (2.0 * theta).sin().powi(2) * (1.0 + (theta - 0.25 * pi).cos()))
+    }
+
+    #[test]
+    fn escape_axis_aligned_stall_with_rotated_restarts() {
+        let calls = Cell::new(0);
+        let f = |x: &[f64]| {
+            calls.set(calls.get() + 1);
+            orientation_trap(x)
+        };
+        let bounds = Bounds {
+            min: vec![-10.0, -10.0],
+            max: vec![10.0, 10.0],
+        };
+        let fixed = crate::minimize_with_restarts(
+            f,
+            vec![0.0, 0.0],
+            0.5,
+            Default::default(),
+            bounds.clone(),
+            100,
+            3,
+            None,
+        );
+        assert_eq!(fixed.point, vec![0.0, 0.0]);
+        assert_eq!(fixed.value, 0.0);
+        assert_eq!(fixed.evaluations, calls.replace(0));
+
+        let rotated = crate::minimize_with_restarts(
+            f,
+            vec![0.0, 0.0],
+            0.5,
+            Default::default(),
+            bounds,
+            100,
+            3,
+            Some(42),
+        );
+        assert!(rotated.value < -10.0);
+        assert_eq!(rotated.evaluations, calls.get());
+    }
+
+    #[test]
+    fn converge_in_log_space() {
+        // values go from about 1e6 to 1e-3
+        let f = |x: &[f64]| 1e-3 + 1e6 * (x[0] * x[0] + x[1] * x[1]);
+        let initial_simplex = InitialSimplex::axis_aligned(vec![1.0, 1.0], 0.5);
+        let raw_params = Params {
+            f_atol: 1e-2,
+            ..Default::default()
+        };
+        let log_params = Params {
+            convergence_transform: ConvergenceTransform::Log,
+            ..raw_params.clone()
+        };
+        let raw = minimize(
+            f,
+            evaluate_simplex(f, &initial_simplex),
+            raw_params,
+            Bounds::none(2),
+            10000,
+        );
+        let log = minimize(
+            f,
+            evaluate_simplex(f, &initial_simplex),
+            log_params,
+            Bounds::none(2),
+            10000,
+        );
+        assert_eq!(log.termination, TerminationReason::Converged);
+        // the values in the final simplex are within 1% of each other
+        assert!(log.value_spread <= 1.02e-2 * log.value);
+        assert!(log.value < 1.02e-3);
+        // an absolute tolerance of 1e-2 is loose for values around 1e-3
+        assert_eq!(raw.termination, TerminationReason::Converged);
+        assert!(raw.value_spread > 1.02e-2 * raw.value);
+        assert!(raw.iterations < log.iterations);
+    }
+
+    #[test]
+    fn extend_while_improving() {
+        let f = |x: &[f64]| (x[0] - 0.3) * (x[0] - 0.3) + 2.0 * (x[1] + 0.7) * (x[1] + 0.7);
+        let initial_simplex = InitialSimplex::axis_aligned(vec![3.0, 2.5], 1.0);
+        let params = Params {
+            f_atol: 1e-10,
+            extend_if_improving: Some((1e-6, 1000)),
+            ..Default::default()
+        };
+        let fixed = minimize(
+            f,
+            evaluate_simplex(f, &initial_simplex),
+            Params {
+                extend_if_improving: None,
+                ..params.clone()
+            },
+            Bounds::none(2),
+            20,
+        );
+        assert_eq!(fixed.termination, TerminationReason::MaxIterations);
+        let extended = minimize(
+            f,
+            evaluate_simplex(f, &initial_simplex),
+            params,
+            Bounds::none(2),
+            20,
+        );
+        assert_eq!(extended.termination, TerminationReason::Converged);
+        assert!(extended.iterations > 20);
+        assert!(extended.value < 1e-8);
+    }
+
+    #[test]
+    fn do_not_extend_stalled_run() {
+        let f = |_: &[f64]| 1.0;
+        let params = Params {
+            extend_if_improving: Some((1e-6, 1000)),
+            ..Default::default()
+        };
+        let initial_simplex = new_simplex(f, vec![3.0, 3.0], 1.0);
+        let result = minimize(f, initial_simplex, params, Bounds::none(2), 20);
+        assert_eq!(result.termination, TerminationReason::MaxIterations);
+        assert_eq!(result.iterations, 20);
+    }
+
+    #[test]
+    fn do_not_extend_run_with_infinite_values() {
+        // the mean value is infinite, so the rate would be NaN
+        let f = |x: &[f64]| if x[0] < 10.0 { f64::INFINITY } else { 1.0 };
+        let params = Params {
+            extend_if_improving: Some((1e-6, 1000)),
+            ..Default::default()
+        };
+        let initial_simplex =
+            evaluate_simplex(f, &InitialSimplex::axis_aligned(vec![3.0, 3.0], 1.0));
+        let result = minimize(f, initial_simplex, params, Bounds::none(2), 20);
+        assert_eq!(result.termination, TerminationReason::MaxIterations);
+        assert_eq!(result.iterations, 20);
+    }
+
+    #[test]
+    fn only_evaluate_projected_points() {
+        let evaluated = RefCell::new(Vec::new());
+        let f = |x: &[f64]| {
+            evaluated.borrow_mut().push(x.to_vec());
+            (x[0] - 0.8) * (x[0] - 0.8) + (x[1] - 0.5) * (x[1] - 0.5)
+        };
+        let projection = |x: &[f64]| {
+            let offset = (x[0] + x[1] - 1.0) / 2.0;
+            vec![x[0] - offset, x[1] - offset]
+        };
+        let bounds = Bounds {
+            min: vec![0.0, 0.0],
+            max: vec![1.0, 1.0],
+        };
+        let result = crate::minimize_with_projection(
+            f,
+            projection,
+            vec![0.2, 0.2],
+            0.5,
+            Params {
+                f_atol: 1e-12,
+                ..Default::default()
+            },
+            bounds,
+            1000,
+        );
+        assert!(evaluated.borrow().iter().all(|x| {
+            (x[0] + x[1] - 1.0).abs() < 1e-9 && x.iter().all(|xi| (0.0..=1.0).contains(xi))
+        }));
+        // the minimum of (x - 0.8)^2 + (0.5 - x)^2
+        assert!((result.point[0] - 0.65).abs() < 1e-5);
+        assert!((result.point[1] - 0.35).abs() < 1e-5);
+    }
+
+    #[test]
+    fn stop_at_min_simplex_size() {
+        // below a scale of about 1e-3, the values are only noise
+        let f = |x: &[f64]| ((x[0] * x[0] + x[1] * x[1]) * 1e6).round() / 1e6;
+        let initial_simplex = InitialSimplex::axis_aligned(vec![1.0, 2.0], 0.5);
+        let grinding = minimize(
+            f,
+            evaluate_simplex(f, &initial_simplex),
+            Default::default(),
+            Bounds::none(2),
+            1000,
+        );
+        assert_eq!(grinding.termination, TerminationReason::MaxIterations);
+        let result = minimize(
+            f,
+            evaluate_simplex(f, &initial_simplex),
+            Params {
+                min_simplex_size: Some(1e-3),
+                ..Default::default()
+            },
+            Bounds::none(2),
+            1000,
+        );
+        assert_eq!(result.termination, TerminationReason::SimplexTooSmall);
+        assert!(result.final_diameter < 1e-3);
+        assert!(result.final_diameter > 1e-4);
+        assert!(result.iterations < 100);
+    }
+
+    #[test]
+    fn restart_stagnating_simplex() {
+        // McKinnon's function, on which the simplex below keeps contracting
+        // towards the non-stationary origin, while the minimum is f(0, -0.5) = -0.25
+        let f: &dyn Fn(&[f64]) -> f64 = &(|args| {
+            let x = if args[0] <= 0.0 {
+                360.0 * args[0] * args[0]
+            } else {
+                6.0 * args[0] * args[0]
+            };
+            x + args[1] + args[1] * args[1]
+        });
+        let initial_simplex = evaluate_simplex(
+            f,
+            &InitialSimplex {
+                points: vec![
+                    vec![0.0, 0.0],
+                    vec![1.0, 1.0],
+                    vec![(1.0 + 33f64.sqrt()) / 8.0, (1.0 - 33f64.sqrt()) / 8.0],
+                ],
+            },
+        );
+        let params = Params {
+            axis_aligned_simplex: true,
+            ..Default::default()
+        };
+        let stuck = minimize(
+            f,
+            initial_simplex.clone(),
+            params.clone(),
+            Bounds::none(2),
+            200,
+        );
+        assert_eq!(stuck.restarts, 0);
+        assert!(stuck.value > -0.01);
+        let restarted = minimize(
+            f,
+            initial_simplex,
+            Params {
+                restart_after: Some(10),
+                ..params
+            },
+            Bounds::none(2),
+            200,
+        );
+        assert!(restarted.restarts > 0);
+        assert_approx_eq!(restarted.value, -0.25);
+        assert_approx_eq!(restarted.point[1], -0.5);
+    }
+
+    #[test]
+    fn stop_on_plateau() {
+        // flat bottom of radius 1 around the origin
+        let f: &dyn Fn(&[f64]) -> f64 = &(|args| {
+            let r = (args[0] * args[0] + args[1] * args[1]).sqrt();
+            (r - 1.0).max(0.0).powi(2)
+        });
+        let params = Params {
+            stop_on_plateau: Some((5, 1e-12)),
+            ..Default::default()
+        };
+        let initial_simplex = new_simplex(f, vec![3.0, 3.0], 0.5);
+        let mut first_flat_iteration = None;
+        let mut last_iteration = 0;
+        let result =
+            minimize_with_callback(f, initial_simplex, params, Bounds::none(2), 500, |info| {
+                if first_flat_iteration.is_none() && info.simplex.iter().all(|(_, fx)| *fx == 0.0) {
+                    first_flat_iteration = Some(info.iteration);
+                }
+                last_iteration = info.iteration;
+                Action::Continue
+            });
+        assert_eq!(result.value, 0.0);
+        assert!(last_iteration <= first_flat_iteration.unwrap() + 10);
+    }
+
+    #[test]
+    fn only_count_distinct_points_against_budget() {
+        let f = |x: &[f64]| (x[0] - 1.0) * (x[0] - 1.0) + (x[1] + 2.0) * (x[1] + 2.0);
+        // snapping to a coarse grid makes the simplex revisit the same points
+        let grid: Vec<f64> = (-20..=20).map(|i| i as f64 * 0.5).collect();
+        let uncached = Params {
+            max_evaluations: Some(60),
+            allowed_values: Some(vec![Some(grid.clone()), Some(grid)]),
+            ..Default::default()
+        };
+        let cached = Params {
+            exact_cache: true,
+            ..uncached.clone()
+        };
+        let initial_simplex = InitialSimplex::axis_aligned(vec![4.0, 3.0], 1.0);
+        let every_lookup = minimize(
+            f,
+            evaluate_simplex(f, &initial_simplex),
+            uncached,
+            Bounds::none(2),
+            1000,
+        );
+        let distinct = minimize(
+            f,
+            evaluate_simplex(f, &initial_simplex),
+            cached,
+            Bounds::none(2),
+            1000,
+        );
+        assert_eq!(every_lookup.termination, TerminationReason::MaxEvaluations);
+        assert!(distinct.cache_hits > distinct.cache_misses);
+        assert!(distinct.evaluations + distinct.cache_hits > 60);
+        assert!(distinct.iterations > every_lookup.iterations);
+    }
+
+    #[test]
+    fn count_resampled_points_once_against_distinct_budget() {
+        let points = RefCell::new(Vec::new());
+        let f = |x: &[f64]| {
+            let mut points = points.borrow_mut();
+            if !points.contains(&x.to_vec()) {
+                points.push(x.to_vec());
+            }
+            (x[0] - 1.0) * (x[0] - 1.0) + (x[1] + 2.0) * (x[1] + 2.0)
+        };
+        let grid: Vec<f64> = (-20..=20).map(|i| i as f64 * 0.5).collect();
+        let params = Params {
+            exact_cache: true,
+            resample: Some((3, Aggregate::Mean)),
+            allowed_values: Some(vec![Some(grid.clone()), Some(grid)]),
+            ..Default::default()
+        };
+        let run = |params: Params| {
+            points.borrow_mut().clear();
+            let initial_simplex = evaluate_initial_simplex(
+                f,
+                &InitialSimplex::axis_aligned(vec![4.0, 3.0], 1.0),
+                &params,
+            );
+            minimize(f, initial_simplex, params, Bounds::none(2), 1000)
+        };
+        let every_sample = run(Params {
+            max_evaluations: Some(30),
+            ..params.clone()
+        });
+        let distinct = run(Params {
+            max_distinct_evals: Some(30),
+            ..params
+        });
+        assert_eq!(every_sample.termination, TerminationReason::MaxEvaluations);
+        assert!(points.borrow().len() <= 30);
+        assert!(distinct.evaluations > 30);
+        assert!(distinct.iterations > every_sample.iterations);
+    }
+
+    #[test]
+    fn do_not_reevaluate_cached_points() {
+        let evaluated = RefCell::new(Vec::new());
+        let f = |args: &[f64]| {
+            evaluated.borrow_mut().push(args.to_vec());
+            args[0] * args[0] + args[1] * args[1]
+        };
+        let params = Params {
+            exact_cache: true,
+            ..Default::default()
+        };
+        let initial_simplex =
+            evaluate_simplex(f, &InitialSimplex::axis_aligned(vec![2.0, 2.0], 0.5));
+        let result = minimize(f, initial_simplex, params, Bounds::none(2), 200);
+        let evaluated = evaluated.borrow();
+        for (i, x) in evaluated.iter().enumerate() {
+            assert!(!evaluated[i + 1..].contains(x));
+        }
+        assert_eq!(evaluated.iter().filter(|&x| *x == result.point).count(), 1);
+        assert!(result.cache_hits > 0);
+        assert_eq!(result.cache_misses as usize, evaluated.len() - 3);
+        assert_eq!(result.evaluations as usize, evaluated.len());
+    }
+
+    #[test]
+    fn share_cached_values_within_tolerance() {
+        let evaluated = RefCell::new(Vec::new());
+        let sphere = |args: &[f64]| args[0] * args[0] + args[1] * args[1];
+        let f = |args: &[f64]| {
+            evaluated.borrow_mut().push(args.to_vec());
+            sphere(args)
+        };
+        let cell =
+            |x: &[f64]| -> Vec<i64> { x.iter().map(|c| (c / 0.01).round() as i64).collect() };
+        let initial_simplex = InitialSimplex::axis_aligned(vec![2.0, 2.0], 0.5);
+        let result = minimize(
+            f,
+            evaluate_simplex(f, &initial_simplex),
+            Params {
+                cache_tolerance: Some(0.01),
+                ..Default::default()
+            },
+            Bounds::none(2),
+            200,
+        );
+        {
+            let evaluated = evaluated.borrow();
+            // the result's point is evaluated again at the end if its value
+            // came from another point of its cell
+            let settled = evaluated.len() - 3 - result.cache_misses as usize;
+            assert!(settled <= 1);
+            let cells: Vec<Vec<i64>> = evaluated[..evaluated.len() - settled]
+                .iter()
+                .map(|x| cell(x))
+                .collect();
+            for (i, cell) in cells.iter().enumerate() {
+                assert!(!cells[i + 1..].contains(cell));
+            }
+            assert_eq!(result.evaluations as usize, evaluated.len());
+            assert_eq!(result.value, sphere(&result.point));
+            assert!(result.value < 1e-3);
+        }
+
+        // a coarser tolerance serves more evaluations than the exact cache
+        let exact = minimize(
+            f,
+            evaluate_simplex(f, &initial_simplex),
+            Params {
+                exact_cache: true,
+                ..Default::default()
+            },
+            Bounds::none(2),
+            200,
+        );
+        assert!(result.cache_hits > exact.cache_hits);
+    }
+
+    #[test]
+    #[should_panic(expected = "The cache tolerance must be finite and positive")]
+    fn reject_zero_cache_tolerance() {
+        let f = |args: &[f64]| args[0] * args[0];
+        let initial_simplex = InitialSimplex::axis_aligned(vec![1.0], 0.5);
+        minimize(
+            f,
+            evaluate_simplex(f, &initial_simplex),
+            Params {
+                cache_tolerance: Some(0.0),
+                ..Default::default()
+            },
+            Bounds::none(1),
+            10,
+        );
+    }
+
+    #[test]
+    fn converge_more_stably_when_resampling_noisy_objective() {
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        // the distance to the true minimum at (1, -2), over several noise
+        // sequences
+        let mean_error = |resample| {
+            (0..8)
+                .map(|seed| {
+                    let rng = RefCell::new(StdRng::seed_from_u64(seed));
+                    let f = |args: &[f64]| {
+                        (args[0] - 1.0).powi(2)
+                            + (args[1] + 2.0).powi(2)
+                            + rng.borrow_mut().gen_range(-0.5, 0.5)
+                    };
+                    let params = Params {
+                        resample,
+                        ..Default::default()
+                    };
+                    let initial_simplex = evaluate_initial_simplex(
+                        f,
+                        &InitialSimplex::axis_aligned(vec![5.0, 5.0], 1.0),
+                        &params,
+                    );
+                    let result = minimize(f, initial_simplex, params, Bounds::none(2), 200);
+                    if let Some((k, _)) = resample {
+                        assert_eq!(result.evaluations % k, 0);
+                    }
+                    distance(&result.point, &[1.0, -2.0])
+                })
+                .sum::<f64>()
+                / 8.0
+        };
+        let single = mean_error(None);
+        let mean = mean_error(Some((25, Aggregate::Mean)));
+        let median = mean_error(Some((25, Aggregate::Median)));
+        assert!(mean < single / 2.0, "{} >= {} / 2", mean, single);
+        // the median is less efficient than the mean under uniform noise
+        assert!(median < 0.75 * single, "{} >= 0.75 * {}", median, single);
+    }
+
+    #[test]
+    fn track_diameter_collapse() {
+        let f: &dyn Fn(&[f64]) -> f64 = &(|args| args[0] * args[0] + args[1] * args[1]);
+        let params = Params {
+            track_diameter: true,
+            ..Default::default()
+        };
+        let initial_simplex = new_simplex(f, vec![0.5, 0.5], 1.0);
+        let result = minimize(f, initial_simplex, params, Bounds::none(2), 200);
+        assert!(result.half_life_iteration.unwrap() < 20);
+        assert!(result.diameter_ratio.unwrap() < 1e-6);
+    }
+
+    #[test]
+    fn detect_non_deterministic_objective() {
+        let calls = Cell::new(0);
+        let f = |args: &[f64]| {
+            calls.set(calls.get() + 1);
+            args[0] * args[0] + 1e-9 * calls.get() as f64
+        };
+        assert!(matches!(
+            check_determinism(f, &[1.0], 1e-12),
+            Err(NelderMeadError::NonDeterministicObjective { .. })
+        ));
+    }
+
+    #[test]
+    fn accept_deterministic_objective() {
+        let f = |args: &[f64]| args[0] * args[0];
+        assert_eq!(check_determinism(f, &[1.0], 0.0), Ok(()));
+    }
+
+    #[test]
+    fn summarize_result() {
+        let f: &dyn Fn(&[f64]) -> f64 = &(|args| args[0] * args[0] + args[1] * args[1]);
+        let params = Params {
+            f_atol: 1e-10,
+            ..Default::default()
+        };
+        let initial_simplex = new_simplex(f, vec![2.0, 2.0], 0.5);
+        let result = minimize(f, initial_simplex, params, Bounds::none(2), 500);
+        let summary = result.summary();
+        assert_eq!(summary.point, result.point);
+        assert_eq!(summary.value, result.value);
+        assert_eq!(summary.iterations, result.iterations);
+        assert_eq!(summary.evaluations, result.evaluations);
+        assert_eq!(summary.termination, TerminationReason::Converged);
+        assert_eq!(summary.termination, result.termination);
+        assert_eq!(summary.final_diameter, result.final_diameter);
+        assert_eq!(summary.value_spread, result.value_spread);
+        assert!(summary.value_spread <= 1e-10);
+        assert!(summary.feasible);
+        assert_eq!(summary.feasible, result.feasible);
+        assert!(summary.to_string().contains("termination: converged"));
+    }
+
+    #[test]
+    fn refine_integer_dimensions() {
+        // the continuous minimum (1.45, 2.6) rounds to (1, 3), but (1, 2) is better
+        let f: &dyn Fn(&[f64]) -> f64 = &(|args| {
+            100.0 * (args[1] - 2.0 * args[0] + 0.3) * (args[1] - 2.0 * args[0] + 0.3)
+                + (args[0] - 1.45) * (args[0] - 1.45)
+        });
+        let params = Params {
+            integer_refinement: Some(vec![true, true]),
+            ..Default::default()
+        };
+        let initial_simplex = new_simplex(f, vec![0.0, 0.0], 0.5);
+        let result = minimize(f, initial_simplex, params, Bounds::none(2), 1000);
+        assert_eq!(result.point, vec![1.0, 2.0]);
+        assert!(result.value < f(&[1.0, 3.0]));
+    }
+
+    #[test]
+    fn refine_integers_within_bounds() {
+        let f: &dyn Fn(&[f64]) -> f64 = &(|args| args[0] * args[0] + args[1]);
+        let bounds_vec = vec![(0.0, 10.0), (5.0, 10.0)];
+        let (point, value) = refine_integers(f, &[0.2, 5.3], &[true, false], &bounds_vec);
+        assert_eq!(point, vec![0.0, 5.3]);
+        assert_eq!(value, 5.3);
+    }
+
+    #[test]
+    fn only_round_integers_beyond_search_dimensions() {
+        let n = MAX_GRID_SEARCH_DIMENSIONS + 1;
+        let calls = std::cell::Cell::new(0);
+        let f = |args: &[f64]| {
+            calls.set(calls.get() + 1);
+            args.iter().map(|x| (x - 0.2) * (x - 0.2)).sum::<f64>()
+        };
+        let params = Params {
+            integer_refinement: Some(vec![true; n]),
+            ..Default::default()
+        };
+        let initial_simplex = new_simplex(f, vec![1.0; n], 0.5);
+        let result = minimize(f, initial_simplex, params, Bounds::none(n), 1000);
+        assert_eq!(result.point, vec![0.0; n]);
+        assert_eq!(result.evaluations, calls.get());
+        assert!(calls.get() < 3u32.pow(n as u32));
+    }
+
+    #[test]
+    fn snap_result_to_best_grid_point() {
+        // the continuous minimum (1.45, 2.6) rounds to (1.5, 2.5) on a grid of
+        // quarters, but (1.5, 2.75) is better
+        let f: &dyn Fn(&[f64]) -> f64 = &(|args| {
+            100.0 * (args[1] - 2.0 * args[0] + 0.3) * (args[1] - 2.0 * args[0] + 0.3)
+                + (args[0] - 1.45) * (args[0] - 1.45)
+        });
+        let params = Params {
+            snap_result_to_grid: Some(vec![0.25, 0.25]),
+            ..Default::default()
+        };
+        let initial_simplex =
+            evaluate_simplex(f, &InitialSimplex::axis_aligned(vec![0.0, 0.0], 0.5));
+        let result = minimize(f, initial_simplex, params, Bounds::none(2), 1000);
+        assert_eq!(result.point, vec![1.5, 2.75]);
+        assert!(result.value < f(&[1.5, 2.5]));
+        // no other grid point around the optimum is better
+        for i in 0..=16 {
+            for j in 0..=16 {
+                let candidate = [0.5 + 0.25 * i as f64, 1.5 + 0.25 * j as f64];
+                assert!(result.value <= f(&candidate));
+            }
+        }
+    }
+
+    #[test]
+    fn only_round_to_grid_beyond_search_dimensions() {
+        let n = MAX_GRID_SEARCH_DIMENSIONS + 1;
+        let calls = std::cell::Cell::new(0);
+        let f = |args: &[f64]| {
+            calls.set(calls.get() + 1);
+            args.iter().sum::<f64>()
+        };
+        let spacing = vec![0.5; n];
+        let bounds_vec = vec![(f64::NEG_INFINITY, f64::INFINITY); n];
+        let (point, _) = refine_on_grid(f, &vec![0.3; n], &spacing, &bounds_vec);
+        assert_eq!(point, vec![0.5; n]);
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn report_monotonic_progress() {
+        let f: &dyn Fn(&[f64]) -> f64 = &(|args| args[0] * args[0] + args[1] * args[1]);
+        let params = Params {
+            f_atol: 1e-8,
+            ..Default::default()
+        };
+        let initial_simplex = new_simplex(f, vec![2.0, 2.0], 0.5);
+        let mut progresses = Vec::new();
+        let result =
+            minimize_with_callback(f, initial_simplex, params, Bounds::none(2), 1000, |info| {
+                progresses.push(info.progress);
+                Action::Continue
+            });
+        assert_eq!(result.termination, TerminationReason::Converged);
+        assert!(progresses.windows(2).all(|p| p[0] <= p[1]));
+        assert!(progresses[0] < 0.5);
+        assert_approx_eq!(progresses[progresses.len() - 1], 1.0);
+    }
+
+    #[test]
+    fn report_iteration_progress_without_tolerances() {
+        let f: &dyn Fn(&[f64]) -> f64 = &(|args| args[0] * args[0] + args[1] * args[1]);
+        let initial_simplex = new_simplex(f, vec![2.0, 2.0], 0.5);
+        let mut progresses = Vec::new();
+        minimize_with_callback(
+            f,
+            initial_simplex,
+            Default::default(),
+            Bounds::none(2),
+            10,
+            |info| {
+                progresses.push(info.progress);
+                Action::Continue
+            },
+        );
+        assert_eq!(progresses[0], 0.1);
+        assert_eq!(progresses[9], 1.0);
+    }
 }
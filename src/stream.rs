@@ -0,0 +1,140 @@
+//! Asynchronous access to the progress of an optimization, available with
+//! the `stream` feature.
+
+extern crate futures;
+use futures::task::{Context, Poll};
+use futures::Stream;
+use std::pin::Pin;
+
+use crate::bounds::*;
+use crate::params::*;
+use crate::simplex::*;
+
+/// The state of the optimization after an iteration, as yielded by
+/// `MinimizeStream`.
+pub struct IterationSnapshot {
+    /// Index of the iteration that just finished, starting at 0.
+    pub iteration: u32,
+    /// The current simplex, sorted from best to worst vertex.
+    pub simplex: Simplex,
+}
+
+/// A `Stream` that performs one Nelder-Mead iteration every time it is
+/// polled, yielding the resulting simplex.
+///
+/// The stream ends after `max_iter` iterations, or once the spread of values
+/// falls within the tolerances of `Params`.
+pub struct MinimizeStream<F: Fn(&[f64]) -> f64> {
+    f: F,
+    simplex: Simplex,
+    params: Params,
+    bounds_vec: Vec<(f64, f64)>,
+    iteration: u32,
+    max_iter: u32,
+    converged: bool,
+}
+
+/// Minimizes a function `f`,
+/// starting with a simplex of size `initial_simplex_size` centered on
+/// `initial_point`, as a stream of per-iteration snapshots.
+///
+/// The search space is bounded by a `Bounds` definition.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::bounds::*;
+/// use nelder_mead::stream::*;
+///
+/// use futures::executor::block_on;
+/// use futures::StreamExt;
+///
+/// // minimize x^2 + y^2, printing every best value
+/// let mut stream = minimize_stream(
+///    |args| args[0]*args[0] + args[1]*args[1],
+///    vec![5.0,5.0],
+///    1.0,
+///    Default::default(),
+///    Bounds::none(2),
+///    100);
+///
+/// block_on(async {
+///     while let Some(snapshot) = stream.next().await {
+///         println!("{}: {}", snapshot.iteration, snapshot.simplex[0].1);
+///     }
+/// });
+/// ```
+pub fn minimize_stream<F: Fn(&[f64]) -> f64>(
+    f: F,
+    initial_point: Vec<f64>,
+    initial_simplex_size: f64,
+    params: Params,
+    bounds: Bounds,
+    max_iter: u32,
+) -> MinimizeStream<F> {
+    let (simplex, _) =
+        crate::entry_points::simplex_around(&f, initial_point, initial_simplex_size, &params);
+    MinimizeStream {
+        f,
+        simplex,
+        params,
+        bounds_vec: bounds.as_vec(),
+        iteration: 0,
+        max_iter,
+        converged: false,
+    }
+}
+
+impl<F: Fn(&[f64]) -> f64 + Unpin> Stream for MinimizeStream<F> {
+    type Item = IterationSnapshot;
+
+    fn poll_next(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Option<IterationSnapshot>> {
+        let state = self.get_mut();
+        if state.converged || state.iteration >= state.max_iter {
+            return Poll::Ready(None);
+        }
+        let n = state.simplex.len() - 1;
+        let simplex = std::mem::take(&mut state.simplex);
+        let (simplex, _) = step(&state.f, simplex, &state.params, &state.bounds_vec, n);
+        state.converged = converged(&simplex, &state.params, 1.0);
+        state.simplex = simplex.clone();
+        let iteration = state.iteration;
+        state.iteration += 1;
+        Poll::Ready(Some(IterationSnapshot { iteration, simplex }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate assert_approx_eq;
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+    use futures::executor::block_on;
+    use futures::StreamExt;
+
+    #[test]
+    fn collect_converging_snapshots() {
+        let stream = minimize_stream(
+            |args| (args[0] - 1.0).powi(2) + (args[1] + 2.0).powi(2),
+            vec![5.0, 5.0],
+            1.0,
+            Default::default(),
+            Bounds::none(2),
+            500,
+        );
+        let snapshots: Vec<IterationSnapshot> = block_on(stream.collect());
+        assert_eq!(snapshots.len(), 500);
+        assert!(snapshots
+            .iter()
+            .enumerate()
+            .all(|(i, snapshot)| snapshot.iteration == i as u32));
+        // the best value never increases
+        assert!(snapshots
+            .windows(2)
+            .all(|pair| pair[1].simplex[0].1 <= pair[0].simplex[0].1));
+        let (point, value) = &snapshots[499].simplex[0];
+        assert_approx_eq!(point[0], 1.0);
+        assert_approx_eq!(point[1], -2.0);
+        assert_approx_eq!(value, 0.0);
+    }
+}
@@ -0,0 +1,136 @@
+use crate::algebra::*;
+use crate::bounds::*;
+
+/// Maps points between the bounded search space and an unbounded space,
+/// in which every point maps back within the bounds.
+///
+/// Dimensions bounded on both sides go through a `tanh`, dimensions bounded
+/// on one side through `sqrt(u^2 + 1)`, and unbounded dimensions are kept
+/// as they are. Fixed dimensions, with equal minimum and maximum bounds,
+/// always map to their value.
+pub struct Transform<T = f64> {
+    bounds_vec: Vec<(T, T)>,
+}
+
+impl<T: Float> Transform<T> {
+    pub fn new(bounds: &Bounds<T>) -> Transform<T> {
+        Transform {
+            bounds_vec: bounds.as_vec(),
+        }
+    }
+
+    /// The bounds of the unbounded space, which only pin the fixed
+    /// dimensions to 0.
+    pub fn unbounded(&self) -> Bounds<T> {
+        let (min, max) = self
+            .bounds_vec
+            .iter()
+            .map(|(min, max)| {
+                if min == max {
+                    (T::zero(), T::zero())
+                } else {
+                    (T::neg_infinity(), T::infinity())
+                }
+            })
+            .unzip();
+        Bounds { min, max }
+    }
+
+    /// Maps a point of the unbounded space to the bounded one.
+    pub fn to_bounded(&self, u: &[T]) -> Vec<T> {
+        let (one, two) = (T::one(), T::from_f64(2.0));
+        u.iter()
+            .zip(self.bounds_vec.iter())
+            .map(|(&u, &(min, max))| {
+                let x = match (min.is_finite(), max.is_finite()) {
+                    (true, true) => min + (max - min) * (u.tanh() + one) / two,
+                    (true, false) => min - one + (u * u + one).sqrt(),
+                    (false, true) => max + one - (u * u + one).sqrt(),
+                    (false, false) => u,
+                };
+                // rounding must not step outside the bounds
+                clamp_coordinate(x, (min, max))
+            })
+            .collect()
+    }
+
+    /// Maps a point of the bounded space to the unbounded one, clamping it
+    /// to the bounds first.
+    ///
+    /// Points on a finite bound of a dimension bounded on both sides map to
+    /// large but finite coordinates.
+    pub fn to_unbounded(&self, x: &[T]) -> Vec<T> {
+        let (one, two) = (T::one(), T::from_f64(2.0));
+        x.iter()
+            .zip(self.bounds_vec.iter())
+            .map(|(x, &(min, max))| {
+                let x = clamp_coordinate(*x, (min, max));
+                match (min.is_finite(), max.is_finite()) {
+                    _ if min == max => T::zero(),
+                    (true, true) => {
+                        let t = two * (x - min) / (max - min) - one;
+                        t.max(-one + T::epsilon()).min(one - T::epsilon()).atanh()
+                    }
+                    (true, false) => ((x - min + one).powi(2) - one).sqrt(),
+                    (false, true) => ((max - x + one).powi(2) - one).sqrt(),
+                    (false, false) => x,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transform() -> Transform {
+        Transform::new(&Bounds {
+            min: vec![-1.0, 2.0, f64::NEG_INFINITY, f64::NEG_INFINITY, 4.0],
+            max: vec![3.0, f64::INFINITY, 5.0, f64::INFINITY, 4.0],
+        })
+    }
+
+    #[test]
+    fn round_trip_interior_points() {
+        let transform = transform();
+        let x = [0.5, 10.0, -7.0, 123.0, 4.0];
+        let round_trip = transform.to_bounded(&transform.to_unbounded(&x));
+        for (a, b) in round_trip.iter().zip(x.iter()) {
+            assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+        }
+    }
+
+    #[test]
+    fn stay_within_bounds() {
+        let transform = transform();
+        for &u in &[-1e300, -50.0, -1.0, 0.0, 1.0, 50.0, 1e300] {
+            let x = transform.to_bounded(&[u; 5]);
+            assert!(-1.0 <= x[0] && x[0] <= 3.0);
+            assert!(2.0 <= x[1]);
+            assert!(x[2] <= 5.0);
+            assert_eq!(x[3], u);
+            assert_eq!(x[4], 4.0);
+        }
+        // the bounds themselves are reachable
+        assert_eq!(transform.to_bounded(&[-50.0; 5])[0], -1.0);
+        assert_eq!(transform.to_bounded(&[50.0; 5])[0], 3.0);
+        assert_eq!(transform.to_bounded(&[0.0; 5])[1], 2.0);
+        assert_eq!(transform.to_bounded(&[0.0; 5])[2], 5.0);
+    }
+
+    #[test]
+    fn map_bounds_to_finite_coordinates() {
+        let transform = transform();
+        let u = transform.to_unbounded(&[-1.0, 2.0, 5.0, 0.0, 4.0]);
+        assert!(u.iter().all(|u| u.is_finite()));
+        assert_eq!(u[4], 0.0);
+        assert_eq!(transform.to_bounded(&u)[1], 2.0);
+        assert_eq!(transform.to_bounded(&u)[2], 5.0);
+        assert_eq!(
+            transform.unbounded().as_vec()[..2],
+            [(f64::NEG_INFINITY, f64::INFINITY); 2]
+        );
+        assert_eq!(transform.unbounded().as_vec()[4], (0.0, 0.0));
+    }
+}
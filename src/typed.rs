@@ -0,0 +1,159 @@
+//! Optimization over user-defined point types, which are converted to and
+//! from coordinate vectors through the `Vectorizable` trait.
+
+use crate::bounds::*;
+use crate::params::*;
+
+/// A point type with a fixed number of real coordinates.
+///
+/// `from_vec(&x.to_vec())` must rebuild `x`, and `to_vec` must always
+/// return `dim()` coordinates.
+pub trait Vectorizable: Sized {
+    /// Number of coordinates of every point.
+    fn dim() -> usize;
+    /// The coordinates of the point.
+    fn to_vec(&self) -> Vec<f64>;
+    /// Builds a point from `dim()` coordinates.
+    fn from_vec(coordinates: &[f64]) -> Self;
+}
+
+/// Minimizes a function `f` over points of type `T`,
+/// starting with a simplex of size `initial_simplex_size` centered on
+/// `initial_point`.
+///
+/// The search space is bounded by a `Bounds` definition, whose dimensions
+/// follow the order of `Vectorizable::to_vec`.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::bounds::*;
+/// use nelder_mead::typed::*;
+///
+/// use assert_approx_eq::assert_approx_eq;
+///
+/// struct Line {
+///     slope: f64,
+///     intercept: f64,
+/// }
+///
+/// impl Vectorizable for Line {
+///     fn dim() -> usize {
+///         2
+///     }
+///     fn to_vec(&self) -> Vec<f64> {
+///         vec![self.slope, self.intercept]
+///     }
+///     fn from_vec(coordinates: &[f64]) -> Line {
+///         Line { slope: coordinates[0], intercept: coordinates[1] }
+///     }
+/// }
+///
+/// // fit y = 2x + 1
+/// let samples = [(0.0, 1.0), (1.0, 3.0), (2.0, 5.0)];
+/// let (line, error) = minimize_typed(
+///    |line: &Line| samples
+///        .iter()
+///        .map(|(x, y)| (line.slope * x + line.intercept - y).powi(2))
+///        .sum(),
+///    Line { slope: 0.0, intercept: 0.0 },
+///    1.0,
+///    Default::default(),
+///    Bounds::none(2),
+///    1000);
+///
+/// assert_approx_eq!(line.slope, 2.0);
+/// assert_approx_eq!(line.intercept, 1.0);
+/// assert_approx_eq!(error, 0.0);
+/// ```
+pub fn minimize_typed<T: Vectorizable>(
+    f: impl Fn(&T) -> f64,
+    initial_point: T,
+    initial_simplex_size: f64,
+    params: Params,
+    bounds: Bounds,
+    max_iter: u32,
+) -> (T, f64) {
+    let initial_point = initial_point.to_vec();
+    debug_assert_eq!(initial_point.len(), T::dim());
+    let (point, value) = crate::minimize(
+        |x| f(&T::from_vec(x)),
+        initial_point,
+        initial_simplex_size,
+        params,
+        bounds,
+        max_iter,
+    );
+    (T::from_vec(&point), value)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate assert_approx_eq;
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[derive(Debug, PartialEq)]
+    struct Gaussian {
+        mean: f64,
+        std_dev: f64,
+    }
+
+    impl Vectorizable for Gaussian {
+        fn dim() -> usize {
+            2
+        }
+
+        fn to_vec(&self) -> Vec<f64> {
+            vec![self.mean, self.std_dev]
+        }
+
+        fn from_vec(coordinates: &[f64]) -> Gaussian {
+            Gaussian {
+                mean: coordinates[0],
+                std_dev: coordinates[1],
+            }
+        }
+    }
+
+    #[test]
+    fn recover_struct_fields() {
+        let samples = [1.0, 2.0, 3.0, 4.0];
+        // negative log-likelihood, up to a constant
+        let nll = |g: &Gaussian| {
+            samples
+                .iter()
+                .map(|x| ((x - g.mean) / g.std_dev).powi(2) / 2.0 + g.std_dev.ln())
+                .sum()
+        };
+        let (gaussian, _) = minimize_typed(
+            nll,
+            Gaussian {
+                mean: 0.0,
+                std_dev: 1.0,
+            },
+            0.5,
+            Params {
+                axis_aligned_simplex: true,
+                ..Default::default()
+            },
+            Bounds {
+                min: vec![f64::NEG_INFINITY, 1e-6],
+                max: vec![f64::INFINITY, f64::INFINITY],
+            },
+            1000,
+        );
+        assert_approx_eq!(gaussian.mean, 2.5);
+        assert_approx_eq!(gaussian.std_dev, 1.25f64.sqrt());
+    }
+
+    #[test]
+    fn round_trip_coordinates() {
+        let gaussian = Gaussian {
+            mean: -1.0,
+            std_dev: 0.5,
+        };
+        assert_eq!(gaussian.to_vec().len(), Gaussian::dim());
+        assert_eq!(Gaussian::from_vec(&gaussian.to_vec()), gaussian);
+    }
+}
@@ -0,0 +1,43 @@
+//! The geometry used by the optimizer, for stopping rules and restart
+//! policies built outside of the crate.
+
+use alloc::vec::Vec;
+use num_traits::float::FloatCore;
+
+use crate::algebra::*;
+
+pub use crate::algebra::clamp;
+
+/// Computes the centroid of every vertex of a sorted `simplex` but the
+/// worst one, through which a standard step reflects the worst vertex.
+///
+/// # Example
+///
+/// ```
+/// use nelder_mead::util::*;
+///
+/// let simplex = vec![
+///     (vec![0.0, 0.0], 1.0),
+///     (vec![2.0, 0.0], 2.0),
+///     (vec![5.0, 5.0], 3.0),
+/// ];
+/// assert_eq!(centroid(&simplex), vec![1.0, 0.0]);
+///
+/// // trial points are clamped to the bounds
+/// let bounds = [(0.0, 1.0), (f64::NEG_INFINITY, f64::INFINITY)];
+/// assert_eq!(clamp(&[1.5, -3.0], &bounds), vec![1.0, -3.0]);
+/// ```
+pub fn centroid<T: FloatCore>(simplex: &[(Vec<T>, T)]) -> Vec<T> {
+    centroid_excluding(simplex, simplex.len() - 1)
+}
+
+/// Computes the centroid of every vertex of `simplex` but the one at index
+/// `excluded`.
+pub fn centroid_excluding<T: FloatCore>(simplex: &[(Vec<T>, T)], excluded: usize) -> Vec<T> {
+    avg(&simplex
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != excluded)
+        .map(|(_, x)| x.0.clone())
+        .collect::<Vec<Vec<T>>>())
+}